@@ -0,0 +1,156 @@
+//! Compares the arena tree with the node cache, on full searches of tic-tac-toe and on
+//! montecarlo tree search. Run with 'cargo bench --bench arena'.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+use std::time::Instant;
+
+use gts::abstractions::Environment;
+use gts::cache::arena::Arena;
+use gts::cache::arena::ArenaMM;
+use gts::cache::node::Node;
+use gts::tree_search::mcts;
+use gts::tree_search::uct;
+use gts::tree_search::ArenaNode;
+use gts::tree_search::CachedNode;
+use gts::tree_search::MctsCache;
+use gts::tree_search::Negamax;
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct TicTacToe {
+    board: [u8; 9],
+    turn: u8,
+}
+
+impl Environment<u8, u8> for TicTacToe {
+    fn initial_state() -> Self {
+        TicTacToe {
+            board: [0; 9],
+            turn: 1,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.board[*a as usize] = self.turn;
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        if self.is_terminal() {
+            return Box::new(std::iter::empty());
+        }
+        let board = self.board;
+        Box::new((0..9).filter(move |a| board[*a as usize] == 0))
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a < 9 && self.board[*a as usize] == 0 && !self.is_terminal()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.winner().is_some() || self.board.iter().all(|cell| *cell != 0)
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        LINES
+            .iter()
+            .map(|line| line.map(|cell| self.board[cell]))
+            .find(|[a, b, c]| *a != 0 && a == b && b == c)
+            .map(|[a, _, _]| a)
+    }
+}
+
+fn reward(env: &TicTacToe, agent_id: &u8) -> f64 {
+    match env.winner() {
+        Some(winner) if winner == *agent_id => 1f64,
+        Some(_) => -1f64,
+        None => 0f64,
+    }
+}
+
+/// Runs 'f' 'rounds' times, and prints the average time and the size it returns.
+fn bench(name: &str, rounds: u32, mut f: impl FnMut() -> usize) {
+    let mut total = Duration::default();
+    let mut size = 0;
+
+    for _ in 0..rounds {
+        let start = Instant::now();
+        size = f();
+        total += start.elapsed();
+    }
+
+    println!(
+        "{:<28} {:>12?} {:>10} positions",
+        name,
+        total / rounds,
+        size
+    );
+}
+
+fn main() {
+    let env = TicTacToe::initial_state();
+    let (alpha, beta) = (f64::NEG_INFINITY, f64::INFINITY);
+
+    bench("alphabeta, node cache", 20, || {
+        let node: Node<TicTacToe, u8, u8, _> = Node::new(&Rc::new(env));
+        let root = node.cache_get(&env).expect("Nodes are in their own cache");
+        Negamax::new(&1, &reward).search(&mut CachedNode::new(&root), 10, alpha, beta);
+        let size = root.borrow().cache_len();
+        size
+    });
+
+    bench("alphabeta, arena", 20, || {
+        let arena: RefCell<ArenaMM<TicTacToe, u8>> = RefCell::new(Arena::new());
+        let root = arena.borrow_mut().set_root(env);
+        Negamax::new(&1, &reward).search(&mut ArenaNode::new(&arena, root), 10, alpha, beta);
+        let size = arena.borrow().len();
+        size
+    });
+
+    let selection_fn = |env: &TicTacToe, agent_id: &u8, cache: &dyn MctsCache<TicTacToe>| {
+        uct(env, agent_id, cache, 1.4)
+    };
+
+    bench("mcts, hash map", 5, || {
+        let mut cache: HashMap<TicTacToe, (f64, u32)> = HashMap::new();
+        for _ in 0..20000 {
+            mcts(&env, &1, &selection_fn, &mut cache);
+        }
+        cache.len()
+    });
+
+    bench("mcts, arena", 5, || {
+        let mut cache: Arena<TicTacToe, u8, (f64, u32)> = Arena::new();
+        for _ in 0..20000 {
+            mcts(&env, &1, &selection_fn, &mut cache);
+        }
+        cache.len()
+    });
+}
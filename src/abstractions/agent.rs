@@ -10,6 +10,33 @@ where
     /// Returns the identity of the agent in the environment T.
     fn identity(&self) -> AgentId;
 
-    /// Returns the agent's action given an environment.
+    /// Returns the agent's action given an environment. Returning None on its turn stops the
+    /// game, which is then unfinished.
     fn action(&mut self, env: &T) -> Option<Action>;
+
+    /// Returns the value of the last action for the agent, and the depth it was searched to.
+    /// Agents that don't search return None.
+    fn last_evaluation(&self) -> Option<(f64, usize)> {
+        None
+    }
+}
+
+/// Boxed agents are agents too, so that agents of different types can be used in the same
+/// place, like the entrants of a tournament.
+impl<Action, AgentId, T, A> Agent<Action, AgentId, T> for Box<A>
+where
+    T: Environment<Action, AgentId>,
+    A: Agent<Action, AgentId, T> + ?Sized,
+{
+    fn identity(&self) -> AgentId {
+        (**self).identity()
+    }
+
+    fn action(&mut self, env: &T) -> Option<Action> {
+        (**self).action(env)
+    }
+
+    fn last_evaluation(&self) -> Option<(f64, usize)> {
+        (**self).last_evaluation()
+    }
 }
@@ -4,5 +4,28 @@ pub use self::agent::Agent;
 mod environment;
 pub use self::environment::Environment;
 
+mod noisy_actions;
+pub use self::noisy_actions::NoisyActions;
+
+mod notation;
+pub use self::notation::Notation;
+
+mod null_move;
+pub use self::null_move::NullMove;
+
 mod play;
 pub use self::play::play;
+pub(crate) use self::play::play_until;
+pub use self::play::play_with_repetitions;
+
+mod predecessors;
+pub use self::predecessors::Predecessors;
+
+mod symmetric;
+pub use self::symmetric::Symmetric;
+
+mod undo;
+pub use self::undo::Undo;
+
+mod zobrist;
+pub use self::zobrist::Zobrist;
@@ -0,0 +1,9 @@
+use super::environment::Environment;
+
+/// Extension for environments where some actions change the evaluation abruptly, like
+/// captures, checks or threats. Searches use them to keep looking past their nominal depth
+/// until the position is quiet.
+pub trait NoisyActions<Action, AgentId>: Environment<Action, AgentId> {
+    /// Returns an iterator with the noisy actions among the valid actions.
+    fn noisy_actions(&self) -> Box<dyn Iterator<Item = Action>>;
+}
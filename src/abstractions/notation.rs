@@ -0,0 +1,12 @@
+use super::environment::Environment;
+
+/// Extension for environments whose positions can be written as text, like FEN in chess.
+/// Game records use it to store games that don't start in 'initial_state', and suites of
+/// openings or puzzles can be given as lists of positions.
+pub trait Notation<Action, AgentId>: Environment<Action, AgentId> + Sized {
+    /// Returns the position written in 'notation', or None if it is not a valid one.
+    fn from_notation(notation: &str) -> Option<Self>;
+
+    /// Writes the position, in a single line that 'from_notation' reads back.
+    fn to_notation(&self) -> String;
+}
@@ -0,0 +1,15 @@
+use super::environment::Environment;
+
+/// Extension for environments where an agent can pass its turn. Searches use it for null-move
+/// pruning: if passing is already good enough for the agent moving, the position is not
+/// searched any further.
+pub trait NullMove<Action, AgentId>: Environment<Action, AgentId> {
+    /// Returns the environment after the current agent passes its turn.
+    fn null_move(&self) -> Self;
+
+    /// Returns true iff passing is a safe estimate of the value of the environment. It should
+    /// be false in zugzwang-prone positions, where every action is worse than passing.
+    fn null_move_allowed(&self) -> bool {
+        true
+    }
+}
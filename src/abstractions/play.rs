@@ -1,3 +1,9 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+
+use crate::record::Move;
+
 use super::agent::Agent;
 use super::environment::Environment;
 
@@ -5,12 +11,70 @@ use super::environment::Environment;
 // Note that players can have different types as far as they
 // implement the Agent trait.
 
-/// Plays a game in Envirnment 'env', and two agents.
+/// Plays a game in Envirnment 'env', and two agents. The game ends at a terminal position, or
+/// as soon as the agent to move returns no action, like a human closing the input. Such games
+/// are left unfinished.
 pub fn play<Action, AgentId, T, R, S>(
     env: &mut T,
     agent_1: &mut R,
     agent_2: &mut S,
 ) -> Vec<(AgentId, Action)>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    without_times(play_until(env, agent_1, agent_2, |_, _| false))
+}
+
+/// Plays a game like 'play', with a repetition rule: the game ends in a draw as soon as a
+/// position appears for the 'repetitions'-th time. Returns the log of the game, and whether
+/// it ended by repetition.
+///
+/// # Panics
+///
+/// If 'repetitions' is less than 2, as the initial position would end the game.
+pub fn play_with_repetitions<Action, AgentId, T, R, S>(
+    env: &mut T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+    repetitions: usize,
+) -> (Vec<(AgentId, Action)>, bool)
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    assert!(
+        repetitions >= 2,
+        "A position must appear at least twice to repeat"
+    );
+
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut repeated = false;
+
+    let game_log = play_until(env, agent_1, agent_2, |env, _| {
+        let count = seen.entry(env.clone()).or_insert(0);
+        *count += 1;
+        repeated = *count >= repetitions && !env.is_terminal();
+        repeated
+    });
+
+    (without_times(game_log), repeated)
+}
+
+/// Plays a game until it ends, until the agent to move has no action, or until 'stop' returns
+/// true. 'stop' is called on the initial environment, and after every action with the
+/// environment and the action played. The log has the time every agent took to produce its
+/// action, and the evaluation it reported.
+pub(crate) fn play_until<Action, AgentId, T, R, S>(
+    env: &mut T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+    mut stop: impl FnMut(&T, Option<&Move<AgentId, Action>>) -> bool,
+) -> Vec<Move<AgentId, Action>>
 where
     AgentId: Eq,
     T: Environment<Action, AgentId>,
@@ -19,39 +83,52 @@ where
 {
     let mut game_log = Vec::new();
 
+    if stop(env, None) {
+        return game_log;
+    }
+
     while !env.is_terminal() {
         let identity = agent_1.identity();
         if identity == env.turn() {
+            let start = Instant::now();
             let action = agent_1.action(env);
-            match action {
-                Some(a) => {
-                    env.update(&a);
-                    game_log.push((identity, a));
-
-                    if env.is_terminal() {
-                        break;
-                    }
+            if let Some(a) = action {
+                let time = start.elapsed();
+                env.update(&a);
+                game_log.push(Move::new(identity, a, time, agent_1.last_evaluation()));
+
+                if stop(env, game_log.last()) || env.is_terminal() {
+                    break;
                 }
-                None => {}
+            } else {
+                break;
             }
         }
 
         let identity = agent_2.identity();
         if identity == env.turn() {
+            let start = Instant::now();
             let action = agent_2.action(env);
-            match action {
-                Some(a) => {
-                    env.update(&a);
-                    game_log.push((identity, a));
-
-                    if env.is_terminal() {
-                        break;
-                    }
+            if let Some(a) = action {
+                let time = start.elapsed();
+                env.update(&a);
+                game_log.push(Move::new(identity, a, time, agent_2.last_evaluation()));
+
+                if stop(env, game_log.last()) || env.is_terminal() {
+                    break;
                 }
-                None => {}
+            } else {
+                break;
             }
         }
     }
 
-    return game_log;
+    game_log
+}
+
+fn without_times<Action, AgentId>(game_log: Vec<Move<AgentId, Action>>) -> Vec<(AgentId, Action)> {
+    game_log
+        .into_iter()
+        .map(|m| (m.agent_id, m.action))
+        .collect()
 }
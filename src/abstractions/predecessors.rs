@@ -0,0 +1,11 @@
+use super::environment::Environment;
+
+/// Extension for environments that can be played backwards. Retrograde analysis uses it to
+/// go from solved positions to the positions leading to them.
+pub trait Predecessors<Action, AgentId>: Environment<Action, AgentId> {
+    /// Returns every environment with an action leading to this one. Environments listed more
+    /// than once count once.
+    fn predecessors(&self) -> Vec<Self>
+    where
+        Self: Sized;
+}
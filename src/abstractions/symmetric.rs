@@ -0,0 +1,35 @@
+use super::environment::Environment;
+
+/// Extension for environments with symmetries, like rotating or mirroring a board, that keep
+/// the value of every position. Caches use it to store a single canonical position for all
+/// the positions related by a symmetry.
+///
+/// Symmetries are numbered from 0 to 'symmetries() - 1', 0 being the identity.
+pub trait Symmetric<Action, AgentId>: Environment<Action, AgentId> {
+    /// Returns the number of symmetries of the game.
+    fn symmetries(&self) -> usize;
+
+    /// Returns the environment transformed by 'symmetry'.
+    fn transform(&self, symmetry: usize) -> Self
+    where
+        Self: Sized;
+
+    /// Returns the action transformed by 'symmetry'. Playing it in the transformed environment
+    /// leads to the transform of playing 'action' in this one.
+    fn transform_action(&self, action: &Action, symmetry: usize) -> Action;
+
+    /// Returns the symmetry undoing 'symmetry'.
+    fn inverse(&self, symmetry: usize) -> usize;
+
+    /// Returns the smallest of the transforms of this environment, together with the symmetry
+    /// producing it.
+    fn canonical(&self) -> (Self, usize)
+    where
+        Self: Sized + Ord,
+    {
+        (0..self.symmetries())
+            .map(|symmetry| (self.transform(symmetry), symmetry))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .expect("There is at least the identity")
+    }
+}
@@ -0,0 +1,26 @@
+use super::environment::Environment;
+
+/// Extension for environments that can take actions back. Searches use it to walk the game
+/// tree on a single environment, instead of cloning it with 'what_if' for every action.
+pub trait Undo<Action, AgentId>: Environment<Action, AgentId> {
+    /// Information needed to take an action back.
+    type Token;
+
+    /// Performs 'a' in place. Returns the token taking it back, or None if 'a' is not valid,
+    /// in which case the environment is left untouched.
+    fn make(&mut self, a: &Action) -> Option<Self::Token>;
+
+    /// Takes back the last action made, given the token returned when making it.
+    fn unmake(&mut self, token: Self::Token);
+
+    /// Makes 'a', calls 'f' with the result, and unmakes it.
+    fn play(&mut self, a: &Action, f: &mut dyn FnMut(&mut Self))
+    where
+        Self: Sized,
+    {
+        if let Some(token) = self.make(a) {
+            f(self);
+            self.unmake(token);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use super::environment::Environment;
+
+/// Extension for environments keeping a 64-bit key of their state, usually updated
+/// incrementally with a 'ZobristTable' as actions are performed. Caches use it instead of
+/// hashing, and possibly storing, the whole environment.
+///
+/// Equal environments must have equal keys. Different environments should have different
+/// keys, but collisions are possible, so caches may verify the full state.
+pub trait Zobrist<Action, AgentId>: Environment<Action, AgentId> {
+    /// Returns the key of the current state.
+    fn zobrist(&self) -> u64;
+}
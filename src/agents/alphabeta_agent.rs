@@ -1,14 +1,40 @@
 use std::cell::RefCell;
-use std::fmt::Debug;
 use std::hash::Hash;
-use std::rc::Rc;
+#[cfg(feature = "serde")]
+use std::io;
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde::Serialize;
 
 use crate::abstractions::Agent;
 use crate::abstractions::Environment;
+use crate::abstractions::NoisyActions;
+use crate::abstractions::NullMove;
+use crate::abstractions::Symmetric;
+
+use super::AlphabetaConfig;
 
-use crate::cache::node::Node;
-use crate::cache::node::NodeRRMM;
-use crate::tree_search::alphabeta;
+use crate::cache::arena::Arena;
+use crate::cache::arena::ArenaMM;
+use crate::cache::arena::NodeId;
+use crate::cache::canonical::Canonicalizer;
+use crate::tablebase::ProbeFn;
+use crate::tree_search::ActionsFn;
+use crate::tree_search::ArenaNode;
+use crate::tree_search::History;
+use crate::tree_search::Killers;
+use crate::tree_search::LateMoveReductions;
+use crate::tree_search::Negamax;
+use crate::tree_search::NullMovePruning;
+use crate::tree_search::OrderingFn;
+use crate::tree_search::OrderingTable;
+use crate::tree_search::OrderingTableFn;
+use crate::tree_search::Repetitions;
+use crate::tree_search::SearchStats;
 
 /// A minmax agent plays based on a reward function and exploration of the game tree up to a given depth.
 /// This agent caches previously seen environments.
@@ -19,7 +45,24 @@ where
     agent_id: AgentId,
     reward: &'a dyn Fn(&T, &AgentId) -> f64,
     depth: usize,
-    root: Option<NodeRRMM<T, Action, AgentId>>,
+    pvs: bool,
+    aspiration: Option<f64>,
+    mtdf: bool,
+    first_guess: Option<f64>,
+    last_value: f64,
+    quiescence: Option<(ActionsFn<Action, T>, usize)>,
+    ordering: Option<&'a OrderingFn<'a, Action, T>>,
+    killers: Option<OrderingTableFn<'a, Action>>,
+    history: Option<OrderingTableFn<'a, Action>>,
+    null_move: Option<NullMovePruning<T>>,
+    lmr: Option<LateMoveReductions>,
+    probe: Option<&'a ProbeFn<'a, T>>,
+    canonical: Option<Canonicalizer<Action, T>>,
+    repetitions: bool,
+    played: Vec<T>,
+    stats: SearchStats,
+    arena: RefCell<ArenaMM<T, Action>>,
+    retained: usize,
 }
 
 /// Methods for MinmaxAgent
@@ -32,35 +75,231 @@ where
             agent_id,
             reward,
             depth: depth + 1, // Avoiding depth 0. With depth 0, minmax does nothing.
-            root: None,
+            pvs: false,
+            aspiration: None,
+            mtdf: false,
+            first_guess: None,
+            last_value: 0f64,
+            quiescence: None,
+            ordering: None,
+            killers: None,
+            history: None,
+            null_move: None,
+            lmr: None,
+            probe: None,
+            canonical: None,
+            repetitions: false,
+            played: Vec::new(),
+            stats: SearchStats::default(),
+            arena: RefCell::new(Arena::new()),
+            retained: 0,
+        }
+    }
+
+    /// Creates an agent from 'config'. Quiescence search, null-move pruning and symmetries
+    /// need extensions of the environment, so they are not enabled here: pass the matching
+    /// fields of 'config' to 'with_quiescence', 'with_null_move' and 'with_symmetries'.
+    pub fn from_config(
+        agent_id: AgentId,
+        reward: &'a dyn Fn(&T, &AgentId) -> f64,
+        config: &AlphabetaConfig,
+    ) -> Self
+    where
+        Action: Clone + Hash + Eq + 'a,
+    {
+        let mut agent = AlphabetaAgent::new(agent_id, reward, config.depth);
+        agent.pvs = config.pvs;
+        agent.aspiration = config.aspiration;
+        agent.mtdf = config.mtdf;
+        agent.first_guess = config.first_guess;
+        agent.lmr = config.lmr;
+        agent.repetitions = config.repetitions;
+        if config.killers {
+            agent = agent.with_killers();
+        }
+        if config.history {
+            agent = agent.with_history();
+        }
+        agent
+    }
+
+    /// Returns the configuration of the agent.
+    pub fn config(&self) -> AlphabetaConfig {
+        AlphabetaConfig {
+            depth: self.depth - 1,
+            pvs: self.pvs,
+            aspiration: self.aspiration,
+            mtdf: self.mtdf,
+            first_guess: self.first_guess,
+            killers: self.killers.is_some(),
+            history: self.history.is_some(),
+            lmr: self.lmr,
+            repetitions: self.repetitions,
+            quiescence: self.quiescence.map(|(_, max_depth)| max_depth),
+            null_move: self.null_move.map(|null_move| null_move.reduction),
+            symmetries: self.canonical.is_some(),
         }
     }
 
-    fn update_root(&mut self, env: &T) -> NodeRRMM<T, Action, AgentId> {
-        let env_rc = Rc::new(env.clone());
-        let current_root = self
-            .root
-            .clone()
-            .unwrap_or(Rc::new(RefCell::new(Node::new(&env_rc))));
-        let new_root = current_root
-            .borrow()
-            .cache_get(&env_rc)
-            .unwrap_or(Rc::new(RefCell::new(Node::new(&env_rc))));
+    /// Enables Principal Variation Search.
+    pub fn with_pvs(mut self) -> Self {
+        self.pvs = true;
+        self
+    }
+
+    /// Searches with iterative deepening, using an aspiration window of size 'window' around
+    /// the value found by the previous iteration.
+    pub fn with_aspiration(mut self, window: f64) -> Self {
+        self.aspiration = Some(window);
+        self
+    }
+
+    /// Searches with MTD(f) instead of a single alpha-beta search. Unless a first guess is
+    /// given with 'with_first_guess', MTD(f) starts from the value of the previous action.
+    pub fn with_mtdf(mut self) -> Self {
+        self.mtdf = true;
+        self
+    }
 
-        self.root = Some(new_root.clone());
-        
-        new_root.clone().borrow().rebase_cache();
+    /// Sets the value MTD(f) starts from in every action.
+    pub fn with_first_guess(mut self, first_guess: f64) -> Self {
+        self.first_guess = Some(first_guess);
+        self
+    }
+
+    /// Enables a quiescence search of up to 'max_depth' plies at the end of the search.
+    pub fn with_quiescence(mut self, max_depth: usize) -> Self
+    where
+        T: NoisyActions<Action, AgentId>,
+    {
+        self.quiescence = Some((T::noisy_actions, max_depth));
+        self
+    }
+
+    /// Tries the actions with a larger 'ordering' score first.
+    pub fn with_ordering(mut self, ordering: &'a OrderingFn<'a, Action, T>) -> Self {
+        self.ordering = Some(ordering);
+        self
+    }
+
+    /// Tries first the killer moves: actions that caused a cutoff at the same ply.
+    pub fn with_killers(mut self) -> Self
+    where
+        Action: Clone + PartialEq + 'a,
+    {
+        self.killers = Some(|| Box::new(Killers::new()) as Box<dyn OrderingTable<Action>>);
+        self
+    }
+
+    /// Tries first the actions that caused more cutoffs anywhere in the tree.
+    pub fn with_history(mut self) -> Self
+    where
+        Action: Clone + Hash + Eq + 'a,
+    {
+        self.history = Some(|| Box::new(History::new()) as Box<dyn OrderingTable<Action>>);
+        self
+    }
+
+    /// Enables null-move pruning, searching the positions after passing 'reduction' plies
+    /// shallower.
+    pub fn with_null_move(mut self, reduction: usize) -> Self
+    where
+        T: NullMove<Action, AgentId>,
+    {
+        self.null_move = Some(NullMovePruning::new(reduction));
+        self
+    }
+
+    /// Enables late move reductions. See 'LateMoveReductions'.
+    pub fn with_lmr(mut self, lmr: LateMoveReductions) -> Self {
+        self.lmr = Some(lmr);
+        self
+    }
 
-        new_root
+    /// Scores the positions found by 'probe', usually a tablebase, without searching them.
+    pub fn with_probe(mut self, probe: &'a ProbeFn<'a, T>) -> Self {
+        self.probe = Some(probe);
+        self
     }
 
+    /// Caches a single canonical position for all the positions related by a symmetry.
+    pub fn with_symmetries(mut self) -> Self
+    where
+        T: Symmetric<Action, AgentId> + Ord,
+    {
+        let canonical = Canonicalizer::new();
+        let arena = self.arena.into_inner();
+        self.arena = RefCell::new(arena.with_canonical(Some(canonical.environment_fn())));
+        self.canonical = Some(canonical);
+        self
+    }
+
+    /// Scores positions repeating one on the search path, or one this agent had to move in
+    /// before, as draws. The positions played are remembered until 'new_game' is called.
+    pub fn with_repetitions(mut self) -> Self {
+        self.repetitions = true;
+        self
+    }
+
+    /// Forgets the positions this agent moved in, before playing another game. The positions
+    /// searched are kept.
+    pub fn new_game(&mut self) {
+        self.played.clear();
+    }
+
+    /// Writes the positions searched so far to a file, to resume the search later with
+    /// 'load_cache'.
+    #[cfg(feature = "serde")]
+    pub fn save_cache<P: AsRef<Path>>(&self, path: P) -> io::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        Action: Serialize + DeserializeOwned,
+    {
+        self.arena.borrow().save(path)
+    }
+
+    /// Replaces the positions searched so far with the ones saved by 'save_cache'. The file
+    /// must come from an agent with the same symmetries setting.
+    #[cfg(feature = "serde")]
+    pub fn load_cache<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        Action: Serialize + DeserializeOwned,
+    {
+        let canonical = self.canonical.as_ref().map(|c| c.environment_fn());
+        let arena = Arena::load(path)?.with_canonical(canonical);
+
+        self.retained = arena.len();
+        self.arena = RefCell::new(arena);
+
+        Ok(())
+    }
+
+    /// Returns the counters collected while producing the last action.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    fn update_root(&mut self, env: &T) -> NodeId {
+        let arena = self.arena.get_mut();
+        arena.set_root(env.clone());
+
+        // Positions left behind are dropped once the arena doubles, so that moving the root
+        // costs O(1) amortized.
+        if arena.len() > 2 * self.retained {
+            arena.retain_reachable();
+            self.retained = arena.len();
+        }
+
+        arena.root().expect("The root was just set")
+    }
 }
 
 /// Implements an agent that runs the minmax tree search arlgorithm to produce moves.
 impl<'a, Action, AgentId, T> Agent<Action, AgentId, T> for AlphabetaAgent<'a, Action, AgentId, T>
 where
-    AgentId: Eq + Copy + Debug,
-    Action: Copy + Debug,
+    AgentId: Eq + Copy,
+    Action: Copy,
     T: Environment<Action, AgentId> + Eq + Hash + Clone,
 {
     /// Returns the agent identity in the game.
@@ -72,25 +311,52 @@ where
     fn action(&mut self, env: &T) -> Option<Action> {
         let new_root = self.update_root(env);
 
-        alphabeta(
-            &new_root,
-            &self.agent_id,
-            self.reward,
-            self.depth,
-            f64::NEG_INFINITY,
-            f64::INFINITY,
-        );
-
-        println!(
-            "Agent {:?} Action {:?}, Value {:?}, CacheSize {:?}",
-            self.agent_id,
-            new_root.borrow().data.action,
-            new_root.borrow().data.value,
-            new_root.borrow().cache_len()
-        );
-
-        let output = new_root.borrow().data.action.clone();
-
-        output
+        // The search sees the positions stored in the arena, which may be canonical.
+        let position = self.arena.borrow().environment(new_root).clone();
+        let mut search = Negamax::new(&self.agent_id, self.reward)
+            .with_pvs(self.pvs)
+            .with_noisy_actions(self.quiescence)
+            .with_ordering(self.ordering)
+            .with_killers(self.killers.map(|killers| killers()))
+            .with_history(self.history.map(|history| history()))
+            .with_null_move(self.null_move)
+            .with_lmr(self.lmr)
+            .with_probe(self.probe)
+            .with_repetitions(if self.repetitions {
+                Some(Repetitions::new().with_previous(&self.played))
+            } else {
+                None
+            });
+        let mut node = ArenaNode::new(&self.arena, new_root);
+
+        let (value, action) = if self.mtdf {
+            let first_guess = self.first_guess.unwrap_or(self.last_value);
+            search.mtdf(&mut node, self.depth, first_guess)
+        } else if let Some(window) = self.aspiration {
+            search.iterative_deepening(&mut node, self.depth, window)
+        } else {
+            search.search(&mut node, self.depth, f64::NEG_INFINITY, f64::INFINITY)
+        };
+
+        self.stats = *search.stats();
+        self.last_value = value;
+
+        if self.repetitions {
+            self.played.push(position);
+        }
+
+        // Actions found in a canonical position are mapped back to the environment played.
+        match &self.canonical {
+            Some(canonical) => action.map(|action| {
+                let (position, symmetry) = canonical.canonical(env);
+                canonical.action_back(&position, &action, symmetry)
+            }),
+            None => action,
+        }
+    }
+
+    /// Returns the value found for the last action, and the depth searched.
+    fn last_evaluation(&self) -> Option<(f64, usize)> {
+        Some((self.last_value, self.depth - 1))
     }
 }
@@ -0,0 +1,330 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::tree_search::LateMoveReductions;
+
+/// Configuration of a 'MinmaxAgent'. See 'MinmaxAgent::from_config'.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MinmaxConfig {
+    pub depth: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub killers: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub history: bool,
+    /// Maximum depth of the quiescence search, for environments with 'NoisyActions'.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quiescence: Option<usize>,
+}
+
+/// Configuration of an 'AlphabetaAgent'. See 'AlphabetaAgent::from_config'.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlphabetaConfig {
+    pub depth: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pvs: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub aspiration: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mtdf: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub first_guess: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub killers: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub history: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub lmr: Option<LateMoveReductions>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub repetitions: bool,
+    /// Maximum depth of the quiescence search, for environments with 'NoisyActions'.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quiescence: Option<usize>,
+    /// Depth reduction of null-move pruning, for environments with 'NullMove'.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub null_move: Option<usize>,
+    /// Whether to cache canonical positions, for environments with 'Symmetric'.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub symmetries: bool,
+}
+
+/// Configuration of an 'MctsUctAgent'. See 'MctsUctAgent::from_config'.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MctsUctConfig {
+    pub exploration: f64,
+    pub mc_runs: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub keep_cache: bool,
+}
+
+/// Configuration of any of the agents. Agents are built from it by the caller, who provides
+/// what can't be configured in a file, like reward functions or tablebases.
+///
+/// Configurations are also written as short specs: the name of the agent followed by its
+/// options, like 'alphabeta:depth=6,pvs' or 'mcts:runs=10000,c=1.4'. Flags may omit their
+/// value, and 'lmr' takes 'after/min_depth/reduction'.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "agent", rename_all = "snake_case"))]
+pub enum AgentConfig {
+    Human,
+    Minmax(MinmaxConfig),
+    Alphabeta(AlphabetaConfig),
+    #[cfg_attr(feature = "serde", serde(rename = "mcts"))]
+    MctsUct(MctsUctConfig),
+    Tablebase,
+}
+
+/// Error parsing an agent spec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseConfigError(String);
+
+impl fmt::Display for ParseConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid agent spec: {}", self.0)
+    }
+}
+
+impl Error for ParseConfigError {}
+
+impl MinmaxConfig {
+    pub fn new(depth: usize) -> Self {
+        MinmaxConfig {
+            depth,
+            killers: false,
+            history: false,
+            quiescence: None,
+        }
+    }
+}
+
+impl AlphabetaConfig {
+    pub fn new(depth: usize) -> Self {
+        AlphabetaConfig {
+            depth,
+            pvs: false,
+            aspiration: None,
+            mtdf: false,
+            first_guess: None,
+            killers: false,
+            history: false,
+            lmr: None,
+            repetitions: false,
+            quiescence: None,
+            null_move: None,
+            symmetries: false,
+        }
+    }
+}
+
+impl MctsUctConfig {
+    pub fn new(exploration: f64, mc_runs: u16) -> Self {
+        MctsUctConfig {
+            exploration,
+            mc_runs,
+            keep_cache: false,
+        }
+    }
+}
+
+impl fmt::Display for AgentConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, options) = match self {
+            AgentConfig::Human => return write!(f, "human"),
+            AgentConfig::Tablebase => return write!(f, "tablebase"),
+            AgentConfig::Minmax(config) => {
+                let mut options = vec![format!("depth={}", config.depth)];
+                push_flag(&mut options, "killers", config.killers);
+                push_flag(&mut options, "history", config.history);
+                push_value(&mut options, "quiescence", config.quiescence);
+                ("minmax", options)
+            }
+            AgentConfig::Alphabeta(config) => {
+                let mut options = vec![format!("depth={}", config.depth)];
+                push_flag(&mut options, "pvs", config.pvs);
+                push_value(&mut options, "aspiration", config.aspiration);
+                push_flag(&mut options, "mtdf", config.mtdf);
+                push_value(&mut options, "first_guess", config.first_guess);
+                push_flag(&mut options, "killers", config.killers);
+                push_flag(&mut options, "history", config.history);
+                push_value(
+                    &mut options,
+                    "lmr",
+                    config
+                        .lmr
+                        .map(|lmr| format!("{}/{}/{}", lmr.after, lmr.min_depth, lmr.reduction)),
+                );
+                push_flag(&mut options, "repetitions", config.repetitions);
+                push_value(&mut options, "quiescence", config.quiescence);
+                push_value(&mut options, "null_move", config.null_move);
+                push_flag(&mut options, "symmetries", config.symmetries);
+                ("alphabeta", options)
+            }
+            AgentConfig::MctsUct(config) => {
+                let mut options = vec![
+                    format!("runs={}", config.mc_runs),
+                    format!("c={}", config.exploration),
+                ];
+                push_flag(&mut options, "keep", config.keep_cache);
+                ("mcts", options)
+            }
+        };
+
+        write!(f, "{}:{}", name, options.join(","))
+    }
+}
+
+impl FromStr for AgentConfig {
+    type Err = ParseConfigError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (name, options) = spec.split_once(':').unwrap_or((spec, ""));
+        let mut options = Options::parse(options)?;
+
+        let config = match name.trim() {
+            "human" => AgentConfig::Human,
+            "tablebase" => AgentConfig::Tablebase,
+            "minmax" => AgentConfig::Minmax(MinmaxConfig {
+                depth: options.required(&["depth"])?,
+                killers: options.flag("killers")?,
+                history: options.flag("history")?,
+                quiescence: options.value(&["quiescence"])?,
+            }),
+            "alphabeta" => AgentConfig::Alphabeta(AlphabetaConfig {
+                depth: options.required(&["depth"])?,
+                pvs: options.flag("pvs")?,
+                aspiration: options.value(&["aspiration"])?,
+                mtdf: options.flag("mtdf")?,
+                first_guess: options.value(&["first_guess"])?,
+                killers: options.flag("killers")?,
+                history: options.flag("history")?,
+                lmr: options.value_with(&["lmr"], parse_lmr)?,
+                repetitions: options.flag("repetitions")?,
+                quiescence: options.value(&["quiescence"])?,
+                null_move: options.value(&["null_move"])?,
+                symmetries: options.flag("symmetries")?,
+            }),
+            "mcts" => AgentConfig::MctsUct(MctsUctConfig {
+                exploration: options.required(&["c", "exploration"])?,
+                mc_runs: options.required(&["runs", "mc_runs"])?,
+                keep_cache: options.flag("keep")?,
+            }),
+            other => return Err(ParseConfigError(format!("unknown agent '{}'", other))),
+        };
+
+        options.finish()?;
+        Ok(config)
+    }
+}
+
+fn push_flag(options: &mut Vec<String>, key: &str, flag: bool) {
+    if flag {
+        options.push(key.to_string());
+    }
+}
+
+fn push_value<V: fmt::Display>(options: &mut Vec<String>, key: &str, value: Option<V>) {
+    if let Some(value) = value {
+        options.push(format!("{}={}", key, value));
+    }
+}
+
+fn parse_lmr(value: &str) -> Option<LateMoveReductions> {
+    let mut parts = value.split('/').map(|part| part.trim().parse().ok());
+    let lmr = LateMoveReductions {
+        after: parts.next()??,
+        min_depth: parts.next()??,
+        reduction: parts.next()??,
+    };
+
+    match parts.next() {
+        Some(_) => None,
+        None => Some(lmr),
+    }
+}
+
+/// Options of an agent spec not used yet, as keys with an optional value.
+struct Options<'s>(Vec<(&'s str, Option<&'s str>)>);
+
+impl<'s> Options<'s> {
+    fn parse(options: &'s str) -> Result<Self, ParseConfigError> {
+        let mut parsed = Vec::new();
+
+        for option in options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            let (key, value) = match option.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (option, None),
+            };
+            if parsed.iter().any(|(k, _)| *k == key) {
+                return Err(ParseConfigError(format!("repeated option '{}'", key)));
+            }
+            parsed.push((key, value));
+        }
+
+        Ok(Options(parsed))
+    }
+
+    /// Removes the option with any of the names in 'keys', and parses its value. Giving the
+    /// option under two of its names repeats it.
+    fn value_with<V>(
+        &mut self,
+        keys: &[&str],
+        parse: impl Fn(&str) -> Option<V>,
+    ) -> Result<Option<V>, ParseConfigError> {
+        let index = match self.0.iter().position(|(key, _)| keys.contains(key)) {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        if self.0[index + 1..]
+            .iter()
+            .any(|(key, _)| keys.contains(key))
+        {
+            return Err(ParseConfigError(format!("repeated option '{}'", keys[0])));
+        }
+
+        match self.0.remove(index) {
+            (key, Some(value)) => parse(value)
+                .map(Some)
+                .ok_or_else(|| ParseConfigError(format!("invalid value for '{}'", key))),
+            (key, None) => Err(ParseConfigError(format!("missing value for '{}'", key))),
+        }
+    }
+
+    fn value<V: FromStr>(&mut self, keys: &[&str]) -> Result<Option<V>, ParseConfigError> {
+        self.value_with(keys, |value| value.parse().ok())
+    }
+
+    fn required<V: FromStr>(&mut self, keys: &[&str]) -> Result<V, ParseConfigError> {
+        self.value(keys)?
+            .ok_or_else(|| ParseConfigError(format!("missing option '{}'", keys[0])))
+    }
+
+    /// Removes a flag, which is set when it has no value.
+    fn flag(&mut self, key: &str) -> Result<bool, ParseConfigError> {
+        match self.0.iter().position(|(k, _)| *k == key) {
+            Some(index) => match self.0.remove(index) {
+                (_, None) => Ok(true),
+                (_, Some(value)) => value
+                    .parse()
+                    .map_err(|_| ParseConfigError(format!("invalid value for '{}'", key))),
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Fails if some option was not used by the agent.
+    fn finish(self) -> Result<(), ParseConfigError> {
+        match self.0.first() {
+            Some((key, _)) => Err(ParseConfigError(format!("unknown option '{}'", key))),
+            None => Ok(()),
+        }
+    }
+}
@@ -29,24 +29,32 @@ where
         self.agent_id
     }
 
-    /// Returns the agent's action given an environment.
+    /// Returns the agent's action given an environment. Asks again until the action is a valid
+    /// one, and returns None at the end of the input, which stops the game.
     fn action(&mut self, env: &T) -> Option<Action> {
         println!("The current board looks like:");
         println!("{}", env);
         println!("You are player: {}.", self.agent_id);
 
-        println!("Please enter your action: ");
-
-        let mut buf = String::new();
-
-        match io::stdin().read_line(&mut buf) {
-            Ok(_) => println!("Input read correctly"),
-            Err(error) => println!("Error reading input {}", error),
-        }
-
-        match buf.trim().parse::<Action>() {
-            Ok(act) => Some(act),
-            Err(_) => self.action(env),
+        loop {
+            println!("Please enter your action: ");
+
+            let mut buf = String::new();
+
+            match io::stdin().read_line(&mut buf) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(error) => {
+                    println!("Error reading input {}", error);
+                    return None;
+                }
+            }
+
+            match buf.trim().parse::<Action>() {
+                Ok(act) if env.is_valid(&act) => return Some(act),
+                Ok(_) => println!("The action is not valid here"),
+                Err(_) => println!("The action could not be read"),
+            }
         }
     }
 }
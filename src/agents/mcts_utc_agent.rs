@@ -1,22 +1,37 @@
 use std::collections::HashMap;
 use std::hash::Hash;
+#[cfg(feature = "serde")]
+use std::io;
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+use super::MctsUctConfig;
 
 use crate::abstractions::Agent;
 use crate::abstractions::Environment;
 
-use crate::tree_search::mcts;
+#[cfg(feature = "serde")]
+use crate::cache::persistence;
+use crate::tablebase::ProbeFn;
+use crate::tree_search::mcts_with_probe;
 use crate::tree_search::uct;
+use crate::tree_search::MctsCache;
+#[cfg(feature = "serde")]
+use crate::tree_search::SavedCache;
 
 /// Implements a montecarlo tree search in which the next move is piced using the
 /// upper confidence bound criteria.
 ///
 /// 'exploration' controls the trade-off between exploration and exploitation.
 /// 'mc_runs' is the number of montacarlo runs in each position.
-pub struct MctsUctAgent<AgentId, T> {
+/// 'C' is the cache storing the values found, by default keyed on whole environments.
+pub struct MctsUctAgent<AgentId, T, C = HashMap<T, (f64, u32)>> {
     agent_id: AgentId,
     exploration: f64,
     mc_runs: u16,
-    cache: HashMap<T, (f64, u32)>,
+    cache: C,
+    keep_cache: bool,
+    probe: Box<ProbeFn<'static, T>>,
 }
 
 /// Methods for MctsUctAgent
@@ -32,32 +47,104 @@ where
             exploration,
             mc_runs,
             cache: HashMap::new(),
+            keep_cache: false,
+            probe: Box::new(|_| None),
+        }
+    }
+
+    /// Creates an agent from 'config'.
+    pub fn from_config(agent_id: AgentId, config: &MctsUctConfig) -> Self {
+        let mut agent = MctsUctAgent::new(agent_id, config.exploration, config.mc_runs);
+        agent.keep_cache = config.keep_cache;
+        agent
+    }
+}
+
+impl<AgentId, T, C> MctsUctAgent<AgentId, T, C>
+where
+    AgentId: Eq + Copy,
+    C: MctsCache<T>,
+{
+    /// Scores the positions found by 'probe', usually a tablebase, without exploring them.
+    pub fn with_probe(mut self, probe: Box<ProbeFn<'static, T>>) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Stores the values found in 'cache' instead, like a 'ZobristCache'.
+    pub fn with_cache<D: MctsCache<T>>(self, cache: D) -> MctsUctAgent<AgentId, T, D> {
+        MctsUctAgent {
+            agent_id: self.agent_id,
+            exploration: self.exploration,
+            mc_runs: self.mc_runs,
+            cache,
+            keep_cache: self.keep_cache,
+            probe: self.probe,
         }
     }
 
+    /// Returns the configuration of the agent.
+    pub fn config(&self) -> MctsUctConfig {
+        MctsUctConfig {
+            exploration: self.exploration,
+            mc_runs: self.mc_runs,
+            keep_cache: self.keep_cache,
+        }
+    }
+
+    /// Keeps the values found between actions, instead of starting every action from scratch.
+    pub fn with_kept_cache(mut self) -> Self {
+        self.keep_cache = true;
+        self
+    }
+
+    /// Writes the values found so far to a file, to warm-start an agent with 'load_cache'.
+    #[cfg(feature = "serde")]
+    pub fn save_cache<P: AsRef<Path>>(&self, path: P) -> io::Result<()>
+    where
+        C: SavedCache<T>,
+    {
+        persistence::save(&self.cache, C::MAGIC, path)
+    }
+
+    /// Replaces the values found so far with the ones saved by 'save_cache', and keeps them
+    /// between actions as with 'with_kept_cache'. The file must come from an agent with the
+    /// same kind of cache.
+    #[cfg(feature = "serde")]
+    pub fn load_cache<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()>
+    where
+        C: SavedCache<T>,
+    {
+        self.cache = persistence::load(C::MAGIC, path)?;
+        self.keep_cache = true;
+        Ok(())
+    }
+
     /// Updates the believe tree begining at the position given by env.
     fn learn<Action>(&mut self, env: &T)
     where
         Action: Copy,
-        T: Environment<Action, AgentId> + Clone,
+        T: Environment<Action, AgentId> + Hash,
     {
         let agent_id = self.agent_id;
         let exploration = self.exploration;
-        mcts(
+        mcts_with_probe(
             env,
             &agent_id,
             &|e, a, c| uct(e, a, c, exploration),
+            self.probe.as_ref(),
             &mut self.cache,
         );
     }
 }
 
 /// Implements an agent that runs montecarlo tree search using the ucb selection method.
-impl<Action, AgentId, T> Agent<Action, AgentId, T> for MctsUctAgent<AgentId, T>
+impl<Action, AgentId, T, C> Agent<Action, AgentId, T> for MctsUctAgent<AgentId, T, C>
 where
     AgentId: Eq + Copy,
     Action: Copy,
-    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+    T: Environment<Action, AgentId> + Hash,
+    C: MctsCache<T>,
 {
     /// Returns the agent identity in the game.
     fn identity(&self) -> AgentId {
@@ -66,13 +153,15 @@ where
 
     /// Produces an action based with mcts using the ucb selection method.
     fn action(&mut self, env: &T) -> Option<Action> {
-        self.cache.clear();
+        if !self.keep_cache {
+            self.cache.clear();
+        }
 
         for _ in 0..self.mc_runs {
             self.learn(env);
         }
 
         // For the return, we set exploration to 0. That way we pick the best seen action.
-        return uct(env, &self.agent_id, &self.cache, 0f64);
+        uct(env, &self.agent_id, &self.cache, 0f64)
     }
 }
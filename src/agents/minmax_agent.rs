@@ -1,33 +1,116 @@
-use std::fmt::Debug;
+use std::hash::Hash;
 
 use crate::abstractions::Agent;
 use crate::abstractions::Environment;
+use crate::abstractions::NoisyActions;
 
-use crate::tree_search::minmax;
+use super::MinmaxConfig;
+
+use crate::tree_search::ActionsFn;
+use crate::tree_search::History;
+use crate::tree_search::Killers;
+use crate::tree_search::Negamax;
+use crate::tree_search::OrderingFn;
+use crate::tree_search::OrderingTable;
+use crate::tree_search::OrderingTableFn;
+use crate::tree_search::StatelessNode;
 
 /// A minmax agent plays based on a reward function and exploration of the game tree up to a given depth.
-pub struct MinmaxAgent<'a, AgentId, T> {
+pub struct MinmaxAgent<'a, Action, AgentId, T> {
     agent_id: AgentId,
     reward: &'a dyn Fn(&T, &AgentId) -> f64,
     depth: usize,
+    quiescence: Option<(ActionsFn<Action, T>, usize)>,
+    ordering: Option<&'a OrderingFn<'a, Action, T>>,
+    killers: Option<OrderingTableFn<'a, Action>>,
+    history: Option<OrderingTableFn<'a, Action>>,
+    last_value: Option<f64>,
 }
 
 /// Methods for MinmaxAgent
-impl<'a, AgentId, T> MinmaxAgent<'a, AgentId, T> {
+impl<'a, Action, AgentId, T> MinmaxAgent<'a, Action, AgentId, T> {
     pub fn new(agent_id: AgentId, reward: &'a dyn Fn(&T, &AgentId) -> f64, depth: usize) -> Self {
         MinmaxAgent {
             agent_id,
             reward,
             depth: depth + 1, // Avoiding depth 0. With depth 0, minmax does nothing.
+            quiescence: None,
+            ordering: None,
+            killers: None,
+            history: None,
+            last_value: None,
+        }
+    }
+
+    /// Creates an agent from 'config'. The quiescence search needs 'NoisyActions', so it is
+    /// not enabled here: pass 'config.quiescence' to 'with_quiescence' when it is set.
+    pub fn from_config(
+        agent_id: AgentId,
+        reward: &'a dyn Fn(&T, &AgentId) -> f64,
+        config: &MinmaxConfig,
+    ) -> Self
+    where
+        Action: Clone + Hash + Eq + 'a,
+    {
+        let mut agent = MinmaxAgent::new(agent_id, reward, config.depth);
+        if config.killers {
+            agent = agent.with_killers();
+        }
+        if config.history {
+            agent = agent.with_history();
         }
+        agent
+    }
+
+    /// Returns the configuration of the agent.
+    pub fn config(&self) -> MinmaxConfig {
+        MinmaxConfig {
+            depth: self.depth - 1,
+            killers: self.killers.is_some(),
+            history: self.history.is_some(),
+            quiescence: self.quiescence.map(|(_, max_depth)| max_depth),
+        }
+    }
+
+    /// Enables a quiescence search of up to 'max_depth' plies at the end of the search.
+    pub fn with_quiescence(mut self, max_depth: usize) -> Self
+    where
+        T: NoisyActions<Action, AgentId>,
+    {
+        self.quiescence = Some((T::noisy_actions, max_depth));
+        self
+    }
+
+    /// Tries the actions with a larger 'ordering' score first.
+    pub fn with_ordering(mut self, ordering: &'a OrderingFn<'a, Action, T>) -> Self {
+        self.ordering = Some(ordering);
+        self
+    }
+
+    /// Tries first the killer moves: actions that caused a cutoff at the same ply.
+    pub fn with_killers(mut self) -> Self
+    where
+        Action: Clone + PartialEq + 'a,
+    {
+        self.killers = Some(|| Box::new(Killers::new()) as Box<dyn OrderingTable<Action>>);
+        self
+    }
+
+    /// Tries first the actions that caused more cutoffs anywhere in the tree.
+    pub fn with_history(mut self) -> Self
+    where
+        Action: Clone + Hash + Eq + 'a,
+    {
+        self.history = Some(|| Box::new(History::new()) as Box<dyn OrderingTable<Action>>);
+        self
     }
 }
 
 /// Implements an agent that runs the minmax tree search arlgorithm to produce moves.
-impl<'a, Action, AgentId, T> Agent<Action, AgentId, T> for MinmaxAgent<'a, AgentId, T>
+impl<'a, Action, AgentId, T> Agent<Action, AgentId, T> for MinmaxAgent<'a, Action, AgentId, T>
 where
-    AgentId: Eq + Copy + Debug,
-    Action: Copy + Debug,
+    AgentId: Eq + Copy,
+    Action: Copy,
     T: Environment<Action, AgentId>,
 {
     /// Returns the agent identity in the game.
@@ -37,19 +120,24 @@ where
 
     /// Produces an action based on minmax search.
     fn action(&mut self, env: &T) -> Option<Action> {
-        let (value, a) = minmax(
-            env,
-            &self.agent_id,
-            self.reward,
-            self.depth,
-            f64::NEG_INFINITY,
-            f64::INFINITY,
-        );
-        println!(
-            "Agent {:?}, Action {:?}, Value {:?}",
-            self.agent_id, &a, &value
-        );
+        let (value, a) = Negamax::new(&self.agent_id, self.reward)
+            .with_noisy_actions(self.quiescence)
+            .with_ordering(self.ordering)
+            .with_killers(self.killers.map(|killers| killers()))
+            .with_history(self.history.map(|history| history()))
+            .search(
+                &mut StatelessNode::new(env),
+                self.depth,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+            );
+        self.last_value = Some(value);
 
         a
     }
+
+    /// Returns the value found for the last action, and the depth searched.
+    fn last_evaluation(&self) -> Option<(f64, usize)> {
+        self.last_value.map(|value| (value, self.depth - 1))
+    }
 }
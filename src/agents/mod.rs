@@ -1,9 +1,17 @@
 mod alphabeta_agent;
+mod config;
 mod human_agent;
 mod mcts_utc_agent;
 mod minmax_agent;
+mod tablebase_agent;
 
 pub use alphabeta_agent::AlphabetaAgent;
+pub use config::AgentConfig;
+pub use config::AlphabetaConfig;
+pub use config::MctsUctConfig;
+pub use config::MinmaxConfig;
+pub use config::ParseConfigError;
 pub use human_agent::HumanPlayer;
 pub use mcts_utc_agent::MctsUctAgent;
 pub use minmax_agent::MinmaxAgent;
+pub use tablebase_agent::TablebaseAgent;
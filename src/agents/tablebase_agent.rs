@@ -0,0 +1,60 @@
+use std::hash::Hash;
+
+use crate::abstractions::Agent;
+use crate::abstractions::Environment;
+
+use crate::tablebase::Outcome;
+use crate::tablebase::Tablebase;
+
+/// A tablebase agent plays perfectly in the positions solved by a tablebase. It wins as fast
+/// as possible, and loses as slowly as possible. Positions missing from the tablebase are
+/// considered draws.
+pub struct TablebaseAgent<'a, AgentId, T> {
+    agent_id: AgentId,
+    tablebase: &'a Tablebase<T>,
+}
+
+/// Methods for TablebaseAgent
+impl<'a, AgentId, T> TablebaseAgent<'a, AgentId, T> {
+    pub fn new(agent_id: AgentId, tablebase: &'a Tablebase<T>) -> Self {
+        TablebaseAgent {
+            agent_id,
+            tablebase,
+        }
+    }
+}
+
+/// Implements an agent that picks the action with the best outcome in a tablebase.
+impl<'a, Action, AgentId, T> Agent<Action, AgentId, T> for TablebaseAgent<'a, AgentId, T>
+where
+    AgentId: Eq + Copy,
+    T: Environment<Action, AgentId> + Eq + Hash,
+{
+    /// Returns the agent identity in the game.
+    fn identity(&self) -> AgentId {
+        self.agent_id
+    }
+
+    /// Produces the action leading to the best outcome.
+    fn action(&mut self, env: &T) -> Option<Action> {
+        let turn = env.turn();
+
+        env.valid_actions()
+            .map(|action| {
+                let next_env = env.what_if(&action);
+                let outcome = self
+                    .tablebase
+                    .probe(&next_env)
+                    .map_or(Outcome::Draw, |o| o.before(next_env.turn() == turn));
+                (action, outcome.preference())
+            })
+            .fold(
+                None,
+                |best: Option<(Action, (i8, i64))>, (action, preference)| match best {
+                    Some((_, best_preference)) if best_preference >= preference => best,
+                    _ => Some((action, preference)),
+                },
+            )
+            .map(|(action, _)| action)
+    }
+}
@@ -0,0 +1,23 @@
+//! Command line tool playing and analyzing the games registered below. Run 'gts help' for the
+//! commands. Other games are added with 'Cli::with_game'.
+
+mod tictactoe;
+
+use std::env;
+use std::process;
+
+use gts::cli::Cli;
+use gts::cli::GameEntry;
+
+use self::tictactoe::TicTacToe;
+
+fn main() {
+    let cli = Cli::new().with_game(GameEntry::new("tictactoe", (1, 2), TicTacToe::reward));
+
+    if let Err(error) = cli.run(env::args().skip(1)) {
+        eprintln!("{}", error);
+        eprintln!();
+        eprint!("{}", Cli::usage());
+        process::exit(2);
+    }
+}
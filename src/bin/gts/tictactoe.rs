@@ -0,0 +1,146 @@
+use std::fmt;
+
+use gts::abstractions::Environment;
+use gts::abstractions::Notation;
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// Tic-tac-toe between agents 1 and 2. Actions are the cells, numbered from 0 to 8 row by
+/// row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TicTacToe {
+    board: [u8; 9],
+    turn: u8,
+}
+
+impl TicTacToe {
+    /// One for a win, minus one for a loss, and zero otherwise.
+    pub fn reward(&self, agent_id: &u8) -> f64 {
+        match self.winner() {
+            Some(winner) if winner == *agent_id => 1f64,
+            Some(_) => -1f64,
+            None => 0f64,
+        }
+    }
+}
+
+impl Environment<u8, u8> for TicTacToe {
+    fn initial_state() -> Self {
+        TicTacToe {
+            board: [0; 9],
+            turn: 1,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.board[*a as usize] = self.turn;
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        if self.is_terminal() {
+            return Box::new(std::iter::empty());
+        }
+        let board = self.board;
+        Box::new((0..9).filter(move |a| board[*a as usize] == 0))
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a < 9 && self.board[*a as usize] == 0 && !self.is_terminal()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.winner().is_some() || self.board.iter().all(|cell| *cell != 0)
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        LINES
+            .iter()
+            .map(|line| line.map(|cell| self.board[cell]))
+            .find(|[a, b, c]| *a != 0 && a == b && b == c)
+            .map(|[a, _, _]| a)
+    }
+}
+
+/// Positions are the cells row by row, '.' for empty ones, followed by the agent to move, like
+/// '1.2...... 1'.
+impl Notation<u8, u8> for TicTacToe {
+    fn from_notation(notation: &str) -> Option<Self> {
+        let (cells, turn) = notation.trim().split_once(' ')?;
+        if cells.chars().count() != 9 {
+            return None;
+        }
+
+        let mut board = [0; 9];
+        for (cell, c) in board.iter_mut().zip(cells.chars()) {
+            *cell = match c {
+                '.' => 0,
+                '1' => 1,
+                '2' => 2,
+                _ => return None,
+            };
+        }
+
+        match turn.trim() {
+            "1" => Some(TicTacToe { board, turn: 1 }),
+            "2" => Some(TicTacToe { board, turn: 2 }),
+            _ => None,
+        }
+    }
+
+    fn to_notation(&self) -> String {
+        let cells: String = self
+            .board
+            .iter()
+            .map(|cell| match cell {
+                0 => '.',
+                1 => '1',
+                _ => '2',
+            })
+            .collect();
+        format!("{} {}", cells, self.turn)
+    }
+}
+
+/// Shows the marks of the agents, 'x' for 1 and 'o' for 2, and the numbers of the empty cells.
+impl fmt::Display for TicTacToe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.board.chunks(3).enumerate() {
+            let (index, cells) = row;
+            let cells: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| match cell {
+                    0 => (3 * index + column).to_string(),
+                    1 => "x".to_string(),
+                    _ => "o".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+#[cfg(feature = "serde")]
+use std::io;
+#[cfg(feature = "serde")]
+use std::io::Read;
+#[cfg(feature = "serde")]
+use std::io::Write;
+use std::mem;
+#[cfg(feature = "serde")]
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::de;
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Deserializer;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::Serializer;
+
+use crate::abstractions::Environment;
+use crate::abstractions::Zobrist;
+
+use super::minmax_data::MinMaxData;
+use super::node::CanonicalFn;
+#[cfg(feature = "serde")]
+use super::persistence;
+use super::zobrist::BuildZobristHasher;
+
+pub type ArenaMM<T, Action> = Arena<T, Action, MinMaxData<Action>>;
+
+/// Index of a node in an 'Arena'. Indices are only valid until 'Arena::retain_reachable'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeId(u32);
+
+impl NodeId {
+    fn new(index: usize) -> Self {
+        NodeId(u32::try_from(index).expect("Arenas hold at most u32::MAX nodes"))
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A position in the arena. Children point to other entries by index.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Entry<T, Action, D> {
+    env: T,
+    data: D,
+    children: Vec<(NodeId, Action)>,
+    unvisited: Option<Vec<Action>>, // In reverse order. None until the entry is expanded.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cursor: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    next_in_bucket: Option<NodeId>,
+}
+
+/// A game tree stored in a single vector, with the same semantics as the node cache: each
+/// position is stored once, children are produced lazily from 'valid_actions', and
+/// transpositions lead to the same node. Nodes refer to each other by 'NodeId', so they can be
+/// accessed without reference counting nor runtime borrow checks.
+///
+/// Moving the root with 'set_root' keeps every node, which makes it O(1). Nodes that can no
+/// longer be reached from the root are dropped by 'retain_reachable'.
+pub struct Arena<T, Action, D> {
+    entries: Vec<Entry<T, Action, D>>,
+    buckets: HashMap<u64, NodeId, BuildZobristHasher>, // First entry of each hash, chained.
+    hasher: RandomState,
+    zobrist: Option<fn(&T) -> u64>,
+    root: Option<NodeId>,
+    canonical: Option<CanonicalFn<T>>,
+}
+
+impl<T, Action, D> Arena<T, Action, D>
+where
+    T: Hash + Eq,
+    D: Default,
+{
+    pub fn new() -> Self {
+        Arena {
+            entries: Vec::new(),
+            buckets: HashMap::default(),
+            hasher: RandomState::new(),
+            zobrist: None,
+            root: None,
+            canonical: None,
+        }
+    }
+
+    /// Sets the function producing the canonical positions stored, as in 'Node::new_canonical'.
+    pub fn with_canonical(mut self, canonical: Option<CanonicalFn<T>>) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Finds positions by their Zobrist key instead of hashing them. Positions sharing a key
+    /// are still compared, so collisions only cost time.
+    pub fn with_zobrist<AgentId>(mut self) -> Self
+    where
+        T: Zobrist<Action, AgentId>,
+    {
+        self.zobrist = Some(T::zobrist);
+        self.rehash();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.buckets.clear();
+        self.root = None;
+    }
+
+    /// Returns the node of 'env', if it was stored.
+    pub fn get(&self, env: &T) -> Option<NodeId> {
+        match self.canonical {
+            Some(canonical) => self.find(&canonical(env)),
+            None => self.find(env),
+        }
+    }
+
+    /// Returns the node of 'env', storing it if needed.
+    pub fn insert(&mut self, env: T) -> NodeId {
+        let env = match self.canonical {
+            Some(canonical) => canonical(&env),
+            None => env,
+        };
+
+        match self.find(&env) {
+            Some(id) => id,
+            None => self.push(Entry {
+                env,
+                data: D::default(),
+                children: Vec::new(),
+                unvisited: None,
+                cursor: 0,
+                next_in_bucket: None,
+            }),
+        }
+    }
+
+    pub fn root(&self) -> Option<NodeId> {
+        self.root
+    }
+
+    /// Moves the root to the node of 'env'. Every node is kept, including the ones that are
+    /// no longer reachable.
+    pub fn set_root(&mut self, env: T) -> NodeId {
+        let root = self.insert(env);
+        self.root = Some(root);
+        root
+    }
+
+    /// Drops the nodes that can't be reached from the root, and renumbers the rest.
+    pub fn retain_reachable(&mut self) {
+        let root = match self.root {
+            Some(root) => root,
+            None => return self.clear(),
+        };
+
+        let mut new_ids = vec![None; self.entries.len()];
+        new_ids[root.index()] = Some(NodeId::new(0));
+
+        let mut reachable = vec![root];
+        let mut next = 0;
+        while next < reachable.len() {
+            for (child, _) in &self.entries[reachable[next].index()].children {
+                if new_ids[child.index()].is_none() {
+                    new_ids[child.index()] = Some(NodeId::new(reachable.len()));
+                    reachable.push(*child);
+                }
+            }
+            next += 1;
+        }
+
+        let mut entries: Vec<_> = mem::take(&mut self.entries).into_iter().map(Some).collect();
+        self.buckets.clear();
+
+        for id in reachable {
+            let mut entry = entries[id.index()].take().expect("Reachable once");
+            for (child, _) in &mut entry.children {
+                *child = new_ids[child.index()].expect("Children are reachable");
+            }
+            entry.next_in_bucket = None;
+            self.push(entry);
+        }
+
+        self.root = Some(NodeId::new(0));
+    }
+
+    pub fn environment(&self, id: NodeId) -> &T {
+        &self.entries[id.index()].env
+    }
+
+    pub fn data(&self, id: NodeId) -> &D {
+        &self.entries[id.index()].data
+    }
+
+    pub fn data_mut(&mut self, id: NodeId) -> &mut D {
+        &mut self.entries[id.index()].data
+    }
+
+    /// Returns the children of a node visited so far, with the actions leading to them.
+    pub fn children(&self, id: NodeId) -> &[(NodeId, Action)] {
+        &self.entries[id.index()].children
+    }
+
+    /// Restarts the iteration of 'next_child' over the children of a node.
+    pub fn reset(&mut self, id: NodeId) {
+        self.entries[id.index()].cursor = 0;
+    }
+
+    /// Returns the next child of a node, visiting a new one once the ones visited before
+    /// have been returned.
+    pub fn next_child<AgentId>(&mut self, id: NodeId) -> Option<(NodeId, Action)>
+    where
+        Action: Copy,
+        T: Environment<Action, AgentId>,
+    {
+        let entry = &mut self.entries[id.index()];
+        let cursor = entry.cursor as usize;
+
+        let output = match entry.children.get(cursor) {
+            Some(child) => *child,
+            None => {
+                let action = self.unvisited(id).pop()?;
+                let child = self.insert(self.entries[id.index()].env.what_if(&action));
+                self.entries[id.index()].children.push((child, action));
+                (child, action)
+            }
+        };
+
+        self.entries[id.index()].cursor += 1;
+        Some(output)
+    }
+
+    /// Reorders the actions of a node that have not been visited yet with 'order'.
+    pub fn order_unvisited<AgentId>(
+        &mut self,
+        id: NodeId,
+        order: impl FnOnce(Vec<Action>) -> Vec<Action>,
+    ) where
+        T: Environment<Action, AgentId>,
+    {
+        let unvisited = self.unvisited(id);
+        unvisited.reverse();
+
+        let mut ordered = order(mem::take(unvisited));
+        ordered.reverse();
+        *unvisited = ordered;
+    }
+
+    /// Returns the actions of a node not visited yet, in reverse order.
+    fn unvisited<AgentId>(&mut self, id: NodeId) -> &mut Vec<Action>
+    where
+        T: Environment<Action, AgentId>,
+    {
+        let entry = &mut self.entries[id.index()];
+        let env = &entry.env;
+
+        entry.unvisited.get_or_insert_with(|| {
+            let mut actions: Vec<Action> = env.valid_actions().collect();
+            actions.reverse();
+            actions
+        })
+    }
+
+    /// Returns the key of the bucket of 'env'.
+    fn hash(&self, env: &T) -> u64 {
+        match self.zobrist {
+            Some(zobrist) => zobrist(env),
+            None => self.hasher.hash_one(env),
+        }
+    }
+
+    /// Puts every entry back in the bucket of its key, after the keys changed.
+    fn rehash(&mut self) {
+        let mut buckets = HashMap::default();
+        for index in 0..self.entries.len() {
+            let hash = self.hash(&self.entries[index].env);
+            self.entries[index].next_in_bucket = buckets.insert(hash, NodeId::new(index));
+        }
+        self.buckets = buckets;
+    }
+
+    fn find(&self, env: &T) -> Option<NodeId> {
+        let mut next = self.buckets.get(&self.hash(env)).copied();
+
+        while let Some(id) = next {
+            let entry = &self.entries[id.index()];
+            if entry.env == *env {
+                return Some(id);
+            }
+            next = entry.next_in_bucket;
+        }
+
+        None
+    }
+
+    fn push(&mut self, mut entry: Entry<T, Action, D>) -> NodeId {
+        let id = NodeId::new(self.entries.len());
+
+        entry.next_in_bucket = self.buckets.insert(self.hash(&entry.env), id);
+        self.entries.push(entry);
+
+        id
+    }
+}
+
+impl<T, Action, D> Default for Arena<T, Action, D>
+where
+    T: Hash + Eq,
+    D: Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Action> Arena<T, Action, MinMaxData<Action>> {
+    /// Sorts the children visited of a node by their value, like 'Node::sort_children'.
+    pub fn sort_children(&mut self, id: NodeId) {
+        let mut children = mem::take(&mut self.entries[id.index()].children);
+        let value = |child: &NodeId| self.entries[child.index()].data.value;
+
+        // Children without a stored value, like the ones depending on a repetition, go last.
+        let is_maximizer = self.entries[id.index()].data.is_maximizer;
+        children.sort_by(|(a, _), (b, _)| {
+            let (a, b) = (value(a), value(b));
+            match (!a.is_nan(), !b.is_nan()) {
+                (true, true) if is_maximizer => b.partial_cmp(&a).expect("Comparing to NaN"),
+                (true, true) => a.partial_cmp(&b).expect("Comparing to NaN"),
+                (a_known, b_known) => b_known.cmp(&a_known),
+            }
+        });
+
+        self.entries[id.index()].children = children;
+    }
+}
+
+#[cfg(feature = "serde")]
+const MAGIC: &[u8; 4] = b"GTAR";
+
+/// Persistence of arenas, so that searches can be resumed by another process. The functions
+/// producing canonical positions and Zobrist keys are not stored, they have to be set again
+/// with 'with_canonical' and 'with_zobrist'.
+#[cfg(feature = "serde")]
+impl<T, Action, D> Arena<T, Action, D>
+where
+    T: Serialize + de::DeserializeOwned + Hash + Eq,
+    Action: Serialize + de::DeserializeOwned,
+    D: Serialize + de::DeserializeOwned + Default,
+{
+    /// Writes the arena to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        persistence::save(self, MAGIC, path)
+    }
+
+    /// Reads an arena from a file written by 'save'.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        persistence::load(MAGIC, path)
+    }
+
+    /// Writes the arena. See 'save'.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        persistence::write_to(self, MAGIC, writer)
+    }
+
+    /// Reads an arena written by 'write_to'.
+    pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        persistence::read_from(MAGIC, reader)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, Action, D> Serialize for Arena<T, Action, D>
+where
+    T: Serialize,
+    Action: Serialize,
+    D: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.root, &self.entries).serialize(serializer)
+    }
+}
+
+/// Arenas are rebuilt from their entries, since the hash buckets depend on the hasher.
+#[cfg(feature = "serde")]
+impl<'de, T, Action, D> Deserialize<'de> for Arena<T, Action, D>
+where
+    T: Deserialize<'de> + Hash + Eq,
+    Action: Deserialize<'de>,
+    D: Deserialize<'de> + Default,
+{
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let (root, entries): (Option<NodeId>, Vec<Entry<T, Action, D>>) =
+            Deserialize::deserialize(deserializer)?;
+
+        let is_valid = |id: &NodeId| id.index() < entries.len();
+        let all_valid = root.iter().all(is_valid)
+            && entries
+                .iter()
+                .all(|entry| entry.children.iter().all(|(child, _)| is_valid(child)));
+        if !all_valid {
+            return Err(de::Error::custom("Node index out of the arena"));
+        }
+
+        let mut arena = Arena::new();
+        for entry in entries {
+            arena.push(entry);
+        }
+        arena.root = root;
+
+        Ok(arena)
+    }
+}
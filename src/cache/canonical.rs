@@ -0,0 +1,62 @@
+use crate::abstractions::Symmetric;
+
+use super::node::CanonicalFn;
+
+/// The functions of 'Symmetric' needed by caches storing canonical positions. Captured where
+/// the environment is known to be symmetric, so that caches do not need the trait bound.
+pub struct Canonicalizer<Action, T> {
+    canonical: fn(&T) -> (T, usize),
+    environment: CanonicalFn<T>,
+    transform_action: fn(&T, &Action, usize) -> Action,
+    inverse: fn(&T, usize) -> usize,
+}
+
+impl<Action, T> Canonicalizer<Action, T> {
+    pub fn new<AgentId>() -> Self
+    where
+        T: Symmetric<Action, AgentId> + Ord,
+    {
+        Canonicalizer {
+            canonical: T::canonical,
+            environment: canonical_environment::<Action, AgentId, T>,
+            transform_action: T::transform_action,
+            inverse: T::inverse,
+        }
+    }
+
+    /// Returns the canonical position of 'env', and the symmetry leading to it.
+    pub fn canonical(&self, env: &T) -> (T, usize) {
+        (self.canonical)(env)
+    }
+
+    /// Returns the canonical position of 'env'.
+    pub fn environment(&self, env: &T) -> T {
+        (self.environment)(env)
+    }
+
+    /// Returns the function producing canonical positions, as used by the node cache.
+    pub fn environment_fn(&self) -> CanonicalFn<T> {
+        self.environment
+    }
+
+    /// Maps an action of 'canonical', the canonical position of some environment reached with
+    /// 'symmetry', back to that environment.
+    pub fn action_back(&self, canonical: &T, action: &Action, symmetry: usize) -> Action {
+        (self.transform_action)(canonical, action, (self.inverse)(canonical, symmetry))
+    }
+}
+
+fn canonical_environment<Action, AgentId, T>(env: &T) -> T
+where
+    T: Symmetric<Action, AgentId> + Ord,
+{
+    env.canonical().0
+}
+
+impl<Action, T> Clone for Canonicalizer<Action, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Action, T> Copy for Canonicalizer<Action, T> {}
@@ -1,8 +1,50 @@
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Tells how a cached value relates to the true value of a position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Bound {
+    /// The value is exact.
+    Exact,
+    /// The true value is at least the cached value.
+    Lower,
+    /// The true value is at most the cached value.
+    Upper,
+}
+
+impl Bound {
+    /// Returns the bound given by a fail-soft search of the window [alpha, beta] that
+    /// returned 'value'.
+    pub fn within(value: f64, alpha: f64, beta: f64) -> Self {
+        if value <= alpha {
+            Bound::Upper
+        } else if value >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        }
+    }
+
+    /// Returns the bound as seen by the opponent, for whom values are negated.
+    pub fn flip(self) -> Self {
+        match self {
+            Bound::Exact => Bound::Exact,
+            Bound::Lower => Bound::Upper,
+            Bound::Upper => Bound::Lower,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MinMaxData<Action> {
     pub is_maximizer: bool,
     pub depth: usize,
     pub value: f64,
+    pub bound: Bound,
     pub action: Option<Action>,
 }
 
@@ -13,6 +55,7 @@ impl<Action> MinMaxData<Action> {
                 is_maximizer: true,
                 depth: 0,
                 value: f64::NEG_INFINITY,
+                bound: Bound::Exact,
                 action: None,
             }
         } else {
@@ -20,6 +63,7 @@ impl<Action> MinMaxData<Action> {
                 is_maximizer: false,
                 depth: 0,
                 value: f64::INFINITY,
+                bound: Bound::Exact,
                 action: None,
             }
         }
@@ -32,6 +76,7 @@ impl<Action> Default for MinMaxData<Action> {
             is_maximizer: true,
             depth: 0,
             value: f64::NAN,
+            bound: Bound::Exact,
             action: None,
         }
     }
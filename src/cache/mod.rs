@@ -1,3 +1,8 @@
+pub mod arena;
+pub mod canonical;
 pub mod minmax_data;
 pub mod node;
+#[cfg(feature = "serde")]
+pub mod persistence;
 pub mod utils;
+pub mod zobrist;
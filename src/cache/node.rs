@@ -1,22 +1,121 @@
 use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
+use std::hash::BuildHasher;
 use std::hash::Hash;
 use std::rc::Rc;
 
 use crate::abstractions::Environment;
+use crate::abstractions::Zobrist;
 
 use super::minmax_data::MinMaxData;
 use super::utils::get_or_insert;
+use super::utils::get_or_insert_canonical;
 use super::utils::node_partial_cmp;
+use super::zobrist::BuildZobristHasher;
 
 pub type NodeRRMM<T, Action, AgentId> = NodeRR<T, Action, AgentId, MinMaxData<Action>>;
 pub type CacheMM<T, Action, AgentId> = Cache<T, Action, AgentId, MinMaxData<Action>>;
 
 pub(super) type NodeRR<T, Action, AgentId, D> = Rc<RefCell<Node<T, Action, AgentId, D>>>;
 
-type Cache<T, Action, AgentId, D> = HashMap<Rc<T>, NodeRR<T, Action, AgentId, D>>;
 pub(super) type CacheRR<T, Action, AgentId, D> = Rc<RefCell<Cache<T, Action, AgentId, D>>>;
 
+/// Positions sharing a key, with their nodes.
+type Bucket<T, Action, AgentId, D> = Vec<(Rc<T>, NodeRR<T, Action, AgentId, D>)>;
+
+/// Nodes of the positions reached, in buckets keyed on the hash of the positions, or on their
+/// Zobrist key. Positions sharing a bucket are told apart by comparing them.
+pub struct Cache<T, Action, AgentId, D>
+where
+    T: Environment<Action, AgentId>,
+{
+    buckets: HashMap<u64, Bucket<T, Action, AgentId, D>, BuildZobristHasher>,
+    hasher: RandomState,
+    zobrist: Option<fn(&T) -> u64>,
+    len: usize,
+}
+
+impl<T, Action, AgentId, D> Cache<T, Action, AgentId, D>
+where
+    T: Environment<Action, AgentId> + Hash + Eq,
+{
+    fn new() -> Self {
+        Cache {
+            buckets: HashMap::default(),
+            hasher: RandomState::new(),
+            zobrist: None,
+            len: 0,
+        }
+    }
+
+    /// Returns the key of the bucket of 'env'.
+    fn hash(&self, env: &T) -> u64 {
+        match self.zobrist {
+            Some(zobrist) => zobrist(env),
+            None => self.hasher.hash_one(env),
+        }
+    }
+
+    /// Keys the buckets on 'zobrist', or on the hash of the positions with None.
+    fn set_zobrist(&mut self, zobrist: Option<fn(&T) -> u64>) {
+        self.zobrist = zobrist;
+
+        let entries: Vec<_> = self
+            .buckets
+            .drain()
+            .flat_map(|(_, bucket)| bucket)
+            .collect();
+        self.len = 0;
+        for (env, node) in entries {
+            self.insert(env, node);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.len = 0;
+    }
+
+    /// Returns the node of 'env', if it was stored.
+    pub fn get(&self, env: &T) -> Option<NodeRR<T, Action, AgentId, D>> {
+        self.buckets
+            .get(&self.hash(env))?
+            .iter()
+            .find(|(stored, _)| **stored == *env)
+            .map(|(_, node)| node.clone())
+    }
+
+    /// Stores 'node' for 'env', and returns the node it replaces, if any.
+    pub fn insert(
+        &mut self,
+        env: Rc<T>,
+        node: NodeRR<T, Action, AgentId, D>,
+    ) -> Option<NodeRR<T, Action, AgentId, D>> {
+        let bucket = self.buckets.entry(self.hash(&env)).or_default();
+
+        match bucket.iter_mut().find(|(stored, _)| *stored == env) {
+            Some((_, stored)) => Some(std::mem::replace(stored, node)),
+            None => {
+                bucket.push((env, node));
+                self.len += 1;
+                None
+            }
+        }
+    }
+}
+
+/// Function mapping an environment to the canonical position representing it in the cache.
+pub type CanonicalFn<T> = fn(&T) -> T;
+
 pub struct Node<T, Action, AgentId, D>
 where
     T: Environment<Action, AgentId>,
@@ -25,9 +124,11 @@ where
     turn: AgentId,
     visited: Vec<(Rc<RefCell<Self>>, Action)>,
     to_visit: Box<dyn Iterator<Item = Action>>,
+    ordered: Vec<Action>, // Actions to visit before 'to_visit', in reverse order.
     index: usize,
     pub data: D,
     cache_ptr: CacheRR<T, Action, AgentId, D>,
+    canonical: Option<CanonicalFn<T>>,
 }
 
 impl<T, Action, AgentId, D> Node<T, Action, AgentId, D>
@@ -41,14 +142,38 @@ where
             turn: env.turn(),
             visited: Vec::new(),
             to_visit: env.valid_actions(),
+            ordered: Vec::new(),
+            index: 0,
+            data: D::default(),
+            cache_ptr: Rc::new(RefCell::new(Cache::new())),
+            canonical: None,
+        };
+
+        get_or_insert(env, node.cache_ptr.clone());
+
+        node
+    }
+
+    /// Creates a node whose cache stores canonical positions only. The node, and every node
+    /// reached from it, holds the canonical position of its environment, and its actions are
+    /// the ones of that position.
+    pub fn new_canonical(env: &T, canonical: CanonicalFn<T>) -> Self {
+        let env = Rc::new(canonical(env));
+        let node = Node {
+            env: env.clone(),
+            turn: env.turn(),
+            visited: Vec::new(),
+            to_visit: env.valid_actions(),
+            ordered: Vec::new(),
             index: 0,
             data: D::default(),
             cache_ptr: Rc::new(RefCell::new(Cache::new())),
+            canonical: Some(canonical),
         };
 
-        get_or_insert(&env, node.cache_ptr.clone());
+        get_or_insert_canonical(&env, node.cache_ptr.clone(), node.canonical);
 
-        return node;
+        node
     }
 
     pub fn with_cache(env: &Rc<T>, cache_ptr: CacheRR<T, Action, AgentId, D>) -> Self {
@@ -57,9 +182,35 @@ where
             turn: env.turn(),
             visited: Vec::new(),
             to_visit: env.valid_actions(),
+            ordered: Vec::new(),
             index: 0,
             data: D::default(),
             cache_ptr,
+            canonical: None,
+        }
+    }
+
+    /// Keys the cache shared by this node on the Zobrist keys of the positions, instead of
+    /// hashing them. Positions sharing a key are still compared, so collisions only cost time.
+    pub fn with_zobrist(self) -> Self
+    where
+        T: Zobrist<Action, AgentId>,
+    {
+        self.cache_ptr.borrow_mut().set_zobrist(Some(T::zobrist));
+        self
+    }
+
+    /// Sets the function producing canonical positions for the nodes reached from this one.
+    pub fn with_canonical(mut self, canonical: Option<CanonicalFn<T>>) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Returns the position representing 'env' in the cache.
+    fn canonical(&self, env: T) -> T {
+        match self.canonical {
+            Some(canonical) => canonical(&env),
+            None => env,
         }
     }
 
@@ -75,13 +226,30 @@ where
     }
 
     fn add_descendants(&self, cache: &mut Cache<T, Action, AgentId, D>) {
-        for (child, _) in &self.visited {
-            let child_ptr = child.borrow();
-            cache.insert(child_ptr.env.clone(), child.clone());
-            child.borrow().add_descendants(cache);
+        let mut stack: Vec<_> = self
+            .visited
+            .iter()
+            .map(|(child, _)| child.clone())
+            .collect();
+
+        while let Some(node) = stack.pop() {
+            let node_ptr = node.borrow();
+            // Positions already added were reached before, possibly through a cycle.
+            if cache.insert(node_ptr.env.clone(), node.clone()).is_none() {
+                stack.extend(node_ptr.visited.iter().map(|(child, _)| child.clone()));
+            }
         }
     }
 
+    /// Reorders the actions that have not been visited yet with 'order'.
+    pub fn order_unvisited(&mut self, order: impl FnOnce(Vec<Action>) -> Vec<Action>) {
+        let mut actions: Vec<Action> = self.ordered.drain(..).rev().collect();
+        actions.extend(&mut self.to_visit);
+
+        self.ordered = order(actions);
+        self.ordered.reverse();
+    }
+
     pub fn reset(&mut self) {
         self.index = 0;
     }
@@ -98,10 +266,21 @@ where
         self.cache_ptr.borrow_mut().len()
     }
 
-    pub fn cache_get(&self, env: &T) -> Option<Rc<RefCell<Node<T, Action, AgentId, D>>>> {
-        match self.cache_ptr.borrow().get(env) {
-            Some(node) => Some(node.clone()),
-            None => None,
+    pub fn cache_get_or_insert(&self, env: &Rc<T>) -> NodeRR<T, Action, AgentId, D> {
+        match self.canonical {
+            Some(canonical) => get_or_insert_canonical(
+                &Rc::new(canonical(env)),
+                self.cache_ptr.clone(),
+                self.canonical,
+            ),
+            None => get_or_insert(env, self.cache_ptr.clone()),
+        }
+    }
+
+    pub fn cache_get(&self, env: &T) -> Option<NodeRR<T, Action, AgentId, D>> {
+        match self.canonical {
+            Some(canonical) => self.cache_ptr.borrow().get(&canonical(env)),
+            None => self.cache_ptr.borrow().get(env),
         }
     }
 }
@@ -116,10 +295,11 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index >= self.visited.len() {
-            match self.to_visit.next() {
+            match self.ordered.pop().or_else(|| self.to_visit.next()) {
                 Some(a) => {
-                    let env_next = Rc::new(self.env.what_if(&a));
-                    let node_next_ptr = get_or_insert(&env_next, self.cache_ptr.clone());
+                    let env_next = Rc::new(self.canonical(self.env.what_if(&a)));
+                    let node_next_ptr =
+                        get_or_insert_canonical(&env_next, self.cache_ptr.clone(), self.canonical);
                     let output = (node_next_ptr, a);
                     self.visited.push(output.clone());
                     self.index += 1;
@@ -140,13 +320,18 @@ where
     T: Environment<Action, AgentId>,
 {
     pub fn sort_children(&mut self) {
+        // Children without a stored value, like the ones depending on a repetition, go last.
         let is_maximizer = self.data.is_maximizer;
-        if is_maximizer {
-            self.visited
-                .sort_by(|(a, _), (b, _)| node_partial_cmp(b, a))
-        } else {
-            self.visited
-                .sort_by(|(a, _), (b, _)| node_partial_cmp(a, b))
-        }
+        self.visited.sort_by(|(a, _), (b, _)| {
+            let (a_known, b_known) = (
+                !a.borrow().data.value.is_nan(),
+                !b.borrow().data.value.is_nan(),
+            );
+            match (a_known, b_known) {
+                (true, true) if is_maximizer => node_partial_cmp(b, a),
+                (true, true) => node_partial_cmp(a, b),
+                _ => b_known.cmp(&a_known),
+            }
+        })
     }
 }
@@ -0,0 +1,74 @@
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Version of the format shared by every cache file. Files start with a magic number telling
+/// what they hold, followed by this version and the value encoded with 'bincode'.
+const VERSION: u8 = 1;
+
+/// Writes 'value' to a file, with a header made of 'magic' and the format version.
+pub fn save<V, P>(value: &V, magic: &[u8; 4], path: P) -> io::Result<()>
+where
+    V: Serialize,
+    P: AsRef<Path>,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_to(value, magic, &mut writer)?;
+    writer.flush()
+}
+
+/// Reads a value written by 'save' with the same 'magic'.
+pub fn load<V, P>(magic: &[u8; 4], path: P) -> io::Result<V>
+where
+    V: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    read_from(magic, &mut BufReader::new(File::open(path)?))
+}
+
+/// Writes the header and 'value'. See 'save'.
+pub fn write_to<V, W>(value: &V, magic: &[u8; 4], writer: &mut W) -> io::Result<()>
+where
+    V: Serialize,
+    W: Write,
+{
+    writer.write_all(magic)?;
+    writer.write_all(&[VERSION])?;
+
+    bincode::serialize_into(writer, value).map_err(invalid_data)
+}
+
+/// Reads a value written by 'write_to' with the same 'magic'.
+pub fn read_from<V, R>(magic: &[u8; 4], reader: &mut R) -> io::Result<V>
+where
+    V: DeserializeOwned,
+    R: Read,
+{
+    let mut found = [0u8; 4];
+    reader.read_exact(&mut found)?;
+    if &found != magic {
+        return Err(invalid_data("Not a file of the expected cache"));
+    }
+
+    let mut version = [0u8; 1];
+    reader.read_exact(&mut version)?;
+    if version[0] != VERSION {
+        return Err(invalid_data("Unsupported cache version"));
+    }
+
+    bincode::deserialize_from(reader).map_err(invalid_data)
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
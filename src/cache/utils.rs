@@ -1,10 +1,13 @@
 use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
 use std::hash::Hash;
+use std::hash::Hasher;
 use std::rc::Rc;
 
 use crate::abstractions::Environment;
 
 use super::node::CacheRR;
+use super::node::CanonicalFn;
 use super::node::Node;
 use super::node::NodeRR;
 use super::node::NodeRRMM;
@@ -18,14 +21,30 @@ where
     T: Environment<Action, AgentId> + Eq + Hash,
     D: Default,
 {
-    let mut cache = cache_ptr.borrow_mut();
-    let output = cache
-        .entry(key.clone())
-        .or_insert(Rc::new(RefCell::new(Node::with_cache(
-            &key,
-            cache_ptr.clone(),
-        ))));
-    output.clone()
+    get_or_insert_canonical(key, cache_ptr, None)
+}
+
+/// Gets or insert a node into a cache of canonical positions. 'key' must be canonical already.
+pub fn get_or_insert_canonical<T, Action, AgentId, D>(
+    key: &Rc<T>,
+    cache_ptr: CacheRR<T, Action, AgentId, D>,
+    canonical: Option<CanonicalFn<T>>,
+) -> NodeRR<T, Action, AgentId, D>
+where
+    T: Environment<Action, AgentId> + Eq + Hash,
+    D: Default,
+{
+    let stored = cache_ptr.borrow().get(key);
+    match stored {
+        Some(node) => node,
+        None => {
+            let node = Rc::new(RefCell::new(
+                Node::with_cache(key, cache_ptr.clone()).with_canonical(canonical),
+            ));
+            cache_ptr.borrow_mut().insert(key.clone(), node.clone());
+            node
+        }
+    }
 }
 
 // Partial comparison for two nodes.
@@ -43,3 +62,10 @@ where
         .partial_cmp(&rhs_value)
         .unwrap_or_else(|| panic!("Comparing to NaN"))
 }
+
+/// Hashes a value with the default hasher.
+pub(crate) fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,85 @@
+use std::hash::BuildHasherDefault;
+use std::hash::Hasher;
+
+/// Random keys for building Zobrist keys of board games. There is a key for every piece on
+/// every square, and one for every agent to move. The key of a position is the XOR of the keys
+/// of its pieces and of the agent moving, so it can be updated with a couple of XORs per action.
+pub struct ZobristTable {
+    pieces: usize,
+    squares: Vec<u64>,
+    turns: Vec<u64>,
+}
+
+impl ZobristTable {
+    /// Creates keys for 'squares' squares with 'pieces' kinds of pieces, and 'agents' agents.
+    pub fn new(squares: usize, pieces: usize, agents: usize) -> Self {
+        Self::with_seed(squares, pieces, agents, 0x9e37_79b9_7f4a_7c15)
+    }
+
+    /// Creates keys like 'new', from a given seed.
+    pub fn with_seed(squares: usize, pieces: usize, agents: usize, seed: u64) -> Self {
+        let mut state = seed;
+
+        ZobristTable {
+            pieces,
+            squares: (0..squares * pieces)
+                .map(|_| split_mix(&mut state))
+                .collect(),
+            turns: (0..agents).map(|_| split_mix(&mut state)).collect(),
+        }
+    }
+
+    /// Returns the key of 'piece' on 'square'.
+    pub fn piece(&self, square: usize, piece: usize) -> u64 {
+        self.squares[square * self.pieces + piece]
+    }
+
+    /// Returns the key of 'agent' being the one to move.
+    pub fn turn(&self, agent: usize) -> u64 {
+        self.turns[agent]
+    }
+
+    /// Computes from scratch the key of a position, given its (square, piece) pairs and the
+    /// agent to move. Useful for initial states, and to verify incremental updates.
+    pub fn key(&self, pieces: impl IntoIterator<Item = (usize, usize)>, agent: usize) -> u64 {
+        pieces
+            .into_iter()
+            .fold(self.turn(agent), |key, (square, piece)| {
+                key ^ self.piece(square, piece)
+            })
+    }
+}
+
+/// Next value of the SplitMix64 generator.
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Hasher for values that are already well distributed 64-bit keys, like Zobrist keys.
+/// It uses the last 'u64' written as the hash.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ZobristHasher(u64);
+
+impl Hasher for ZobristHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*byte);
+        }
+    }
+
+    fn write_u64(&mut self, key: u64) {
+        self.0 = key;
+    }
+}
+
+/// Builds 'ZobristHasher's for hash maps keyed on Zobrist keys.
+pub type BuildZobristHasher = BuildHasherDefault<ZobristHasher>;
@@ -0,0 +1,113 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io;
+use std::str::FromStr;
+
+use super::arguments::Arguments;
+use super::game_entry::CliGame;
+use super::GameEntry;
+use crate::abstractions::Notation;
+
+const USAGE: &str = "\
+Usage: gts <command> [arguments]
+
+Commands:
+  games                           Lists the games
+  match <game> <agent> <agent>    Plays games between two agents, which swap sides every game
+        [--games N] [--position P] [--record FILE]
+  play <game> <agent>             Plays against an agent, on side S (the first one by default)
+        [--side S] [--position P] [--record FILE]
+  analyze <game> <agent>          Searches a position, and prints the action found
+        [--position P]
+  perft <game> <depth>            Counts the positions reached after every number of plies,
+        [--position P] [--divide] or after every action with '--divide'
+  help                            Prints this message
+
+Agents are specs like 'alphabeta:depth=6', 'mcts:runs=10000,c=1.4' or 'human'. Positions are
+written in the notation of the game, the initial one by default. Records are written in the
+text format, separated by empty lines.
+";
+
+/// Error running a command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CliError(String);
+
+impl CliError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        CliError(message.into())
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError(error.to_string())
+    }
+}
+
+/// Command line interface to the games registered with 'with_game'. It plays matches between
+/// agents given as specs, lets a human play against an agent, analyzes positions, and runs
+/// perft. See 'Cli::usage' for the commands.
+#[derive(Default)]
+pub struct Cli {
+    games: Vec<Box<dyn CliGame>>,
+}
+
+impl Cli {
+    pub fn new() -> Self {
+        Cli::default()
+    }
+
+    pub fn with_game<Action, AgentId, T>(mut self, game: GameEntry<Action, AgentId, T>) -> Self
+    where
+        Action: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+        AgentId: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+        T: Notation<Action, AgentId> + Display + Clone + Eq + Hash + 'static,
+    {
+        self.games.push(Box::new(game));
+        self
+    }
+
+    /// Returns the help message listing the commands.
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+
+    /// Runs the command in 'args', which don't include the name of the program.
+    pub fn run(&self, args: impl IntoIterator<Item = String>) -> Result<(), CliError> {
+        let arguments = Arguments::parse(args)?;
+
+        match arguments.positional(0, "command")? {
+            "help" => {
+                print!("{}", USAGE);
+                Ok(())
+            }
+            "games" => {
+                arguments.expect(1, &[])?;
+                for game in &self.games {
+                    println!("{}", game.name());
+                }
+                Ok(())
+            }
+            command => {
+                let name = arguments.positional(1, "game")?;
+                let game = self
+                    .games
+                    .iter()
+                    .find(|game| game.name() == name)
+                    .ok_or_else(|| CliError::new(format!("Unknown game '{}'", name)))?;
+                game.run(command, &arguments)
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use super::CliError;
+
+/// Options taking no value.
+const FLAGS: [&str; 1] = ["divide"];
+
+/// Arguments of a command: positional ones, and options written '--name value' or '--flag'.
+pub(crate) struct Arguments {
+    positional: Vec<String>,
+    options: HashMap<String, Option<String>>,
+}
+
+impl Arguments {
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut arguments = Arguments {
+            positional: Vec::new(),
+            options: HashMap::new(),
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if FLAGS.contains(&name) => {
+                    arguments.options.insert(name.to_string(), None);
+                }
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| CliError::new(format!("Missing value of '--{}'", name)))?;
+                    arguments.options.insert(name.to_string(), Some(value));
+                }
+                None => arguments.positional.push(arg),
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    /// Returns the positional argument 'index', called 'what' in errors.
+    pub(crate) fn positional(&self, index: usize, what: &str) -> Result<&str, CliError> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| CliError::new(format!("Missing {}", what)))
+    }
+
+    /// Returns the value of the option 'name', if it was given.
+    pub(crate) fn option<V: FromStr>(&self, name: &str) -> Result<Option<V>, CliError> {
+        match self.options.get(name) {
+            Some(Some(value)) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| CliError::new(format!("Invalid value of '--{}': {}", name, value))),
+            _ => Ok(None),
+        }
+    }
+
+    pub(crate) fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    /// Checks that there are at most 'positional' positional arguments, and no other options
+    /// than 'options'.
+    pub(crate) fn expect(&self, positional: usize, options: &[&str]) -> Result<(), CliError> {
+        if let Some(extra) = self.positional.get(positional) {
+            return Err(CliError::new(format!("Unexpected argument '{}'", extra)));
+        }
+
+        match self
+            .options
+            .keys()
+            .find(|name| !options.contains(&name.as_str()))
+        {
+            Some(name) => Err(CliError::new(format!("Unknown option '--{}'", name))),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::BufWriter;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::slice;
+use std::str::FromStr;
+use std::time::Instant;
+
+use super::arguments::Arguments;
+use super::CliError;
+use crate::abstractions::Agent;
+use crate::abstractions::Notation;
+use crate::agents::AgentConfig;
+use crate::agents::AlphabetaAgent;
+use crate::agents::HumanPlayer;
+use crate::agents::MctsUctAgent;
+use crate::agents::MinmaxAgent;
+use crate::record::play_recorded;
+use crate::record::GameRecord;
+use crate::record::GameResult;
+use crate::record::RecordWriter;
+use crate::runner::play_paired_openings;
+use crate::runner::EloEstimate;
+use crate::runner::Wdl;
+use crate::validation::divide;
+use crate::validation::perft;
+
+/// Reward of the searching agents, like the one given to 'AlphabetaAgent::new'.
+type RewardFn<AgentId, T> = dyn Fn(&T, &AgentId) -> f64;
+
+/// Commands of a registered game, without its types.
+pub(crate) trait CliGame {
+    fn name(&self) -> &str;
+
+    /// Runs 'command', whose game is the second positional argument.
+    fn run(&self, command: &str, arguments: &Arguments) -> Result<(), CliError>;
+}
+
+/// A game to register with 'Cli': its name, the agents playing it, and the reward of the
+/// searching agents. Positions are read and written with 'Notation', shown to humans with
+/// 'Display', and actions and agents are read with 'FromStr'.
+pub struct GameEntry<Action, AgentId, T> {
+    name: String,
+    sides: (AgentId, AgentId),
+    reward: Box<RewardFn<AgentId, T>>,
+    _action: PhantomData<Action>,
+}
+
+impl<Action, AgentId, T> GameEntry<Action, AgentId, T> {
+    pub fn new(
+        name: &str,
+        sides: (AgentId, AgentId),
+        reward: impl Fn(&T, &AgentId) -> f64 + 'static,
+    ) -> Self {
+        GameEntry {
+            name: name.to_string(),
+            sides,
+            reward: Box::new(reward),
+            _action: PhantomData,
+        }
+    }
+}
+
+impl<Action, AgentId, T> GameEntry<Action, AgentId, T>
+where
+    Action: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+    AgentId: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+    T: Notation<Action, AgentId> + Display + Clone + Eq + Hash + 'static,
+{
+    /// Plays games between two agents, in pairs from the same position with the agents
+    /// swapping sides, and prints the result of every game and the Elo difference of the first
+    /// agent. An odd number of games is rounded up to complete the last pair.
+    fn run_match(&self, arguments: &Arguments) -> Result<(), CliError> {
+        arguments.expect(4, &["games", "position", "record"])?;
+        let configs = (
+            parse_config(arguments.positional(2, "first agent")?)?,
+            parse_config(arguments.positional(3, "second agent")?)?,
+        );
+        let games: usize = arguments.option("games")?.unwrap_or(2);
+        let start = self.start(arguments)?.unwrap_or_else(T::initial_state);
+        let mut file = create(arguments)?;
+
+        // Agents are created for every game, so their configurations are checked once here.
+        self.agent(&configs.0, self.sides.0)?;
+        self.agent(&configs.1, self.sides.1)?;
+        let engine = |config: &AgentConfig, side| {
+            self.agent(config, side)
+                .expect("The configuration was checked before the match")
+        };
+
+        let mut wdl = Wdl::default();
+        let mut game = 0;
+        for _ in 0..games.div_ceil(2) {
+            let paired = play_paired_openings(
+                slice::from_ref(&start),
+                self.sides,
+                |side| engine(&configs.0, side),
+                |side| engine(&configs.1, side),
+            );
+
+            for (record, side) in paired.iter().flat_map(|games| games.games()) {
+                game += 1;
+                let players = if side == self.sides.0 {
+                    configs
+                } else {
+                    (configs.1, configs.0)
+                };
+                let record = record
+                    .clone()
+                    .with_metadata("Game", &self.name)
+                    .with_metadata("Round", &game.to_string())
+                    .with_player_config(&self.sides.0, players.0)
+                    .with_player_config(&self.sides.1, players.1);
+                wdl.add(&Wdl::of(&record.result, &side));
+
+                println!(
+                    "Game {}: {} ({}) - {} ({}): {}",
+                    game,
+                    players.0,
+                    self.sides.0,
+                    players.1,
+                    self.sides.1,
+                    describe(&record.result)
+                );
+                write_record(&mut file, &record)?;
+            }
+        }
+
+        println!("{} against {}: {}", configs.0, configs.1, wdl);
+        if let Some(estimate) = EloEstimate::from_wdl(&wdl) {
+            println!("Elo {}", estimate);
+        }
+        Ok(())
+    }
+
+    /// Plays a game between a human and an agent.
+    fn run_play(&self, arguments: &Arguments) -> Result<(), CliError> {
+        arguments.expect(3, &["side", "position", "record"])?;
+        let engine = parse_config(arguments.positional(2, "agent")?)?;
+        let side = arguments.option("side")?.unwrap_or(self.sides.0);
+        let players = if side == self.sides.0 {
+            (AgentConfig::Human, engine)
+        } else if side == self.sides.1 {
+            (engine, AgentConfig::Human)
+        } else {
+            return Err(CliError::new(format!("Unknown side '{}'", side)));
+        };
+        let start = self.start(arguments)?;
+        let mut file = create(arguments)?;
+
+        let (env, record) = self.play_game(start.as_ref(), players)?;
+        println!("{}", env);
+        println!("Result: {}", describe(&record.result));
+        write_record(&mut file, &record)
+    }
+
+    /// Searches a position with an agent, and prints the action it chose and its evaluation.
+    fn run_analyze(&self, arguments: &Arguments) -> Result<(), CliError> {
+        arguments.expect(3, &["position"])?;
+        let config = parse_config(arguments.positional(2, "agent")?)?;
+        let env = self.start(arguments)?.unwrap_or_else(T::initial_state);
+        if env.is_terminal() {
+            return Err(CliError::new("The position is terminal"));
+        }
+
+        let mut agent = self.agent(&config, env.turn())?;
+        let start = Instant::now();
+        let action = agent.action(&env);
+        let time = start.elapsed();
+
+        match action {
+            Some(action) => println!("Action: {}", action),
+            None => println!("No action"),
+        }
+        if let Some((score, depth)) = agent.last_evaluation() {
+            println!("Score: {} at depth {}", score, depth);
+        }
+        println!("Time: {:?}", time);
+        Ok(())
+    }
+
+    /// Counts the positions reached from a position, in total or under every action.
+    fn run_perft(&self, arguments: &Arguments) -> Result<(), CliError> {
+        arguments.expect(3, &["position", "divide"])?;
+        let depth = arguments.positional(2, "depth")?;
+        let depth: usize = depth
+            .parse()
+            .map_err(|_| CliError::new(format!("Invalid depth '{}'", depth)))?;
+        let env = self.start(arguments)?.unwrap_or_else(T::initial_state);
+
+        let start = Instant::now();
+        if arguments.flag("divide") {
+            let counts = divide(&env, depth);
+            for (action, count) in &counts {
+                println!("{} {}", action, count);
+            }
+            println!(
+                "Total {}",
+                counts.iter().map(|(_, count)| count).sum::<u64>()
+            );
+        } else {
+            for (plies, count) in perft(&env, depth).iter().enumerate() {
+                println!("{:>3} {:>15}", plies, count);
+            }
+        }
+        println!("Time: {:?}", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the position given with '--position', if any.
+    fn start(&self, arguments: &Arguments) -> Result<Option<T>, CliError> {
+        match arguments.option::<String>("position")? {
+            Some(notation) => T::from_notation(&notation)
+                .map(Some)
+                .ok_or_else(|| CliError::new(format!("Invalid position '{}'", notation))),
+            None => Ok(None),
+        }
+    }
+
+    /// Creates the agent of 'config'. Options needing extensions of the environment can't be
+    /// enabled for any game, so they are refused.
+    fn agent<'a>(
+        &'a self,
+        config: &AgentConfig,
+        agent_id: AgentId,
+    ) -> Result<Box<dyn Agent<Action, AgentId, T> + 'a>, CliError> {
+        let agent: Box<dyn Agent<Action, AgentId, T> + 'a> = match config {
+            AgentConfig::Human => Box::new(HumanPlayer::new(agent_id)),
+            AgentConfig::Minmax(minmax) if minmax.quiescence.is_none() => {
+                Box::new(MinmaxAgent::from_config(agent_id, &*self.reward, minmax))
+            }
+            AgentConfig::Alphabeta(alphabeta)
+                if alphabeta.quiescence.is_none()
+                    && alphabeta.null_move.is_none()
+                    && !alphabeta.symmetries =>
+            {
+                Box::new(AlphabetaAgent::from_config(
+                    agent_id,
+                    &*self.reward,
+                    alphabeta,
+                ))
+            }
+            AgentConfig::MctsUct(mcts) => Box::new(MctsUctAgent::from_config(agent_id, mcts)),
+            _ => {
+                return Err(CliError::new(format!(
+                    "The agent '{}' can't be used from the command line",
+                    config
+                )))
+            }
+        };
+        Ok(agent)
+    }
+
+    /// Plays a game between the agents of 'players', from 'start' or the initial state, and
+    /// returns the final position and the record.
+    fn play_game(
+        &self,
+        start: Option<&T>,
+        players: (AgentConfig, AgentConfig),
+    ) -> Result<(T, GameRecord<AgentId, Action>), CliError> {
+        let mut agent_1 = self.agent(&players.0, self.sides.0)?;
+        let mut agent_2 = self.agent(&players.1, self.sides.1)?;
+
+        let mut env = start.cloned().unwrap_or_else(T::initial_state);
+        let mut record = play_recorded(&mut env, &mut agent_1, &mut agent_2);
+        if let Some(start) = start {
+            record = record.with_start(&start.to_notation());
+        }
+
+        let record = record
+            .with_metadata("Game", &self.name)
+            .with_player_config(&self.sides.0, players.0)
+            .with_player_config(&self.sides.1, players.1);
+        Ok((env, record))
+    }
+}
+
+impl<Action, AgentId, T> CliGame for GameEntry<Action, AgentId, T>
+where
+    Action: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+    AgentId: FromStr + Display + Debug + Copy + Eq + Hash + 'static,
+    T: Notation<Action, AgentId> + Display + Clone + Eq + Hash + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, command: &str, arguments: &Arguments) -> Result<(), CliError> {
+        match command {
+            "match" => self.run_match(arguments),
+            "play" => self.run_play(arguments),
+            "analyze" => self.run_analyze(arguments),
+            "perft" => self.run_perft(arguments),
+            other => Err(CliError::new(format!("Unknown command '{}'", other))),
+        }
+    }
+}
+
+fn parse_config(spec: &str) -> Result<AgentConfig, CliError> {
+    spec.parse()
+        .map_err(|error| CliError::new(format!("Invalid agent '{}': {}", spec, error)))
+}
+
+fn describe<AgentId: Display>(result: &GameResult<AgentId>) -> String {
+    match result {
+        GameResult::Win(winner) => format!("win {}", winner),
+        GameResult::Draw => "draw".to_string(),
+        GameResult::Unfinished => "unfinished".to_string(),
+    }
+}
+
+/// Creates the file given with '--record', if any.
+fn create(arguments: &Arguments) -> Result<Option<BufWriter<File>>, CliError> {
+    match arguments.option::<String>("record")? {
+        Some(path) => Ok(Some(BufWriter::new(File::create(path)?))),
+        None => Ok(None),
+    }
+}
+
+/// Writes 'record' to 'file', followed by an empty line separating it from the next one.
+fn write_record<AgentId, Action>(
+    file: &mut Option<BufWriter<File>>,
+    record: &GameRecord<AgentId, Action>,
+) -> Result<(), CliError>
+where
+    AgentId: Display,
+    Action: Display,
+{
+    if let Some(file) = file {
+        let mut writer = RecordWriter::new(Vec::new());
+        writer.write_record(record)?;
+        file.write_all(&writer.into_inner())?;
+        writeln!(file)?;
+        file.flush()?;
+    }
+    Ok(())
+}
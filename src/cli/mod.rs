@@ -0,0 +1,8 @@
+mod app;
+pub use self::app::Cli;
+pub use self::app::CliError;
+
+mod arguments;
+
+mod game_entry;
+pub use self::game_entry::GameEntry;
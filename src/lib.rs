@@ -1,4 +1,10 @@
 pub mod abstractions;
 pub mod agents;
 pub mod cache;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod record;
+pub mod runner;
+pub mod tablebase;
 pub mod tree_search;
+pub mod validation;
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::abstractions::play_until;
+use crate::abstractions::Agent;
+use crate::abstractions::Environment;
+use crate::agents::AgentConfig;
+
+/// A player of a recorded game.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Player<AgentId> {
+    pub agent_id: AgentId,
+    pub name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub config: Option<AgentConfig>,
+}
+
+/// An action of a recorded game, with the time the agent took to produce it and, for agents
+/// that search, the value and depth of the search.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Move<AgentId, Action> {
+    pub agent_id: AgentId,
+    pub action: Action,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub score: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub depth: Option<usize>,
+}
+
+impl<AgentId, Action> Move<AgentId, Action> {
+    pub(crate) fn new(
+        agent_id: AgentId,
+        action: Action,
+        time: Duration,
+        evaluation: Option<(f64, usize)>,
+    ) -> Self {
+        Move {
+            agent_id,
+            action,
+            time: Some(time),
+            score: evaluation.map(|(score, _)| score),
+            depth: evaluation.map(|(_, depth)| depth),
+        }
+    }
+}
+
+/// How a recorded game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GameResult<AgentId> {
+    Win(AgentId),
+    Draw,
+    /// The game stopped before reaching a terminal position.
+    Unfinished,
+}
+
+impl<AgentId> GameResult<AgentId> {
+    /// Returns the result of a game that stopped in 'env'.
+    pub fn of<Action, T>(env: &T) -> Self
+    where
+        T: Environment<Action, AgentId>,
+    {
+        match env.winner() {
+            Some(winner) => GameResult::Win(winner),
+            None if env.is_terminal() => GameResult::Draw,
+            None => GameResult::Unfinished,
+        }
+    }
+
+    /// Returns the points of 'agent_id': one for a win and a half for a draw. Unfinished
+    /// games count as draws.
+    pub fn points(&self, agent_id: &AgentId) -> f64
+    where
+        AgentId: PartialEq,
+    {
+        match self {
+            GameResult::Win(winner) if winner == agent_id => 1f64,
+            GameResult::Win(_) => 0f64,
+            GameResult::Draw | GameResult::Unfinished => 0.5,
+        }
+    }
+}
+
+/// Record of a game: free-form metadata, like the event or the date, the players, the
+/// position the game started in, when it is not the initial state, the actions in the order
+/// they were played, and the result.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameRecord<AgentId, Action> {
+    pub metadata: BTreeMap<String, String>,
+    pub players: Vec<Player<AgentId>>,
+    /// Starting position, written with 'Notation'. None for the initial state.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub start: Option<String>,
+    pub moves: Vec<Move<AgentId, Action>>,
+    pub result: GameResult<AgentId>,
+}
+
+impl<AgentId, Action> GameRecord<AgentId, Action>
+where
+    AgentId: PartialEq,
+{
+    /// Creates the record of an unfinished game without actions.
+    pub fn new(players: Vec<AgentId>) -> Self {
+        GameRecord {
+            metadata: BTreeMap::new(),
+            players: players
+                .into_iter()
+                .map(|agent_id| Player {
+                    agent_id,
+                    name: None,
+                    config: None,
+                })
+                .collect(),
+            start: None,
+            moves: Vec::new(),
+            result: GameResult::Unfinished,
+        }
+    }
+
+    /// Sets a metadata entry.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the starting position, written with 'Notation'.
+    pub fn with_start(mut self, notation: &str) -> Self {
+        self.start = Some(notation.to_string());
+        self
+    }
+
+    /// Names the player 'agent_id'.
+    pub fn with_player_name(mut self, agent_id: &AgentId, name: &str) -> Self {
+        for player in &mut self.players {
+            if player.agent_id == *agent_id {
+                player.name = Some(name.to_string());
+            }
+        }
+        self
+    }
+
+    /// Sets the configuration of the player 'agent_id'.
+    pub fn with_player_config(mut self, agent_id: &AgentId, config: AgentConfig) -> Self {
+        for player in &mut self.players {
+            if player.agent_id == *agent_id {
+                player.config = Some(config);
+            }
+        }
+        self
+    }
+
+    /// Returns the actions played, as returned by 'play'.
+    pub fn log(&self) -> Vec<(AgentId, Action)>
+    where
+        AgentId: Copy,
+        Action: Copy,
+    {
+        self.moves.iter().map(|m| (m.agent_id, m.action)).collect()
+    }
+
+    /// Returns the time 'agent_id' took to produce all its actions, as far as it was recorded.
+    pub fn total_time(&self, agent_id: &AgentId) -> Duration {
+        self.moves
+            .iter()
+            .filter(|m| m.agent_id == *agent_id)
+            .filter_map(|m| m.time)
+            .sum()
+    }
+}
+
+/// Plays a game like 'play', and returns its record.
+pub fn play_recorded<Action, AgentId, T, R, S>(
+    env: &mut T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+) -> GameRecord<AgentId, Action>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    let mut record = GameRecord::new(vec![agent_1.identity(), agent_2.identity()]);
+
+    record.moves = play_until(env, agent_1, agent_2, |_, _| false);
+    record.result = GameResult::of(env);
+
+    record
+}
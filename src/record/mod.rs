@@ -0,0 +1,17 @@
+mod game_record;
+pub use self::game_record::play_recorded;
+pub use self::game_record::GameRecord;
+pub use self::game_record::GameResult;
+pub use self::game_record::Move;
+pub use self::game_record::Player;
+
+mod replay;
+pub use self::replay::play_written;
+pub use self::replay::replay;
+pub use self::replay::resume;
+pub use self::replay::start_position;
+
+mod text;
+pub use self::text::parse_record;
+pub use self::text::RecordError;
+pub use self::text::RecordWriter;
@@ -0,0 +1,123 @@
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+
+use crate::abstractions::play_until;
+use crate::abstractions::Agent;
+use crate::abstractions::Environment;
+use crate::abstractions::Notation;
+
+use super::GameRecord;
+use super::GameResult;
+use super::RecordError;
+use super::RecordWriter;
+
+/// Returns the position the game of 'record' started in: its 'start', or the initial state.
+pub fn start_position<Action, AgentId, T>(
+    record: &GameRecord<AgentId, Action>,
+) -> Result<T, RecordError>
+where
+    T: Notation<Action, AgentId>,
+{
+    match &record.start {
+        Some(notation) => T::from_notation(notation).ok_or(RecordError::InvalidStart),
+        None => Ok(T::initial_state()),
+    }
+}
+
+/// Plays the actions of 'record' from 'start', checking that every one is valid and played in
+/// turn, and that the game ends with the recorded result. Returns the position reached.
+/// 'start' is usually 'Environment::initial_state', or the one given by 'start_position'.
+pub fn replay<Action, AgentId, T>(
+    record: &GameRecord<AgentId, Action>,
+    start: T,
+) -> Result<T, RecordError>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    let mut env = start;
+
+    for (ply, m) in record.moves.iter().enumerate() {
+        if env.turn() != m.agent_id {
+            return Err(RecordError::WrongTurn { ply });
+        }
+        if !env.is_valid(&m.action) || !env.update(&m.action) {
+            return Err(RecordError::InvalidAction { ply });
+        }
+    }
+
+    if GameResult::of(&env) != record.result {
+        return Err(RecordError::ResultMismatch);
+    }
+
+    Ok(env)
+}
+
+/// Resumes the game of 'record', usually an unfinished one, and plays it until it ends. The
+/// game started in 'start', as in 'replay'. Returns the position reached and the record of the
+/// whole game.
+pub fn resume<Action, AgentId, T, R, S>(
+    mut record: GameRecord<AgentId, Action>,
+    start: T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+) -> Result<(T, GameRecord<AgentId, Action>), RecordError>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    let mut env = replay(&record, start)?;
+
+    let moves = play_until(&mut env, agent_1, agent_2, |_, _| false);
+    record.moves.extend(moves);
+    record.result = GameResult::of(&env);
+
+    Ok((env, record))
+}
+
+/// Plays the game of 'record' in 'env' until it ends, like 'resume', writing the record to
+/// 'writer' as the game goes: its header and actions first, every action as soon as it is
+/// played, and the result at the end. 'env' must be the position the actions of 'record'
+/// lead to, like the initial state for a record without actions.
+pub fn play_written<Action, AgentId, T, R, S, W>(
+    env: &mut T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+    mut record: GameRecord<AgentId, Action>,
+    writer: &mut RecordWriter<W>,
+) -> io::Result<GameRecord<AgentId, Action>>
+where
+    Action: Display,
+    AgentId: Eq + Display,
+    T: Environment<Action, AgentId>,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+    W: Write,
+{
+    writer.write_header(&record)?;
+    for m in &record.moves {
+        writer.write_move(m)?;
+    }
+    writer.flush()?;
+
+    // The game stops at the first error writing it.
+    let mut result = Ok(());
+    let moves = play_until(env, agent_1, agent_2, |_, m| {
+        if let Some(m) = m {
+            result = writer.write_move(m).and_then(|_| writer.flush());
+        }
+        result.is_err()
+    });
+    result?;
+
+    record.moves.extend(moves);
+    record.result = GameResult::of(env);
+
+    writer.write_result(&record.result)?;
+    writer.flush()?;
+
+    Ok(record)
+}
@@ -0,0 +1,402 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::GameRecord;
+use super::GameResult;
+use super::Move;
+use super::Player;
+
+/// Error reading or replaying a game record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    /// The line 'line', counting from 1, could not be parsed.
+    Syntax { line: usize, message: String },
+    /// The action in place 'ply', counting from 0, is not valid in the position reached.
+    InvalidAction { ply: usize },
+    /// The action in place 'ply' was played by an agent whose turn it was not.
+    WrongTurn { ply: usize },
+    /// The recorded result is not the one of the position reached.
+    ResultMismatch,
+    /// The starting position can't be read with 'Notation'.
+    InvalidStart,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Syntax { line, message } => write!(f, "Line {}: {}", line, message),
+            RecordError::InvalidAction { ply } => write!(f, "Invalid action at ply {}", ply),
+            RecordError::WrongTurn { ply } => write!(f, "Action out of turn at ply {}", ply),
+            RecordError::ResultMismatch => write!(f, "The result does not match the game"),
+            RecordError::InvalidStart => write!(f, "Invalid starting position"),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Writes game records in the text format, as a whole or while the game is played.
+///
+/// The format has one entry per line. Tags are written '[Key "value"]', or '[Key agent
+/// "value"]' for the tags of a player: 'Player' for its name and 'Config' for its agent spec.
+/// The result is the tag 'Result', with value 'win <agent>', 'draw', or '*' for unfinished
+/// games, and the starting position the tag 'Position'. Every other tag is metadata, whose
+/// keys can't hold whitespace or quotes. In values, quotes, backslashes and line breaks are
+/// escaped with a backslash, as in '\"', '\\' and '\n'.
+///
+/// Actions are written '<agent> <action>', optionally followed by annotations like
+/// '{score=0.5 depth=6 time=0.010250000}', with the time in seconds. Scores of very large or
+/// small magnitude are written in scientific notation, like '1.7976931348623157e308'. Agents
+/// and actions are written with 'Display', and read with 'FromStr'. Empty lines and lines
+/// starting with ';' are ignored.
+pub struct RecordWriter<W> {
+    writer: W,
+}
+
+impl<W> RecordWriter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        RecordWriter { writer }
+    }
+
+    /// Writes the metadata and the players of 'record'. Fails with 'InvalidInput' if a
+    /// metadata key is empty, holds whitespace or quotes, or is the name of another tag.
+    pub fn write_header<AgentId, Action>(
+        &mut self,
+        record: &GameRecord<AgentId, Action>,
+    ) -> io::Result<()>
+    where
+        AgentId: Display,
+    {
+        if let Some(key) = record.metadata.keys().find(|key| !is_metadata_key(key)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid metadata key '{}'", key),
+            ));
+        }
+
+        for (key, value) in &record.metadata {
+            writeln!(self.writer, "[{} \"{}\"]", key, escape(value))?;
+        }
+
+        if let Some(start) = &record.start {
+            writeln!(self.writer, "[Position \"{}\"]", escape(start))?;
+        }
+
+        for player in &record.players {
+            if let Some(name) = &player.name {
+                writeln!(
+                    self.writer,
+                    "[Player {} \"{}\"]",
+                    player.agent_id,
+                    escape(name)
+                )?;
+            }
+            if let Some(config) = &player.config {
+                writeln!(
+                    self.writer,
+                    "[Config {} \"{}\"]",
+                    player.agent_id,
+                    escape(&config.to_string())
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes an action with its annotations.
+    pub fn write_move<AgentId, Action>(&mut self, m: &Move<AgentId, Action>) -> io::Result<()>
+    where
+        AgentId: Display,
+        Action: Display,
+    {
+        let mut annotations = Vec::new();
+        if let Some(score) = m.score {
+            annotations.push(format!("score={}", format_score(score)));
+        }
+        if let Some(depth) = m.depth {
+            annotations.push(format!("depth={}", depth));
+        }
+        if let Some(time) = m.time {
+            annotations.push(format!(
+                "time={}.{:09}",
+                time.as_secs(),
+                time.subsec_nanos()
+            ));
+        }
+
+        if annotations.is_empty() {
+            writeln!(self.writer, "{} {}", m.agent_id, m.action)
+        } else {
+            writeln!(
+                self.writer,
+                "{} {} {{{}}}",
+                m.agent_id,
+                m.action,
+                annotations.join(" ")
+            )
+        }
+    }
+
+    /// Writes the result of the game.
+    pub fn write_result<AgentId>(&mut self, result: &GameResult<AgentId>) -> io::Result<()>
+    where
+        AgentId: Display,
+    {
+        match result {
+            GameResult::Win(winner) => writeln!(self.writer, "[Result \"win {}\"]", winner),
+            GameResult::Draw => writeln!(self.writer, "[Result \"draw\"]"),
+            GameResult::Unfinished => writeln!(self.writer, "[Result \"*\"]"),
+        }
+    }
+
+    /// Writes a whole record.
+    pub fn write_record<AgentId, Action>(
+        &mut self,
+        record: &GameRecord<AgentId, Action>,
+    ) -> io::Result<()>
+    where
+        AgentId: Display,
+        Action: Display,
+    {
+        self.write_header(record)?;
+        self.write_result(&record.result)?;
+        for m in &record.moves {
+            self.write_move(m)?;
+        }
+        self.writer.flush()
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Parses a game record in the text format described in 'RecordWriter'. Players appear in
+/// the order in which their first tag or action does.
+pub fn parse_record<AgentId, Action>(text: &str) -> Result<GameRecord<AgentId, Action>, RecordError>
+where
+    AgentId: FromStr + PartialEq + Clone,
+    Action: FromStr,
+{
+    let mut record = GameRecord::new(Vec::new());
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let syntax = |message: &str| RecordError::Syntax {
+            line: index + 1,
+            message: message.to_string(),
+        };
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        } else if line.starts_with('[') {
+            parse_tag(line, &mut record).map_err(syntax)?;
+        } else {
+            let m: Move<AgentId, Action> = parse_move(line).map_err(syntax)?;
+            player(&mut record, m.agent_id.clone());
+            record.moves.push(m);
+        }
+    }
+
+    Ok(record)
+}
+
+/// Parses a tag line into 'record'.
+fn parse_tag<AgentId, Action>(
+    line: &str,
+    record: &mut GameRecord<AgentId, Action>,
+) -> Result<(), &'static str>
+where
+    AgentId: FromStr + PartialEq,
+{
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .ok_or("Tags must be enclosed in brackets")?;
+    let quote = inner.find('"').ok_or("Tag values must be quoted")?;
+    let value = unescape(&inner[quote..])?;
+    let mut words = inner[..quote].split_whitespace();
+    let key = words.next().ok_or("Missing tag name")?;
+    let agent = words.next();
+    if words.next().is_some() {
+        return Err("Too many words in tag");
+    }
+
+    match (key, agent) {
+        ("Result", None) => {
+            record.result = match value.as_str() {
+                "draw" => GameResult::Draw,
+                "*" => GameResult::Unfinished,
+                other => {
+                    let winner = other.strip_prefix("win ").ok_or("Invalid result")?;
+                    GameResult::Win(winner.trim().parse().map_err(|_| "Invalid winner")?)
+                }
+            }
+        }
+        ("Position", None) => record.start = Some(value),
+        ("Player", Some(agent)) => {
+            player(record, agent.parse().map_err(|_| "Invalid agent")?).name = Some(value)
+        }
+        ("Config", Some(agent)) => {
+            let config = value.parse().map_err(|_| "Invalid agent config")?;
+            player(record, agent.parse().map_err(|_| "Invalid agent")?).config = Some(config)
+        }
+        (_, Some(_)) => return Err("Unknown player tag"),
+        (key, None) => {
+            record.metadata.insert(key.to_string(), value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses an action line.
+fn parse_move<AgentId, Action>(line: &str) -> Result<Move<AgentId, Action>, &'static str>
+where
+    AgentId: FromStr,
+    Action: FromStr,
+{
+    let (agent, rest) = line
+        .split_once(char::is_whitespace)
+        .ok_or("Actions must follow the agent playing them")?;
+    let (action, annotations) = match rest.split_once('{') {
+        Some((action, annotations)) => (
+            action,
+            annotations
+                .trim_end()
+                .strip_suffix('}')
+                .ok_or("Unclosed annotations")?,
+        ),
+        None => (rest, ""),
+    };
+
+    let mut m = Move {
+        agent_id: agent.parse().map_err(|_| "Invalid agent")?,
+        action: action.trim().parse().map_err(|_| "Invalid action")?,
+        time: None,
+        score: None,
+        depth: None,
+    };
+
+    for annotation in annotations.split_whitespace() {
+        let (key, value) = annotation
+            .split_once('=')
+            .ok_or("Annotations must be 'key=value'")?;
+        match key {
+            "score" => m.score = Some(value.parse().map_err(|_| "Invalid score")?),
+            "depth" => m.depth = Some(value.parse().map_err(|_| "Invalid depth")?),
+            "time" => m.time = Some(parse_seconds(value).ok_or("Invalid time")?),
+            // Annotations from newer writers are skipped.
+            _ => {}
+        }
+    }
+
+    Ok(m)
+}
+
+/// Returns the player 'agent_id' of 'record', adding it if needed.
+fn player<AgentId, Action>(
+    record: &mut GameRecord<AgentId, Action>,
+    agent_id: AgentId,
+) -> &mut Player<AgentId>
+where
+    AgentId: PartialEq,
+{
+    let index = match record.players.iter().position(|p| p.agent_id == agent_id) {
+        Some(index) => index,
+        None => {
+            record.players.push(Player {
+                agent_id,
+                name: None,
+                config: None,
+            });
+            record.players.len() - 1
+        }
+    };
+
+    &mut record.players[index]
+}
+
+/// Parses a number of seconds written with up to nine decimals, without rounding.
+fn parse_seconds(value: &str) -> Option<Duration> {
+    let (secs, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let nanos = format!("{:0<9}", fraction).parse().ok()?;
+    Some(Duration::new(secs.parse().ok()?, nanos))
+}
+
+/// Names of the tags that are not metadata.
+const RESERVED_KEYS: [&str; 4] = ["Result", "Position", "Player", "Config"];
+
+/// Returns true iff 'key' can be written as the key of a metadata tag, and read back as one.
+fn is_metadata_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.chars().any(|c| c.is_whitespace() || c == '"')
+        && !RESERVED_KEYS.contains(&key)
+}
+
+/// Writes 'score' like 'Display' does, or in scientific notation when that would be long.
+fn format_score(score: f64) -> String {
+    let magnitude = score.abs();
+    if magnitude == 0f64 || !magnitude.is_finite() || (1e-5..1e16).contains(&magnitude) {
+        score.to_string()
+    } else {
+        format!("{:e}", score)
+    }
+}
+
+/// Escapes the quotes, backslashes and line breaks of a tag value, so that it fits on a line.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reads a quoted tag value, which must end the tag.
+fn unescape(quoted: &str) -> Result<String, &'static str> {
+    let mut chars = quoted.chars().skip(1);
+    let mut value = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next().ok_or("Unfinished escape")? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                c => value.push(c),
+            },
+            '"' => {
+                return match chars.next() {
+                    None => Ok(value),
+                    Some(_) => Err("Text after the tag value"),
+                }
+            }
+            c => value.push(c),
+        }
+    }
+
+    Err("Unclosed tag value")
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+
+use crate::record::GameResult;
+
+/// Wins, draws and losses of an agent against another one, or against the whole field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Wdl {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Wdl {
+    /// Returns the result of a single game seen from 'agent_id'. Unfinished games count as
+    /// draws.
+    pub fn of<AgentId>(result: &GameResult<AgentId>, agent_id: &AgentId) -> Self
+    where
+        AgentId: PartialEq,
+    {
+        let points = result.points(agent_id);
+        Wdl {
+            wins: u32::from(points == 1f64),
+            draws: u32::from(points == 0.5),
+            losses: u32::from(points == 0f64),
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns one point per win and a half per draw.
+    pub fn points(&self) -> f64 {
+        f64::from(self.wins) + f64::from(self.draws) / 2f64
+    }
+
+    /// Returns the same results seen from the opponent.
+    pub fn flip(&self) -> Self {
+        Wdl {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+
+    /// Adds the results in 'other'.
+    pub fn add(&mut self, other: &Wdl) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+}
+
+impl fmt::Display for Wdl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{}={}-{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// Results of every pairing of a tournament. The cell in row 'i' and column 'j' holds the
+/// results of entrant 'i' against entrant 'j'.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crosstable {
+    names: Vec<String>,
+    cells: Vec<Vec<Wdl>>,
+}
+
+impl Crosstable {
+    /// Creates an empty crosstable for the entrants in 'names'.
+    pub fn new(names: Vec<String>) -> Self {
+        let len = names.len();
+        Crosstable {
+            names,
+            cells: vec![vec![Wdl::default(); len]; len],
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the number of entrants.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the results of entrant 'i' against entrant 'j'.
+    pub fn get(&self, i: usize, j: usize) -> Wdl {
+        self.cells[i][j]
+    }
+
+    /// Adds the results of entrant 'i' against entrant 'j', and the opposite ones to 'j'.
+    pub fn add(&mut self, i: usize, j: usize, wdl: &Wdl) {
+        self.cells[i][j].add(wdl);
+        self.cells[j][i].add(&wdl.flip());
+    }
+
+    /// Returns the results of entrant 'i' against the whole field.
+    pub fn total(&self, i: usize) -> Wdl {
+        let mut total = Wdl::default();
+        for wdl in &self.cells[i] {
+            total.add(wdl);
+        }
+        total
+    }
+}
+
+/// Prints a table with a row per entrant, a column per opponent, and the totals.
+impl fmt::Display for Crosstable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = (0..self.len())
+            .map(|i| {
+                (0..self.len())
+                    .map(|j| {
+                        if i == j {
+                            "-".to_string()
+                        } else if self.get(i, j).games() == 0 {
+                            String::new()
+                        } else {
+                            self.get(i, j).to_string()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let name_width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(4);
+        let cell_width = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.len())
+            .max()
+            .unwrap_or(0)
+            .max(self.len().to_string().len() + 1);
+
+        write!(
+            f,
+            "{:>3}  {:<name_width$}",
+            "#",
+            "Name",
+            name_width = name_width
+        )?;
+        for j in 0..self.len() {
+            write!(f, "  {:>cell_width$}", j + 1, cell_width = cell_width)?;
+        }
+        writeln!(f, "  {:>12}  {:>7}", "Total", "Points")?;
+
+        for (i, row) in cells.iter().enumerate() {
+            let total = self.total(i);
+            write!(
+                f,
+                "{:>3}  {:<name_width$}",
+                i + 1,
+                self.names[i],
+                name_width = name_width
+            )?;
+            for cell in row {
+                write!(f, "  {:>cell_width$}", cell, cell_width = cell_width)?;
+            }
+            writeln!(f, "  {:>12}  {:>7.1}", total.to_string(), total.points())?;
+        }
+
+        Ok(())
+    }
+}
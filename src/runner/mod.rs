@@ -0,0 +1,26 @@
+mod crosstable;
+pub use self::crosstable::Crosstable;
+pub use self::crosstable::Wdl;
+
+mod openings;
+pub use self::openings::play_from;
+pub use self::openings::play_paired_openings;
+pub use self::openings::PairedGames;
+
+mod rating;
+pub use self::rating::elo_from_score;
+pub use self::rating::score_from_elo;
+pub use self::rating::EloEstimate;
+pub use self::rating::Rating;
+pub use self::rating::Ratings;
+
+mod sprt;
+pub use self::sprt::Sprt;
+pub use self::sprt::SprtStatus;
+
+mod tournament;
+pub use self::tournament::AgentFactory;
+pub use self::tournament::Pairings;
+pub use self::tournament::Tournament;
+pub use self::tournament::TournamentGame;
+pub use self::tournament::TournamentResult;
@@ -0,0 +1,84 @@
+use super::Wdl;
+use crate::abstractions::Agent;
+use crate::abstractions::Notation;
+use crate::record::play_recorded;
+use crate::record::GameRecord;
+
+/// Plays a game starting in 'start'. The record keeps the starting position, so that it can
+/// be replayed from 'start_position'.
+pub fn play_from<Action, AgentId, T, R, S>(
+    start: &T,
+    agent_1: &mut R,
+    agent_2: &mut S,
+) -> GameRecord<AgentId, Action>
+where
+    AgentId: Eq,
+    T: Notation<Action, AgentId> + Clone,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    let mut env = start.clone();
+    play_recorded(&mut env, agent_1, agent_2).with_start(&start.to_notation())
+}
+
+/// The two games of an opening played with colours swapped. Engine 1 plays 'sides.0' in
+/// 'first', and 'sides.1' in 'swapped'.
+#[derive(Clone, Debug)]
+pub struct PairedGames<AgentId, Action> {
+    pub sides: (AgentId, AgentId),
+    pub first: GameRecord<AgentId, Action>,
+    pub swapped: GameRecord<AgentId, Action>,
+}
+
+impl<AgentId, Action> PairedGames<AgentId, Action>
+where
+    AgentId: Eq,
+{
+    /// Returns the points of engine 1 and engine 2 over both games: one per win and a half
+    /// per draw. Unfinished games count as draws.
+    pub fn points(&self) -> (f64, f64) {
+        let points_1 =
+            self.first.result.points(&self.sides.0) + self.swapped.result.points(&self.sides.1);
+        (points_1, 2f64 - points_1)
+    }
+
+    /// Returns both records, each with the side engine 1 played in it.
+    pub fn games(&self) -> [(&GameRecord<AgentId, Action>, AgentId); 2]
+    where
+        AgentId: Copy,
+    {
+        [(&self.first, self.sides.0), (&self.swapped, self.sides.1)]
+    }
+
+    /// Returns the wins, draws and losses of engine 1 over both games.
+    pub fn wdl(&self) -> Wdl {
+        let mut wdl = Wdl::of(&self.first.result, &self.sides.0);
+        wdl.add(&Wdl::of(&self.swapped.result, &self.sides.1));
+        wdl
+    }
+}
+
+/// Plays every opening twice, with the engines swapping sides, so that neither engine gets
+/// the better side of an unbalanced opening. Engines are created for every game by 'engine_1'
+/// and 'engine_2', given the side they play.
+pub fn play_paired_openings<Action, AgentId, T, R, S>(
+    openings: &[T],
+    sides: (AgentId, AgentId),
+    mut engine_1: impl FnMut(AgentId) -> R,
+    mut engine_2: impl FnMut(AgentId) -> S,
+) -> Vec<PairedGames<AgentId, Action>>
+where
+    AgentId: Eq + Copy,
+    T: Notation<Action, AgentId> + Clone,
+    R: Agent<Action, AgentId, T>,
+    S: Agent<Action, AgentId, T>,
+{
+    openings
+        .iter()
+        .map(|opening| PairedGames {
+            sides,
+            first: play_from(opening, &mut engine_1(sides.0), &mut engine_2(sides.1)),
+            swapped: play_from(opening, &mut engine_1(sides.1), &mut engine_2(sides.0)),
+        })
+        .collect()
+}
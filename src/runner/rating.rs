@@ -0,0 +1,404 @@
+use std::f64::consts::LN_10;
+use std::f64::consts::SQRT_2;
+use std::fmt;
+
+use super::Crosstable;
+use super::Wdl;
+
+/// Quantile of the normal distribution bounding 95% confidence intervals.
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// Largest number of iterations of the Bradley–Terry fit.
+const MAX_ITERATIONS: usize = 10_000;
+
+/// Change of the ratings, in natural units, below which the Bradley–Terry fit stops.
+const TOLERANCE: f64 = 1e-10;
+
+/// Returns the Elo difference giving 'score', the expected points per game against the
+/// opponent. Scores of 0 and 1 give infinite differences.
+pub fn elo_from_score(score: f64) -> f64 {
+    -400f64 * (1f64 / score - 1f64).log10()
+}
+
+/// Returns the expected points per game of a player 'elo' points stronger than its opponent.
+pub fn score_from_elo(elo: f64) -> f64 {
+    1f64 / (1f64 + 10f64.powf(-elo / 400f64))
+}
+
+/// Elo difference between two agents estimated from the results of a match, with its 95%
+/// confidence interval and the likelihood of superiority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EloEstimate {
+    pub elo: f64,
+    pub lower: f64,
+    pub upper: f64,
+    /// Probability that the agent is the stronger one, from its wins and losses.
+    pub los: f64,
+}
+
+impl EloEstimate {
+    /// Estimates the Elo difference of the agent whose results are 'wdl'. The interval comes
+    /// from the variance of the game scores observed in the match. Matches won or lost in
+    /// every game give infinite differences, which 'Ratings' avoids with a prior. Returns None
+    /// if no game was played.
+    pub fn from_wdl(wdl: &Wdl) -> Option<Self> {
+        if wdl.games() == 0 {
+            return None;
+        }
+
+        let games = f64::from(wdl.games());
+        let score = wdl.points() / games;
+        let variance = (f64::from(wdl.wins) * (1f64 - score).powi(2)
+            + f64::from(wdl.draws) * (0.5 - score).powi(2)
+            + f64::from(wdl.losses) * score.powi(2))
+            / games;
+        let margin = Z_95 * (variance / games).sqrt();
+
+        let decisive = f64::from(wdl.wins + wdl.losses);
+        let los = if decisive > 0f64 {
+            normal_cdf((f64::from(wdl.wins) - f64::from(wdl.losses)) / decisive.sqrt())
+        } else {
+            0.5
+        };
+
+        Some(EloEstimate {
+            elo: elo_from_score(score),
+            lower: elo_from_score((score - margin).max(0f64)),
+            upper: elo_from_score((score + margin).min(1f64)),
+            los,
+        })
+    }
+}
+
+impl fmt::Display for EloEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:+.1} [{:+.1}, {:+.1}], LOS {:.1}%",
+            self.elo,
+            self.lower,
+            self.upper,
+            100f64 * self.los
+        )
+    }
+}
+
+/// Rating of an entrant, in Elo relative to the average of the field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub name: String,
+    pub elo: f64,
+    /// Half width of the 95% confidence interval of 'elo'.
+    pub margin: f64,
+    pub games: u32,
+    pub points: f64,
+}
+
+/// Bradley–Terry ratings of the entrants of a crosstable, fitted by maximum likelihood over
+/// all the games, so that results against strong and weak opponents are weighed alike. Draws
+/// count as half a win for each side. Intervals come from the curvature of the likelihood.
+///
+/// Only entrants connected by a chain of games can be compared. The ratings of each group of
+/// connected entrants average zero, and entrants without games are rated zero with an
+/// infinite margin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ratings {
+    ratings: Vec<Rating>,
+    /// Covariance of the ratings in squared Elo, or None for pairs of entrants that can't be
+    /// compared.
+    covariance: Vec<Vec<Option<f64>>>,
+}
+
+impl Ratings {
+    /// Fits the ratings of the entrants of 'crosstable'. Every pairing that was played gets
+    /// 'prior' more draws, which keeps the ratings of entrants that won or lost all their
+    /// games finite. One draw is a usual choice.
+    ///
+    /// # Panics
+    ///
+    /// If 'prior' is not positive.
+    pub fn new(crosstable: &Crosstable, prior: f64) -> Self {
+        assert!(prior > 0f64, "The prior must be positive");
+
+        let len = crosstable.len();
+        let mut games = vec![vec![0f64; len]; len];
+        let mut points = vec![0f64; len];
+        for (i, row) in games.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let wdl = crosstable.get(i, j);
+                if i != j && wdl.games() > 0 {
+                    *cell = f64::from(wdl.games()) + prior;
+                    points[i] += wdl.points() + prior / 2f64;
+                }
+            }
+        }
+
+        let components = components(&games);
+
+        // Minorization-maximization: each strength becomes the points of the entrant divided
+        // by the games it would have won per unit of strength.
+        let mut strength = vec![1f64; len];
+        for _ in 0..MAX_ITERATIONS {
+            let mut updated: Vec<f64> = (0..len)
+                .map(|i| {
+                    let weight: f64 = (0..len)
+                        .map(|j| games[i][j] / (strength[i] + strength[j]))
+                        .sum();
+                    if weight > 0f64 {
+                        points[i] / weight
+                    } else {
+                        strength[i]
+                    }
+                })
+                .collect();
+
+            for component in components.iter() {
+                let mean = component.iter().map(|i| updated[*i].ln()).sum::<f64>()
+                    / component.len() as f64;
+                component.iter().for_each(|i| updated[*i] /= mean.exp());
+            }
+
+            let change = updated
+                .iter()
+                .zip(&strength)
+                .map(|(new, old)| (new.ln() - old.ln()).abs())
+                .fold(0f64, f64::max);
+            strength = updated;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+
+        let scale = 400f64 / LN_10;
+        let mut covariance = vec![vec![None; len]; len];
+        for component in components.iter().filter(|component| component.len() > 1) {
+            if let Some(inverse) = self::covariance(&games, &strength, component) {
+                for (a, i) in component.iter().enumerate() {
+                    for (b, j) in component.iter().enumerate() {
+                        covariance[*i][*j] = Some(inverse[a][b] * scale * scale);
+                    }
+                }
+            }
+        }
+
+        let ratings = (0..len)
+            .map(|i| {
+                let total = crosstable.total(i);
+                Rating {
+                    name: crosstable.names()[i].clone(),
+                    elo: scale * strength[i].ln(),
+                    margin: match covariance[i][i] {
+                        Some(variance) => Z_95 * variance.sqrt(),
+                        None => f64::INFINITY,
+                    },
+                    games: total.games(),
+                    points: total.points(),
+                }
+            })
+            .collect();
+
+        Ratings {
+            ratings,
+            covariance,
+        }
+    }
+
+    /// Returns the ratings in the order of the entrants of the crosstable.
+    pub fn ratings(&self) -> &[Rating] {
+        &self.ratings
+    }
+
+    /// Returns the indices of the entrants from the highest to the lowest rating.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.ratings.len()).collect();
+        ranking.sort_by(|a, b| self.ratings[*b].elo.total_cmp(&self.ratings[*a].elo));
+        ranking
+    }
+
+    /// Returns the likelihood of superiority of entrant 'i' over entrant 'j': the probability
+    /// that 'i' is the stronger one. It is 0.5 when they can't be compared.
+    pub fn los(&self, i: usize, j: usize) -> f64 {
+        let covariance = &self.covariance;
+        match (covariance[i][i], covariance[j][j], covariance[i][j]) {
+            (Some(ii), Some(jj), Some(ij)) => {
+                let variance = ii + jj - 2f64 * ij;
+                let difference = self.ratings[i].elo - self.ratings[j].elo;
+                if variance > 0f64 {
+                    normal_cdf(difference / variance.sqrt())
+                } else {
+                    0.5
+                }
+            }
+            _ => 0.5,
+        }
+    }
+}
+
+/// Prints the entrants from the highest to the lowest rating, with the likelihood of
+/// superiority of each entrant over the next one. Entrants without games have no score, shown
+/// as '-'.
+impl fmt::Display for Ratings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .ratings
+            .iter()
+            .map(|r| r.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        writeln!(
+            f,
+            "{:>4}  {:<name_width$}  {:>7}  {:>6}  {:>6}  {:>6}  {:>6}",
+            "Rank",
+            "Name",
+            "Elo",
+            "+/-",
+            "Games",
+            "Score",
+            "LOS",
+            name_width = name_width
+        )?;
+
+        let ranking = self.ranking();
+        for (rank, i) in ranking.iter().enumerate() {
+            let rating = &self.ratings[*i];
+            let los = match ranking.get(rank + 1) {
+                Some(next) => format!("{:.1}%", 100f64 * self.los(*i, *next)),
+                None => String::new(),
+            };
+            let score = if rating.games > 0 {
+                format!("{:.1}%", 100f64 * rating.points / f64::from(rating.games))
+            } else {
+                String::from("-")
+            };
+            writeln!(
+                f,
+                "{:>4}  {:<name_width$}  {:>+7.1}  {:>6.1}  {:>6}  {:>6}  {:>6}",
+                rank + 1,
+                rating.name,
+                rating.elo,
+                rating.margin,
+                rating.games,
+                score,
+                los,
+                name_width = name_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the groups of entrants connected by a chain of games, each in increasing order.
+fn components(games: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let len = games.len();
+    let mut seen = vec![false; len];
+    let mut components = Vec::new();
+
+    for first in 0..len {
+        if seen[first] {
+            continue;
+        }
+        seen[first] = true;
+        let mut component = vec![first];
+        let mut stack = vec![first];
+        while let Some(i) = stack.pop() {
+            for j in 0..len {
+                if !seen[j] && games[i][j] > 0f64 {
+                    seen[j] = true;
+                    component.push(j);
+                    stack.push(j);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components
+}
+
+/// Returns the covariance of the logarithms of the strengths of the entrants in 'component',
+/// with their sum held at zero, as the pseudo-inverse of the information matrix. Rows and
+/// columns follow the order of 'component'. Returns None if that matrix has more than the
+/// one null direction the sum removes, that is if the games don't connect the entrants.
+fn covariance(games: &[Vec<f64>], strength: &[f64], component: &[usize]) -> Option<Vec<Vec<f64>>> {
+    let len = component.len();
+    let shift = 1f64 / len as f64;
+
+    let mut information = vec![vec![shift; len]; len];
+    for (a, i) in component.iter().enumerate() {
+        for (b, j) in component.iter().enumerate() {
+            if a != b {
+                let p = strength[*i] / (strength[*i] + strength[*j]);
+                let curvature = games[*i][*j] * p * (1f64 - p);
+                information[a][b] -= curvature;
+                information[a][a] += curvature;
+            }
+        }
+    }
+
+    let mut inverse = invert(information)?;
+    inverse
+        .iter_mut()
+        .for_each(|row| row.iter_mut().for_each(|c| *c -= shift));
+    Some(inverse)
+}
+
+/// Inverts a square matrix by Gauss-Jordan elimination. Returns None if it is singular.
+fn invert(mut matrix: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let len = matrix.len();
+    let mut inverse: Vec<Vec<f64>> = (0..len)
+        .map(|i| (0..len).map(|j| f64::from(u8::from(i == j))).collect())
+        .collect();
+
+    for column in 0..len {
+        let pivot = (column..len).max_by(|a, b| {
+            matrix[*a][column]
+                .abs()
+                .total_cmp(&matrix[*b][column].abs())
+        })?;
+        if matrix[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(column, pivot);
+        inverse.swap(column, pivot);
+
+        let divisor = matrix[column][column];
+        matrix[column].iter_mut().for_each(|c| *c /= divisor);
+        inverse[column].iter_mut().for_each(|c| *c /= divisor);
+
+        for row in 0..len {
+            if row != column {
+                let factor = matrix[row][column];
+                for k in 0..len {
+                    matrix[row][k] -= factor * matrix[column][k];
+                    inverse[row][k] -= factor * inverse[column][k];
+                }
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Distribution function of the standard normal distribution.
+pub(crate) fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1f64 + erf(x / SQRT_2))
+}
+
+/// Error function, with an absolute error below 1.5e-7 (Abramowitz and Stegun, 7.1.26).
+fn erf(x: f64) -> f64 {
+    let t = 1f64 / (1f64 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let value = 1f64 - polynomial * (-x * x).exp();
+    if x < 0f64 {
+        -value
+    } else {
+        value
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+use std::slice;
+
+use super::play_paired_openings;
+use super::score_from_elo;
+use super::EloEstimate;
+use super::Wdl;
+use crate::abstractions::Agent;
+use crate::abstractions::Notation;
+use crate::record::GameResult;
+
+/// State of a sequential probability ratio test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtStatus {
+    /// Neither hypothesis is accepted yet: more games are needed.
+    Continue,
+    /// The candidate is not stronger than the baseline by 'elo_1': it is at most 'elo_0'
+    /// stronger.
+    AcceptH0,
+    /// The candidate is at least 'elo_1' stronger than the baseline.
+    AcceptH1,
+}
+
+/// Sequential probability ratio test of a candidate agent against a baseline, deciding
+/// between the hypotheses that the candidate is 'elo_0' (H0) or 'elo_1' (H1) stronger. Games
+/// are added until the log-likelihood ratio leaves the bounds given by the error rates:
+/// 'alpha' is the probability of accepting H1 when H0 holds, and 'beta' the opposite.
+///
+/// The likelihood is the one of a normal approximation of the game scores, with the
+/// variance observed in the games, which takes draws into account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprt {
+    elo_0: f64,
+    elo_1: f64,
+    alpha: f64,
+    beta: f64,
+    wdl: Wdl,
+}
+
+impl Sprt {
+    /// Creates a test without games. Typical bounds are 0 and 5 Elo, with error rates of 0.05.
+    pub fn new(elo_0: f64, elo_1: f64, alpha: f64, beta: f64) -> Self {
+        Sprt {
+            elo_0,
+            elo_1,
+            alpha,
+            beta,
+            wdl: Wdl::default(),
+        }
+    }
+
+    /// Returns the results of the candidate so far.
+    pub fn wdl(&self) -> Wdl {
+        self.wdl
+    }
+
+    /// Adds results of the candidate.
+    pub fn add(&mut self, wdl: &Wdl) {
+        self.wdl.add(wdl);
+    }
+
+    /// Returns the log-likelihood ratios at which H0 and H1 are accepted.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1f64 - self.alpha)).ln(),
+            ((1f64 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Returns the log-likelihood ratio of H1 against H0. The variance of the scores is
+    /// estimated with a win and a loss more than played, so that it isn't zero when all games
+    /// end alike.
+    pub fn llr(&self) -> f64 {
+        let games = f64::from(self.wdl.games());
+        let points = self.wdl.points();
+
+        let wins = f64::from(self.wdl.wins) + 1f64;
+        let draws = f64::from(self.wdl.draws);
+        let losses = f64::from(self.wdl.losses) + 1f64;
+        let score = (points + 1f64) / (games + 2f64);
+        let variance = (wins * (1f64 - score).powi(2)
+            + draws * (0.5 - score).powi(2)
+            + losses * score.powi(2))
+            / (games + 2f64);
+
+        let score_0 = score_from_elo(self.elo_0);
+        let score_1 = score_from_elo(self.elo_1);
+        (score_1 - score_0) * (2f64 * points - games * (score_0 + score_1)) / (2f64 * variance)
+    }
+
+    pub fn status(&self) -> SprtStatus {
+        let llr = self.llr();
+        let (lower, upper) = self.bounds();
+        if llr <= lower {
+            SprtStatus::AcceptH0
+        } else if llr >= upper {
+            SprtStatus::AcceptH1
+        } else {
+            SprtStatus::Continue
+        }
+    }
+
+    /// Plays games from the initial state. See 'run_from'.
+    pub fn run<Action, AgentId, T, R, S>(
+        &mut self,
+        sides: (AgentId, AgentId),
+        baseline: impl FnMut(AgentId) -> R,
+        candidate: impl FnMut(AgentId) -> S,
+        max_games: usize,
+        progress: impl FnMut(&Sprt),
+    ) -> SprtStatus
+    where
+        AgentId: Eq + Copy,
+        T: Notation<Action, AgentId> + Clone,
+        R: Agent<Action, AgentId, T>,
+        S: Agent<Action, AgentId, T>,
+    {
+        self.run_from(
+            &[T::initial_state()],
+            sides,
+            baseline,
+            candidate,
+            max_games,
+            progress,
+        )
+    }
+
+    /// Plays games between the agents created by 'candidate' and 'baseline' for the side they
+    /// play, until a hypothesis is accepted or 'max_games' are played, and returns the final
+    /// status. Every opening is played twice in turn, with the agents swapping sides, so an odd
+    /// 'max_games' is rounded up. 'progress' is called after every game, for example to print
+    /// the test. Games stopped by an agent without an action are unfinished, and left out of
+    /// the test.
+    pub fn run_from<Action, AgentId, T, R, S>(
+        &mut self,
+        openings: &[T],
+        sides: (AgentId, AgentId),
+        mut baseline: impl FnMut(AgentId) -> R,
+        mut candidate: impl FnMut(AgentId) -> S,
+        max_games: usize,
+        mut progress: impl FnMut(&Sprt),
+    ) -> SprtStatus
+    where
+        AgentId: Eq + Copy,
+        T: Notation<Action, AgentId> + Clone,
+        R: Agent<Action, AgentId, T>,
+        S: Agent<Action, AgentId, T>,
+    {
+        for pair in 0..max_games.div_ceil(2) {
+            if openings.is_empty() || self.status() != SprtStatus::Continue {
+                break;
+            }
+
+            let opening = &openings[pair % openings.len()];
+            let paired = play_paired_openings(
+                slice::from_ref(opening),
+                sides,
+                &mut candidate,
+                &mut baseline,
+            );
+
+            for (record, candidate_side) in paired.iter().flat_map(|games| games.games()) {
+                if record.result != GameResult::Unfinished {
+                    self.add(&Wdl::of(&record.result, &candidate_side));
+                }
+                progress(self);
+            }
+        }
+
+        self.status()
+    }
+}
+
+/// Prints the results, the Elo estimate, and the log-likelihood ratio within its bounds.
+impl fmt::Display for Sprt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lower, upper) = self.bounds();
+        write!(
+            f,
+            "Games {} {}, LLR {:.2} [{:.2}, {:.2}] for Elo [{}, {}]",
+            self.wdl.games(),
+            self.wdl,
+            self.llr(),
+            lower,
+            upper,
+            self.elo_0,
+            self.elo_1
+        )?;
+        if let Some(estimate) = EloEstimate::from_wdl(&self.wdl) {
+            write!(f, ", Elo {}", estimate)?;
+        }
+        Ok(())
+    }
+}
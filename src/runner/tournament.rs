@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::thread;
+
+use super::Crosstable;
+use super::Wdl;
+use crate::abstractions::Agent;
+use crate::abstractions::Environment;
+use crate::abstractions::Notation;
+use crate::record::play_recorded;
+use crate::record::GameRecord;
+
+/// Creates the agent of an entrant for a game, given the side it plays. Agents are created
+/// anew for every game, so that no cache or history is carried from one game to another.
+pub type AgentFactory<'a, Action, AgentId, T> =
+    dyn Fn(AgentId) -> Box<dyn Agent<Action, AgentId, T> + 'a> + Send + Sync + 'a;
+
+/// How the entrants of a tournament are paired.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pairings {
+    /// Every entrant meets every other one.
+    RoundRobin,
+    /// The first entrant meets every other one, and the others don't meet each other.
+    Gauntlet,
+    /// In every round, entrants meet an opponent with a similar score that they haven't met
+    /// yet, when there is one. With an odd number of entrants, the lowest ranked one without a
+    /// bye sits out the round and gets the points of winning all its games.
+    Swiss { rounds: usize },
+}
+
+struct Entrant<'a, Action, AgentId, T> {
+    name: String,
+    factory: Box<AgentFactory<'a, Action, AgentId, T>>,
+}
+
+/// A game to be played: the entrants playing 'sides.0' and 'sides.1', and the index of the
+/// opening.
+#[derive(Clone, Copy, Debug)]
+struct Job {
+    players: (usize, usize),
+    opening: usize,
+}
+
+/// Tournament between agents given by factories. Every meeting of two entrants is made of
+/// 'games' games for each opening, with the entrants alternating sides, and the side played
+/// first balanced over the tournament.
+pub struct Tournament<'a, Action, AgentId, T> {
+    sides: (AgentId, AgentId),
+    entrants: Vec<Entrant<'a, Action, AgentId, T>>,
+    pairings: Pairings,
+    games: usize,
+    openings: Vec<(T, String)>,
+    threads: usize,
+}
+
+impl<'a, Action, AgentId, T> Tournament<'a, Action, AgentId, T>
+where
+    AgentId: Eq + Copy + Send + Sync,
+    Action: Send,
+    T: Environment<Action, AgentId> + Clone + Sync,
+{
+    /// Creates a round robin tournament without entrants, for an environment played by the
+    /// agents in 'sides'. Meetings are two games from the initial state, played on one thread.
+    pub fn new(sides: (AgentId, AgentId)) -> Self {
+        Tournament {
+            sides,
+            entrants: Vec::new(),
+            pairings: Pairings::RoundRobin,
+            games: 2,
+            openings: Vec::new(),
+            threads: 1,
+        }
+    }
+
+    /// Adds an entrant whose agents are created by 'factory'.
+    pub fn with_entrant<F, A>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(AgentId) -> A + Send + Sync + 'a,
+        A: Agent<Action, AgentId, T> + 'a,
+    {
+        self.entrants.push(Entrant {
+            name: name.to_string(),
+            factory: Box::new(move |agent_id| Box::new(factory(agent_id))),
+        });
+        self
+    }
+
+    pub fn with_pairings(mut self, pairings: Pairings) -> Self {
+        self.pairings = pairings;
+        self
+    }
+
+    /// Sets the number of games of every meeting, for each opening.
+    pub fn with_games(mut self, games: usize) -> Self {
+        self.games = games;
+        self
+    }
+
+    /// Plays every meeting from each of 'openings' instead of the initial state. The records
+    /// keep the starting positions.
+    pub fn with_openings(mut self, openings: Vec<T>) -> Self
+    where
+        T: Notation<Action, AgentId>,
+    {
+        self.openings = openings
+            .into_iter()
+            .map(|opening| {
+                let notation = opening.to_notation();
+                (opening, notation)
+            })
+            .collect();
+        self
+    }
+
+    /// Plays the games of a round on 'threads' threads.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Plays the tournament.
+    pub fn run(&self) -> TournamentResult<AgentId, Action> {
+        let mut result = TournamentResult {
+            sides: self.sides,
+            names: self.entrants.iter().map(|e| e.name.clone()).collect(),
+            games: Vec::new(),
+            byes: Vec::new(),
+            bye_points: (self.games * self.openings.len().max(1)) as f64,
+        };
+        let mut balance = vec![0i64; self.entrants.len()];
+
+        match self.pairings {
+            Pairings::RoundRobin => {
+                let mut jobs = Vec::new();
+                for i in 0..self.entrants.len() {
+                    for j in (i + 1)..self.entrants.len() {
+                        jobs.extend(self.meeting(i, j, &mut balance));
+                    }
+                }
+                result.games = self.play_jobs(&jobs);
+            }
+            Pairings::Gauntlet => {
+                let mut jobs = Vec::new();
+                for j in 1..self.entrants.len() {
+                    jobs.extend(self.meeting(0, j, &mut balance));
+                }
+                result.games = self.play_jobs(&jobs);
+            }
+            Pairings::Swiss { rounds } => {
+                for _ in 0..rounds {
+                    let mut jobs = Vec::new();
+                    for (i, j) in self.swiss_pairs(&mut result) {
+                        jobs.extend(self.meeting(i, j, &mut balance));
+                    }
+                    let games = self.play_jobs(&jobs);
+                    result.games.extend(games);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns the games of a meeting between 'i' and 'j'. The entrant which played
+    /// 'sides.0' less often so far plays it first.
+    fn meeting(&self, i: usize, j: usize, balance: &mut [i64]) -> Vec<Job> {
+        let (first, second) = if balance[i] <= balance[j] {
+            (i, j)
+        } else {
+            (j, i)
+        };
+
+        let mut jobs = Vec::new();
+        for opening in 0..self.openings.len().max(1) {
+            for game in 0..self.games {
+                let players = if game % 2 == 0 {
+                    (first, second)
+                } else {
+                    (second, first)
+                };
+                balance[players.0] += 1;
+                balance[players.1] -= 1;
+                jobs.push(Job { players, opening });
+            }
+        }
+        jobs
+    }
+
+    /// Pairs the entrants for the next Swiss round, from their points so far. Entrants are
+    /// ranked by points, and paired from the top with the highest ranked opponent they
+    /// haven't met. When no pairing without rematches is found after a bounded search, every
+    /// entrant from the top meets the highest ranked opponent left that it hasn't met, or the
+    /// next one in the ranking when it has met them all.
+    fn swiss_pairs(&self, result: &mut TournamentResult<AgentId, Action>) -> Vec<(usize, usize)> {
+        let points = result.points();
+        let met: HashSet<(usize, usize)> = result
+            .games
+            .iter()
+            .map(|game| {
+                let (i, j) = game.players;
+                (i.min(j), i.max(j))
+            })
+            .collect();
+
+        let mut ranking: Vec<usize> = (0..self.entrants.len()).collect();
+        ranking.sort_by(|a, b| points[*b].total_cmp(&points[*a]).then(a.cmp(b)));
+
+        if ranking.len() % 2 == 1 {
+            let position = ranking
+                .iter()
+                .rposition(|i| !result.byes.contains(i))
+                .unwrap_or(ranking.len() - 1);
+            result.byes.push(ranking.remove(position));
+        }
+
+        let mut steps = MAX_PAIRING_STEPS;
+        match pair_unmet(&ranking, &met, &mut steps) {
+            Some(pairs) => pairs,
+            None => pair_greedy(&ranking, &met),
+        }
+    }
+
+    /// Plays 'jobs', on several threads if asked to, and returns the games in the same order.
+    fn play_jobs(&self, jobs: &[Job]) -> Vec<TournamentGame<AgentId, Action>> {
+        if self.threads <= 1 {
+            return jobs.iter().map(|job| self.play_job(job)).collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let played = Mutex::new(Vec::with_capacity(jobs.len()));
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(jobs.len()) {
+                scope.spawn(|| {
+                    let mut index = next.fetch_add(1, Ordering::Relaxed);
+                    while let Some(job) = jobs.get(index) {
+                        let game = self.play_job(job);
+                        played
+                            .lock()
+                            .expect("No thread panics while holding the lock")
+                            .push((index, game));
+                        index = next.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        let mut played = played
+            .into_inner()
+            .expect("No thread panics while holding the lock");
+        played.sort_by_key(|(index, _)| *index);
+        played.into_iter().map(|(_, game)| game).collect()
+    }
+
+    fn play_job(&self, job: &Job) -> TournamentGame<AgentId, Action> {
+        let (first, second) = job.players;
+        let mut agent_1 = (self.entrants[first].factory)(self.sides.0);
+        let mut agent_2 = (self.entrants[second].factory)(self.sides.1);
+
+        let (mut env, start) = match self.openings.get(job.opening) {
+            Some((opening, notation)) => (opening.clone(), Some(notation.clone())),
+            None => (T::initial_state(), None),
+        };
+
+        let mut record = play_recorded(&mut env, &mut agent_1, &mut agent_2)
+            .with_player_name(&self.sides.0, &self.entrants[first].name)
+            .with_player_name(&self.sides.1, &self.entrants[second].name);
+        record.start = start;
+
+        TournamentGame {
+            players: job.players,
+            record,
+        }
+    }
+}
+
+/// Largest number of partial pairings tried by 'pair_unmet' for a round. Finding that no
+/// pairing avoids rematches can otherwise take a time exponential in the number of entrants.
+const MAX_PAIRING_STEPS: usize = 100_000;
+
+/// Pairs the entrants in 'ranking' so that none meets an opponent again, preferring the
+/// highest ranked opponents for the highest ranked entrants. Returns None if there is no such
+/// pairing, or if none was found within 'steps' partial pairings.
+fn pair_unmet(
+    ranking: &[usize],
+    met: &HashSet<(usize, usize)>,
+    steps: &mut usize,
+) -> Option<Vec<(usize, usize)>> {
+    let (i, rest) = match ranking.split_first() {
+        Some(split) => split,
+        None => return Some(Vec::new()),
+    };
+
+    for (k, j) in rest.iter().enumerate() {
+        if met.contains(&(*i.min(j), *i.max(j))) {
+            continue;
+        }
+        if *steps == 0 {
+            return None;
+        }
+        *steps -= 1;
+
+        let others: Vec<usize> = rest
+            .iter()
+            .enumerate()
+            .filter(|(l, _)| *l != k)
+            .map(|(_, other)| *other)
+            .collect();
+        if let Some(mut pairs) = pair_unmet(&others, met, steps) {
+            pairs.insert(0, (*i, *j));
+            return Some(pairs);
+        }
+    }
+
+    None
+}
+
+/// Pairs the entrants in 'ranking' from the top, each with the highest ranked opponent left
+/// that it hasn't met, or with the next one when it has met them all.
+fn pair_greedy(ranking: &[usize], met: &HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut left = ranking.to_vec();
+    let mut pairs = Vec::new();
+
+    while left.len() >= 2 {
+        let i = left.remove(0);
+        let k = left
+            .iter()
+            .position(|j| !met.contains(&(i.min(*j), i.max(*j))))
+            .unwrap_or(0);
+        pairs.push((i, left.remove(k)));
+    }
+
+    pairs
+}
+
+/// A game of a tournament, between the entrants of index 'players.0' and 'players.1', which
+/// played 'sides.0' and 'sides.1'.
+#[derive(Clone, Debug)]
+pub struct TournamentGame<AgentId, Action> {
+    pub players: (usize, usize),
+    pub record: GameRecord<AgentId, Action>,
+}
+
+/// Games played in a tournament, in the order of the pairings.
+#[derive(Clone, Debug)]
+pub struct TournamentResult<AgentId, Action> {
+    pub sides: (AgentId, AgentId),
+    pub names: Vec<String>,
+    pub games: Vec<TournamentGame<AgentId, Action>>,
+    /// Entrants that sat out a Swiss round, once per round.
+    pub byes: Vec<usize>,
+    /// Points given for a bye: those of winning all the games of a meeting.
+    pub bye_points: f64,
+}
+
+impl<AgentId, Action> TournamentResult<AgentId, Action>
+where
+    AgentId: Eq,
+{
+    /// Returns the wins, draws and losses of every pairing. Unfinished games count as draws.
+    pub fn crosstable(&self) -> Crosstable {
+        let mut crosstable = Crosstable::new(self.names.clone());
+        for game in &self.games {
+            let wdl = Wdl::of(&game.record.result, &self.sides.0);
+            crosstable.add(game.players.0, game.players.1, &wdl);
+        }
+        crosstable
+    }
+
+    /// Returns the points of every entrant, byes included.
+    pub fn points(&self) -> Vec<f64> {
+        let mut points = vec![0f64; self.names.len()];
+        for game in &self.games {
+            let first = game.record.result.points(&self.sides.0);
+            points[game.players.0] += first;
+            points[game.players.1] += 1f64 - first;
+        }
+        for bye in &self.byes {
+            points[*bye] += self.bye_points;
+        }
+        points
+    }
+
+    /// Returns the indices of the entrants from the most to the least points, with their
+    /// points.
+    pub fn standings(&self) -> Vec<(usize, f64)> {
+        let mut standings: Vec<(usize, f64)> = self.points().into_iter().enumerate().collect();
+        standings.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        standings
+    }
+}
@@ -0,0 +1,10 @@
+mod outcome;
+pub use self::outcome::Outcome;
+pub use self::outcome::ProbeFn;
+
+mod retrograde;
+pub use self::retrograde::retrograde;
+pub use self::retrograde::retrograde_with_predecessors;
+
+mod table;
+pub use self::table::Tablebase;
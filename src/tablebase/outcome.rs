@@ -0,0 +1,70 @@
+/// Game-theoretic outcome of a position for the agent moving in it, with perfect play.
+/// Distances are the number of plies until the game ends. The winning agent plays to make
+/// them as short as possible, and the losing agent as long as possible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win(usize),
+    Loss(usize),
+    Draw,
+}
+
+impl Outcome {
+    /// Returns the outcome as seen by the other agent.
+    pub fn flip(self) -> Self {
+        match self {
+            Outcome::Win(distance) => Outcome::Loss(distance),
+            Outcome::Loss(distance) => Outcome::Win(distance),
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    /// Returns the outcome one ply earlier, for the agent moving before.
+    /// 'same_agent' tells whether that agent is the one moving now.
+    pub fn before(self, same_agent: bool) -> Self {
+        let outcome = if same_agent { self } else { self.flip() };
+
+        match outcome {
+            Outcome::Win(distance) => Outcome::Win(distance + 1),
+            Outcome::Loss(distance) => Outcome::Loss(distance + 1),
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    /// Returns a value comparing outcomes for the agent moving: wins are better than draws,
+    /// which are better than losses. Short wins and long losses are preferred.
+    pub fn preference(self) -> (i8, i64) {
+        match self {
+            Outcome::Win(distance) => (1, -(distance as i64)),
+            Outcome::Draw => (0, 0),
+            Outcome::Loss(distance) => (-1, distance as i64),
+        }
+    }
+
+    /// Encodes the outcome in 32 bits: two bits for the kind and the rest for the distance.
+    pub(super) fn encode(self) -> u32 {
+        if let Outcome::Win(distance) | Outcome::Loss(distance) = self {
+            assert!(distance < 1 << 30, "Distance too large to encode");
+        }
+
+        match self {
+            Outcome::Draw => 0,
+            Outcome::Win(distance) => ((distance as u32) << 2) | 1,
+            Outcome::Loss(distance) => ((distance as u32) << 2) | 2,
+        }
+    }
+
+    /// Decodes an outcome written by 'encode'.
+    pub(super) fn decode(code: u32) -> Option<Self> {
+        let distance = (code >> 2) as usize;
+
+        match code & 3 {
+            0 if distance == 0 => Some(Outcome::Draw),
+            1 => Some(Outcome::Win(distance)),
+            2 => Some(Outcome::Loss(distance)),
+            _ => None,
+        }
+    }
+}
+
+/// Function probing the outcome of a position, if it is known.
+pub type ProbeFn<'a, T> = dyn Fn(&T) -> Option<Outcome> + 'a;
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::hash::Hash;
+
+use crate::abstractions::Environment;
+use crate::abstractions::Predecessors;
+
+use super::Outcome;
+use super::Tablebase;
+
+/// Solves every position in a subset of the game by retrograde analysis, from an enumeration
+/// of its positions, each of them once. The predecessors of each position are found by
+/// playing every action of the subset forwards.
+///
+/// Subsets are expected to be closed under actions, like an endgame of a given material.
+/// Positions with successors outside of the subset are never considered lost, and end up as
+/// draws unless they can force a win inside the subset.
+pub fn retrograde<Action, AgentId, T, I>(positions: I) -> Tablebase<T>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let positions: Vec<T> = positions.into_iter().collect();
+    let index = index_of(&positions);
+
+    let mut remaining = vec![0; positions.len()];
+    let mut predecessors = vec![Vec::new(); positions.len()];
+
+    for (i, env) in positions.iter().enumerate() {
+        if env.is_terminal() {
+            continue;
+        }
+
+        let children = children(env);
+        remaining[i] = children.len();
+
+        for child in children {
+            if let Some(child_index) = index.get(&child) {
+                predecessors[*child_index].push(i);
+            }
+        }
+    }
+
+    let outcomes = solve(&positions, remaining, |i| predecessors[i].clone());
+
+    Tablebase::new(positions, &outcomes)
+}
+
+/// Solves every position in a subset of the game like 'retrograde', but walking backwards
+/// with the 'Predecessors' extension instead of keeping the predecessors of every position
+/// in memory.
+pub fn retrograde_with_predecessors<Action, AgentId, T, I>(positions: I) -> Tablebase<T>
+where
+    AgentId: Eq,
+    T: Predecessors<Action, AgentId> + Clone + Eq + Hash,
+    I: IntoIterator<Item = T>,
+{
+    let positions: Vec<T> = positions.into_iter().collect();
+    let index = index_of(&positions);
+
+    let remaining = positions
+        .iter()
+        .map(|env| {
+            if env.is_terminal() {
+                0
+            } else {
+                children(env).len()
+            }
+        })
+        .collect();
+
+    let outcomes = solve(&positions, remaining, |i| {
+        positions[i]
+            .predecessors()
+            .iter()
+            .filter_map(|env| index.get(env).copied())
+            .collect()
+    });
+
+    Tablebase::new(positions, &outcomes)
+}
+
+/// Maps every position to its place in the enumeration.
+fn index_of<T: Eq + Hash>(positions: &[T]) -> HashMap<&T, usize> {
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, env)| (env, i))
+        .collect()
+}
+
+/// Returns the distinct positions reached with the valid actions of 'env'.
+fn children<Action, AgentId, T>(env: &T) -> HashSet<T>
+where
+    T: Environment<Action, AgentId> + Eq + Hash,
+{
+    env.valid_actions().map(|a| env.what_if(&a)).collect()
+}
+
+/// Propagates outcomes backwards from the positions where the game ends. 'remaining' holds the
+/// number of distinct children of each position, and 'predecessors' the positions of the
+/// subset leading to a given one, possibly more than once.
+///
+/// Positions are solved in order of distance. A position is won as soon as one of its children
+/// is lost for the agent moving in it, and lost once all of its children are won for the other
+/// agent. Positions that never get solved are draws.
+fn solve<Action, AgentId, T>(
+    positions: &[T],
+    mut remaining: Vec<usize>,
+    predecessors: impl Fn(usize) -> Vec<usize>,
+) -> Vec<Outcome>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    let mut outcomes: Vec<Option<Outcome>> = vec![None; positions.len()];
+    let mut queue = VecDeque::new();
+
+    for (i, env) in positions.iter().enumerate() {
+        if env.is_terminal() || remaining[i] == 0 {
+            let outcome = match env.winner() {
+                Some(winner) if winner == env.turn() => Outcome::Win(0),
+                Some(_) => Outcome::Loss(0),
+                None => Outcome::Draw,
+            };
+
+            outcomes[i] = Some(outcome);
+            if outcome != Outcome::Draw {
+                queue.push_back(i);
+            }
+        }
+    }
+
+    while let Some(i) = queue.pop_front() {
+        let outcome = outcomes[i].expect("Queued positions are solved");
+        let turn = positions[i].turn();
+
+        // A position listed twice as a predecessor would lose a remaining child twice.
+        let mut predecessors = predecessors(i);
+        predecessors.sort_unstable();
+        predecessors.dedup();
+
+        for predecessor in predecessors {
+            if outcomes[predecessor].is_some() {
+                continue;
+            }
+
+            let before = outcome.before(positions[predecessor].turn() == turn);
+            let solved = match before {
+                Outcome::Win(_) => true,
+                _ => {
+                    remaining[predecessor] -= 1;
+                    remaining[predecessor] == 0
+                }
+            };
+
+            if solved {
+                outcomes[predecessor] = Some(before);
+                queue.push_back(predecessor);
+            }
+        }
+    }
+
+    outcomes
+        .into_iter()
+        .map(|outcome| outcome.unwrap_or(Outcome::Draw))
+        .collect()
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hash;
+use std::io;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+
+use super::Outcome;
+
+const MAGIC: &[u8; 4] = b"GTTB";
+const VERSION: u8 = 1;
+
+/// Outcomes of every position in a subset of the game. Positions are identified by their
+/// place in the enumeration of the subset used to build the tablebase, so files store four
+/// bytes per position, and loading a file needs the same enumeration.
+pub struct Tablebase<T> {
+    index: HashMap<T, usize>,
+    outcomes: Vec<u32>,
+}
+
+impl<T> Tablebase<T>
+where
+    T: Eq + Hash,
+{
+    /// Creates a tablebase from the positions of a subset and their outcomes, in the same order.
+    pub fn new(positions: Vec<T>, outcomes: &[Outcome]) -> Self {
+        assert_eq!(positions.len(), outcomes.len());
+
+        Tablebase {
+            index: positions
+                .into_iter()
+                .enumerate()
+                .map(|(i, env)| (env, i))
+                .collect(),
+            outcomes: outcomes.iter().map(|o| o.encode()).collect(),
+        }
+    }
+
+    /// Returns the outcome of 'env' for the agent moving in it, if 'env' is in the tablebase.
+    pub fn probe(&self, env: &T) -> Option<Outcome> {
+        self.index
+            .get(env)
+            .and_then(|i| Outcome::decode(self.outcomes[*i]))
+    }
+
+    /// Returns the number of positions in the tablebase.
+    pub fn len(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    /// Returns true iff the tablebase has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.outcomes.is_empty()
+    }
+
+    /// Writes the tablebase to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a tablebase from a file. 'positions' must enumerate the subset in the same order
+    /// as when the tablebase was built.
+    pub fn load<P, I>(path: P, positions: I) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = T>,
+    {
+        Self::read_from(&mut BufReader::new(File::open(path)?), positions)
+    }
+
+    /// Writes the tablebase: a header with a magic number, the format version and the number of
+    /// positions, followed by the outcome of every position.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&(self.outcomes.len() as u64).to_le_bytes())?;
+
+        for outcome in &self.outcomes {
+            writer.write_all(&outcome.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a tablebase written by 'write_to'. See 'load'.
+    pub fn read_from<R, I>(reader: &mut R, positions: I) -> io::Result<Self>
+    where
+        R: Read,
+        I: IntoIterator<Item = T>,
+    {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a tablebase file"));
+        }
+
+        let mut version = [0u8; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data("Unsupported tablebase version"));
+        }
+
+        let mut len = [0u8; 8];
+        reader.read_exact(&mut len)?;
+        let len = u64::from_le_bytes(len) as usize;
+
+        // The length is checked before allocating, so that corrupted files can't ask for
+        // more memory than the positions need.
+        let index: HashMap<T, usize> = positions
+            .into_iter()
+            .enumerate()
+            .map(|(i, env)| (env, i))
+            .collect();
+        if index.len() != len {
+            return Err(invalid_data("Positions do not match the tablebase"));
+        }
+
+        let mut outcomes = Vec::with_capacity(len);
+        let mut outcome = [0u8; 4];
+        for _ in 0..len {
+            reader.read_exact(&mut outcome)?;
+            outcomes.push(u32::from_le_bytes(outcome));
+        }
+
+        Ok(Tablebase { index, outcomes })
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
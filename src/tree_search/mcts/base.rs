@@ -1,34 +1,131 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
 use crate::abstractions::Environment;
+use crate::abstractions::Undo;
+use crate::cache::utils::hash;
+use crate::tablebase::ProbeFn;
 
 use super::add_value;
 use super::find_terminal_value;
+use super::probe_value;
 
-use super::Cache;
-use super::Hash;
+use super::InPlaceSelectionFn;
+use super::MctsCache;
+use super::SelectionFn;
 use super::Stored;
 
 /// Runs montecarlo tree search in an environment.
 /// The number of wins and loses at that node is stored in the cache.
 /// The selection function pics an action based on the currently cached values.
+/// Descending into a position already on the path ends the run with a draw by repetition.
 pub fn mcts<Action, AgentId, T>(
     env: &T,
     agent_id: &AgentId,
-    selection_fn: &dyn Fn(&T, &AgentId, &Cache<T>) -> Option<Action>,
-    cache: &mut Cache<T>,
+    selection_fn: &SelectionFn<'_, Action, AgentId, T>,
+    cache: &mut dyn MctsCache<T>,
+) -> Stored
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Hash,
+{
+    mcts_with_probe(env, agent_id, selection_fn, &|_| None, cache)
+}
+
+/// Runs montecarlo tree search like 'mcts', but stops descending at positions with an outcome
+/// known by 'probe', usually a tablebase. Those positions are scored like terminal positions.
+pub fn mcts_with_probe<Action, AgentId, T>(
+    env: &T,
+    agent_id: &AgentId,
+    selection_fn: &SelectionFn<'_, Action, AgentId, T>,
+    probe: &ProbeFn<'_, T>,
+    cache: &mut dyn MctsCache<T>,
 ) -> Stored
 where
     AgentId: Eq,
-    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+    T: Environment<Action, AgentId> + Hash,
 {
-    let value = match selection_fn(env, agent_id, cache) {
-        Some(action) => {
-            let next_env = env.what_if(&action);
-            mcts(&next_env, agent_id, selection_fn, cache)
+    let mut keys = HashSet::new();
+    keys.insert(hash(env));
+
+    // Positions reached below 'env', kept for the backup.
+    let mut path: Vec<T> = Vec::new();
+
+    let value = loop {
+        let current = path.last().unwrap_or(env);
+
+        match selection_fn(current, agent_id, cache) {
+            Some(action) => {
+                let next_env = current.what_if(&action);
+
+                if !keys.insert(hash(&next_env)) {
+                    break (0f64, 1);
+                } else if let Some(outcome) = probe(&next_env) {
+                    let value = probe_value(&next_env, agent_id, outcome);
+                    add_value(&next_env, &value, cache);
+                    break value;
+                }
+
+                path.push(next_env);
+            }
+            None => break find_terminal_value(current, agent_id),
         }
-        None => find_terminal_value(env, agent_id),
     };
 
+    for position in &path {
+        add_value(position, &value, cache);
+    }
     add_value(env, &value, cache);
 
-    return value;
+    value
+}
+
+/// Runs montecarlo tree search like 'mcts', but descending on 'env' itself with 'Undo::make',
+/// and taking the actions back with 'Undo::unmake' during the backup. 'selection_fn' also gets
+/// 'env' mutably, so that it can look at the children in place, like 'uct_in_place' does.
+/// Environments are then only cloned when they enter the cache. 'env' is left as it was found.
+///
+/// # Panics
+///
+/// If 'Undo::make' fails on an action picked by 'selection_fn'.
+pub fn mcts_in_place<Action, AgentId, T>(
+    env: &mut T,
+    agent_id: &AgentId,
+    selection_fn: &InPlaceSelectionFn<'_, Action, AgentId, T>,
+    cache: &mut dyn MctsCache<T>,
+) -> Stored
+where
+    AgentId: Eq,
+    T: Undo<Action, AgentId> + Hash,
+{
+    let mut keys = HashSet::new();
+    keys.insert(hash(&*env));
+
+    let mut tokens = Vec::new();
+
+    let (value, repeated) = loop {
+        match selection_fn(env, agent_id, cache) {
+            Some(action) => {
+                let token = env
+                    .make(&action)
+                    .expect("'Undo::make' failed on the action selected");
+                tokens.push(token);
+                if !keys.insert(hash(&*env)) {
+                    break ((0f64, 1), true);
+                }
+            }
+            None => break (find_terminal_value(env, agent_id), false),
+        }
+    };
+
+    // A repeated position is not part of this run, its value is the one of its first visit.
+    if !repeated {
+        add_value(env, &value, cache);
+    }
+    while let Some(token) = tokens.pop() {
+        env.unmake(token);
+        add_value(env, &value, cache);
+    }
+
+    value
 }
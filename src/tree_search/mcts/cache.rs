@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::abstractions::Symmetric;
+use crate::abstractions::Zobrist;
+use crate::cache::arena::Arena;
+use crate::cache::node::CanonicalFn;
+use crate::cache::zobrist::BuildZobristHasher;
+
+use super::Stored;
+
+/// Storage for the scores and visits found by montecarlo tree search.
+pub trait MctsCache<T> {
+    /// Returns the score and visits stored for 'env', if any.
+    fn get(&self, env: &T) -> Option<Stored>;
+
+    /// Adds a score and a number of visits to the ones stored for 'env'.
+    fn add(&mut self, env: &T, value: &Stored);
+
+    /// Returns the number of positions stored.
+    fn len(&self) -> usize;
+
+    /// Returns true iff no position is stored.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets every position.
+    fn clear(&mut self);
+}
+
+/// Caches that 'MctsUctAgent' can write to files. Every kind of cache starts its files with
+/// its own magic number, so that a file is only read back into the kind of cache that wrote it.
+#[cfg(feature = "serde")]
+pub trait SavedCache<T>: MctsCache<T> + Serialize + DeserializeOwned {
+    const MAGIC: &'static [u8; 4];
+}
+
+/// The default cache, hashing and storing whole environments.
+impl<T> MctsCache<T> for HashMap<T, Stored>
+where
+    T: Eq + Hash + Clone,
+{
+    fn get(&self, env: &T) -> Option<Stored> {
+        HashMap::get(self, env).copied()
+    }
+
+    fn add(&mut self, env: &T, (score, visits): &Stored) {
+        match self.get_mut(env) {
+            Some((stored_score, stored_visits)) => {
+                *stored_score += score;
+                *stored_visits += visits;
+            }
+            None => {
+                self.insert(env.clone(), (*score, *visits));
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+
+    fn clear(&mut self) {
+        HashMap::clear(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> SavedCache<T> for HashMap<T, Stored>
+where
+    T: Eq + Hash + Clone + Serialize + DeserializeOwned,
+{
+    const MAGIC: &'static [u8; 4] = b"GTMH";
+}
+
+/// An arena as a cache. Positions are stored once, and the tree can be kept between searches.
+impl<T, Action> MctsCache<T> for Arena<T, Action, Stored>
+where
+    T: Eq + Hash + Clone,
+{
+    fn get(&self, env: &T) -> Option<Stored> {
+        Arena::get(self, env).map(|id| *self.data(id))
+    }
+
+    fn add(&mut self, env: &T, (score, visits): &Stored) {
+        let id = match Arena::get(self, env) {
+            Some(id) => id,
+            None => self.insert(env.clone()),
+        };
+        let (stored_score, stored_visits) = self.data_mut(id);
+        *stored_score += score;
+        *stored_visits += visits;
+    }
+
+    fn len(&self) -> usize {
+        Arena::len(self)
+    }
+
+    fn clear(&mut self) {
+        Arena::clear(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, Action> SavedCache<T> for Arena<T, Action, Stored>
+where
+    T: Eq + Hash + Clone + Serialize + DeserializeOwned,
+    Action: Serialize + DeserializeOwned,
+{
+    const MAGIC: &'static [u8; 4] = b"GTMA";
+}
+
+/// A cache keyed on the Zobrist key of environments. Unless verification is enabled, it stores
+/// no environments, and positions with colliding keys share their values.
+pub struct ZobristCache<T> {
+    key: fn(&T) -> u64,
+    verify: bool,
+    entries: HashMap<u64, (Stored, Option<T>), BuildZobristHasher>,
+}
+
+impl<T> ZobristCache<T> {
+    pub fn new<Action, AgentId>() -> Self
+    where
+        T: Zobrist<Action, AgentId>,
+    {
+        ZobristCache {
+            key: T::zobrist,
+            verify: false,
+            entries: HashMap::default(),
+        }
+    }
+
+    /// Stores a clone of every environment, and checks it on every access. Positions whose key
+    /// collides with the one stored are treated as new, and replace it.
+    pub fn with_verification(mut self) -> Self {
+        self.verify = true;
+        self
+    }
+}
+
+impl<T> MctsCache<T> for ZobristCache<T>
+where
+    T: Eq + Clone,
+{
+    fn get(&self, env: &T) -> Option<Stored> {
+        match self.entries.get(&(self.key)(env)) {
+            Some((_, Some(stored))) if stored != env => None,
+            Some((value, _)) => Some(*value),
+            None => None,
+        }
+    }
+
+    fn add(&mut self, env: &T, (score, visits): &Stored) {
+        let key = (self.key)(env);
+        let stored = if self.verify { Some(env.clone()) } else { None };
+
+        match self.entries.get_mut(&key) {
+            Some(((stored_score, stored_visits), stored_env))
+                if stored_env.as_ref().is_none_or(|e| e == env) =>
+            {
+                *stored_score += score;
+                *stored_visits += visits;
+            }
+            _ => {
+                self.entries.insert(key, ((*score, *visits), stored));
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear()
+    }
+}
+
+/// A cache storing the values of canonical positions in another cache, so that positions
+/// related by a symmetry share their values.
+pub struct SymmetricCache<T, C> {
+    canonical: CanonicalFn<T>,
+    cache: C,
+}
+
+impl<T, C> SymmetricCache<T, C>
+where
+    C: MctsCache<T>,
+{
+    pub fn new<Action, AgentId>(cache: C) -> Self
+    where
+        T: Symmetric<Action, AgentId> + Ord,
+    {
+        SymmetricCache {
+            canonical: |env| env.canonical().0,
+            cache,
+        }
+    }
+}
+
+impl<T, C> MctsCache<T> for SymmetricCache<T, C>
+where
+    C: MctsCache<T>,
+{
+    fn get(&self, env: &T) -> Option<Stored> {
+        self.cache.get(&(self.canonical)(env))
+    }
+
+    fn add(&mut self, env: &T, value: &Stored) {
+        self.cache.add(&(self.canonical)(env), value)
+    }
+
+    fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    fn clear(&mut self) {
+        self.cache.clear()
+    }
+}
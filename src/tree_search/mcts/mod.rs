@@ -1,16 +1,27 @@
-use std::collections::HashMap;
-use std::hash::Hash;
-
 mod base;
 pub use self::base::mcts;
+pub use self::base::mcts_in_place;
+pub use self::base::mcts_with_probe;
+
+mod cache;
+pub use self::cache::MctsCache;
+#[cfg(feature = "serde")]
+pub use self::cache::SavedCache;
+pub use self::cache::SymmetricCache;
+pub use self::cache::ZobristCache;
 
 mod uct;
 pub use self::uct::uct;
+pub use self::uct::uct_in_place;
 
 mod utils;
 use self::utils::add_value;
 use self::utils::find_terminal_value;
+use self::utils::probe_value;
 use self::utils::read_cache;
 
 type Stored = (f64, u32);
-type Cache<T> = HashMap<T, Stored>;
+type SelectionFn<'a, Action, AgentId, T> =
+    dyn Fn(&T, &AgentId, &dyn MctsCache<T>) -> Option<Action> + 'a;
+type InPlaceSelectionFn<'a, Action, AgentId, T> =
+    dyn Fn(&mut T, &AgentId, &dyn MctsCache<T>) -> Option<Action> + 'a;
@@ -1,37 +1,76 @@
 use crate::abstractions::Environment;
+use crate::abstractions::Undo;
 
 use super::read_cache;
 
-use super::Cache;
-use super::Hash;
+use super::MctsCache;
+use super::Stored;
 
 /// Picks an action by using the upper confidence bound criteria.
 pub fn uct<Action, AgentId, T>(
     env: &T,
     agent_id: &AgentId,
-    cache: &Cache<T>,
+    cache: &dyn MctsCache<T>,
     exploration: f64,
 ) -> Option<Action>
 where
     Action: Copy,
     AgentId: Eq,
-    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+    T: Environment<Action, AgentId>,
 {
-    let total_visits: u32 = env
+    let children = env
+        .valid_actions()
+        .map(|action| (action, read_cache(&env.what_if(&action), cache)))
+        .collect();
+
+    best_child(children, *agent_id == env.turn(), exploration)
+}
+
+/// Same as 'uct', but reading the children from 'env' itself with 'Undo::make' and
+/// 'Undo::unmake', instead of cloning it for every action. 'env' is left as it was found.
+///
+/// # Panics
+///
+/// If 'Undo::make' fails on an action given by 'valid_actions'.
+pub fn uct_in_place<Action, AgentId, T>(
+    env: &mut T,
+    agent_id: &AgentId,
+    cache: &dyn MctsCache<T>,
+    exploration: f64,
+) -> Option<Action>
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Undo<Action, AgentId>,
+{
+    let is_agent_turn = *agent_id == env.turn();
+    let children = env
         .valid_actions()
         .map(|action| {
-            let (_, visits) = read_cache(&env.what_if(&action), cache);
-            visits
+            let token = env
+                .make(&action)
+                .expect("'Undo::make' failed on an action given by 'valid_actions'");
+            let stored = read_cache(env, cache);
+            env.unmake(token);
+            (action, stored)
         })
-        .sum();
+        .collect();
 
-    let exploration_numerator = exploration * (total_visits as f64).ln().sqrt();
+    best_child(children, is_agent_turn, exploration)
+}
 
-    let is_agent_turn = *agent_id == env.turn();
+/// Returns the action of the child with the best uct score.
+fn best_child<Action>(
+    children: Vec<(Action, Stored)>,
+    is_agent_turn: bool,
+    exploration: f64,
+) -> Option<Action> {
+    let total_visits: u32 = children.iter().map(|(_, (_, visits))| visits).sum();
 
-    let best_action = env
-        .valid_actions()
-        .map(|x| (x, read_cache(&env.what_if(&x), cache)))
+    let exploration_numerator = exploration * (total_visits as f64).ln().sqrt();
+
+    let best_action = children
+        .into_iter()
         .map(|(x, (score, visits))| {
             let sc = if is_agent_turn { score } else { -score };
             (x, uct_score(sc, visits, exploration_numerator))
@@ -40,19 +79,15 @@ where
             score0.partial_cmp(score1).expect("Tried to compare a NaN")
         });
 
-    match best_action {
-        Some((action, _)) => Some(action),
-        None => None,
-    }
+    best_action.map(|(action, _)| action)
 }
 
 /// Calculates the uct score of an action based on the average score of and the number of visits of a node.
 fn uct_score(score: f64, visits: u32, exploration_numerator: f64) -> f64 {
     if visits == 0 {
-        return f64::INFINITY;
+        f64::INFINITY
     } else {
         let n = visits as f64;
-        let score = (score as f64 / n) + exploration_numerator / n.sqrt();
-        return score;
+        (score / n) + exploration_numerator / n.sqrt()
     }
 }
@@ -1,37 +1,25 @@
 use crate::abstractions::Environment;
+use crate::tablebase::Outcome;
 
-use super::Hash;
-
-use super::Cache;
+use super::MctsCache;
 use super::Stored;
 
 /// Read a value from the cache. It fills with zeroes when the value is not in the cache.
-pub(super) fn read_cache<Action, AgentId, T>(env: &T, cache: &Cache<T>) -> Stored
+pub(super) fn read_cache<Action, AgentId, T>(env: &T, cache: &dyn MctsCache<T>) -> Stored
 where
     AgentId: Eq,
-    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+    T: Environment<Action, AgentId>,
 {
-    match cache.get(env) {
-        Some((score, visits)) => (*score, *visits),
-        None => (0f64, 0u32),
-    }
+    cache.get(env).unwrap_or((0f64, 0u32))
 }
 
 /// Adds a value to the value currently in the cache.
-pub(super) fn add_value<Action, AgentId, T>(
-    env: &T,
-    (this_score, this_visits): &Stored,
-    cache: &mut Cache<T>,
-) where
+pub(super) fn add_value<Action, AgentId, T>(env: &T, value: &Stored, cache: &mut dyn MctsCache<T>)
+where
     AgentId: Eq,
-    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+    T: Environment<Action, AgentId>,
 {
-    let (mut score, mut visits) = read_cache(env, cache);
-
-    score += this_score;
-    visits += this_visits;
-
-    cache.insert(env.clone(), (score, visits));
+    cache.add(env, value);
 }
 
 /// Finds the value for a terminal action.
@@ -51,3 +39,26 @@ where
         None => (0f64, 1),
     }
 }
+
+/// Finds the value for a position with a known outcome for the agent moving in it.
+pub(super) fn probe_value<Action, AgentId, T>(
+    env: &T,
+    agent_id: &AgentId,
+    outcome: Outcome,
+) -> Stored
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    let outcome = if env.turn() == *agent_id {
+        outcome
+    } else {
+        outcome.flip()
+    };
+
+    match outcome {
+        Outcome::Win(_) => (1f64, 1),
+        Outcome::Loss(_) => (-1f64, 1),
+        Outcome::Draw => (0f64, 1),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::abstractions::Undo;
+
+use super::negamax::Negamax;
+use super::negamax::MAX_DEPTH;
+use super::search_node::InPlaceNode;
+
+/// Same as 'minmax', but walking the tree on 'env' itself with 'Undo::make' and
+/// 'Undo::unmake', instead of cloning it with 'what_if' for every action. 'env' is left as it
+/// was found, even if the search panics.
+pub fn minmax_in_place<Action, AgentId, T>(
+    env: &mut T,
+    agent_id: &AgentId,
+    reward: &dyn Fn(&T, &AgentId) -> f64,
+    depth: usize,
+    alpha: f64,
+    beta: f64,
+) -> (f64, Option<Action>)
+where
+    AgentId: Eq,
+    T: Undo<Action, AgentId>,
+{
+    Negamax::new(agent_id, reward).search(&mut InPlaceNode::new(env), depth, alpha, beta)
+}
+
+/// Same as 'depth_first', but walking the tree on 'env' itself. See 'minmax_in_place'.
+pub fn depth_first_in_place<Action, AgentId, T>(env: &mut T, agent: &AgentId) -> f64
+where
+    AgentId: Eq,
+    T: Undo<Action, AgentId>,
+{
+    // The reward is never used, as the search only stops at terminal environments.
+    let (value, _) = minmax_in_place(
+        env,
+        agent,
+        &|_, _| 0f64,
+        MAX_DEPTH,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+    );
+
+    value
+}
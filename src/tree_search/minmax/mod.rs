@@ -1,8 +1,45 @@
 mod alphabeta;
 mod depth_first;
+mod in_place;
+#[allow(clippy::module_inception)]
 mod minmax;
+mod mtdf;
+mod negamax;
+mod ordering;
+mod pruning;
+mod quiescence;
+mod repetitions;
+mod search_node;
+mod solve;
+mod stats;
 mod utils;
 
 pub use alphabeta::alphabeta;
 pub use depth_first::depth_first;
+pub use in_place::depth_first_in_place;
+pub use in_place::minmax_in_place;
 pub use minmax::minmax;
+pub use mtdf::mtdf;
+pub use negamax::ActionsFn;
+pub use negamax::Negamax;
+pub use ordering::History;
+pub use ordering::Killers;
+pub use ordering::OrderKey;
+pub use ordering::OrderingFn;
+pub use ordering::OrderingTable;
+pub use ordering::OrderingTableFn;
+pub use pruning::LateMoveReductions;
+pub use pruning::NullMovePruning;
+pub use quiescence::quiescence;
+pub use repetitions::Repetitions;
+pub use search_node::ArenaNode;
+pub use search_node::CachedNode;
+pub use search_node::InPlaceNode;
+pub use search_node::SearchNode;
+pub use search_node::Searched;
+pub use search_node::StatelessNode;
+pub use solve::solve;
+pub use solve::solve_symmetric;
+pub use solve::Solution;
+pub use solve::Solved;
+pub use stats::SearchStats;
@@ -0,0 +1,29 @@
+use crate::abstractions::Environment;
+
+use crate::cache::node::NodeRRMM;
+
+use std::hash::Hash;
+
+use super::negamax::Negamax;
+use super::search_node::CachedNode;
+
+/// Estimates the value of the environment in 'node' like 'alphabeta', using MTD(f) instead
+/// of a single search. MTD(f) converges to the value through null window searches, reusing the
+/// bounds stored in the cache, and it is faster the closer 'first_guess' is to the value.
+pub fn mtdf<Action, AgentId, T>(
+    node: &NodeRRMM<T, Action, AgentId>,
+    agent_id: &AgentId,
+    reward: &dyn Fn(&T, &AgentId) -> f64,
+    depth: usize,
+    first_guess: f64,
+) -> f64
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let (value, _) =
+        Negamax::new(agent_id, reward).mtdf(&mut CachedNode::new(node), depth, first_guess);
+
+    value
+}
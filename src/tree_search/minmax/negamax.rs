@@ -0,0 +1,676 @@
+use std::marker::PhantomData;
+
+use crate::abstractions::Environment;
+use crate::abstractions::NoisyActions;
+use crate::cache::minmax_data::Bound;
+use crate::tablebase::ProbeFn;
+
+use super::ordering::OrderKey;
+use super::ordering::OrderingFn;
+use super::ordering::OrderingTable;
+use super::pruning::LateMoveReductions;
+use super::pruning::NullMovePruning;
+use super::repetitions::Repetitions;
+use super::search_node::SearchNode;
+use super::search_node::Searched;
+use super::stats::SearchStats;
+use super::utils::next_down;
+use super::utils::next_up;
+use super::utils::outcome_score;
+use super::utils::terminal_score;
+
+pub const MAX_DEPTH: usize = usize::MAX >> 1; // Any depth larger than this bring its values from a terminal node.
+
+/// Function producing a subset of the valid actions of an environment.
+pub type ActionsFn<Action, T> = fn(&T) -> Box<dyn Iterator<Item = Action>>;
+
+/// Negamax search core shared by 'minmax', 'alphabeta' and 'depth_first'.
+///
+/// Values inside the search are seen from the agent moving at each position, so that a single
+/// branch handles both maximizers and minimizers. The values returned by 'search' are seen
+/// from 'agent_id', like the reward function.
+pub struct Negamax<'a, Action, AgentId, T> {
+    agent_id: &'a AgentId,
+    reward: &'a dyn Fn(&T, &AgentId) -> f64,
+    pvs: bool,
+    quiescence: Option<(ActionsFn<Action, T>, usize)>,
+    ordering: Option<&'a OrderingFn<'a, Action, T>>,
+    killers: Option<Box<dyn OrderingTable<Action> + 'a>>,
+    history: Option<Box<dyn OrderingTable<Action> + 'a>>,
+    null_move: Option<NullMovePruning<T>>,
+    lmr: Option<LateMoveReductions>,
+    probe: Option<&'a ProbeFn<'a, T>>,
+    repetitions: Option<Repetitions<T>>,
+    cycle_level: usize,
+    after_null: bool,
+    stats: SearchStats,
+    _action: PhantomData<Action>,
+}
+
+impl<'a, Action, AgentId, T> Negamax<'a, Action, AgentId, T>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    pub fn new(agent_id: &'a AgentId, reward: &'a dyn Fn(&T, &AgentId) -> f64) -> Self {
+        Negamax {
+            agent_id,
+            reward,
+            pvs: false,
+            quiescence: None,
+            ordering: None,
+            killers: None,
+            history: None,
+            null_move: None,
+            lmr: None,
+            probe: None,
+            repetitions: None,
+            cycle_level: usize::MAX,
+            after_null: false,
+            stats: SearchStats::default(),
+            _action: PhantomData,
+        }
+    }
+
+    /// Enables Principal Variation Search. Every child but the first is scouted with a null
+    /// window, and only searched with the full window when the scout fails high.
+    pub fn with_pvs(mut self, pvs: bool) -> Self {
+        self.pvs = pvs;
+        self
+    }
+
+    /// Enables the quiescence search with a given function producing the noisy actions and
+    /// a maximum depth, or disables it with None. See 'with_quiescence'.
+    pub fn with_noisy_actions(mut self, quiescence: Option<(ActionsFn<Action, T>, usize)>) -> Self {
+        self.quiescence = quiescence;
+        self
+    }
+
+    /// Orders the actions of every position by decreasing score, as given by 'ordering'.
+    /// The user ordering goes first, and killer moves and history only break its ties.
+    pub fn with_ordering(mut self, ordering: Option<&'a OrderingFn<'a, Action, T>>) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    /// Tries the killer moves of each ply first, as tracked by 'killers'. See 'Killers'.
+    pub fn with_killers(mut self, killers: Option<Box<dyn OrderingTable<Action> + 'a>>) -> Self {
+        self.killers = killers;
+        self
+    }
+
+    /// Orders actions by the cutoffs they caused in the rest of the tree, as tracked by
+    /// 'history'. See 'History'.
+    pub fn with_history(mut self, history: Option<Box<dyn OrderingTable<Action> + 'a>>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Enables null-move pruning, or disables it with None. See 'NullMovePruning'.
+    pub fn with_null_move(mut self, null_move: Option<NullMovePruning<T>>) -> Self {
+        self.null_move = null_move;
+        self
+    }
+
+    /// Enables late move reductions, or disables them with None. See 'LateMoveReductions'.
+    pub fn with_lmr(mut self, lmr: Option<LateMoveReductions>) -> Self {
+        self.lmr = lmr;
+        self
+    }
+
+    /// Looks positions up in a tablebase, or stops doing it with None. Positions with a known
+    /// outcome are scored like terminal positions, except at the root, which is searched to
+    /// find an action.
+    pub fn with_probe(mut self, probe: Option<&'a ProbeFn<'a, T>>) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Scores positions repeating one on the path from the root, or one played before the
+    /// search, as draws. Results depending on those draws are not stored, so that cached values
+    /// do not depend on the path leading to a position. See 'Repetitions'.
+    pub fn with_repetitions(mut self, repetitions: Option<Repetitions<T>>) -> Self {
+        self.repetitions = repetitions;
+        self
+    }
+
+    /// Returns the counters collected by all the searches run so far.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// Searches 'node' up to 'depth' within the window [alpha, beta], and returns its value
+    /// together with the best action found.
+    pub fn search<N>(
+        &mut self,
+        node: &mut N,
+        depth: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> (f64, Option<Action>)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let is_maximizer = self.is_maximizer(node.environment());
+
+        if is_maximizer {
+            let searched = self.negamax(node, depth, 0, alpha, beta);
+            (searched.value, searched.action)
+        } else {
+            let searched = self.negamax(node, depth, 0, -beta, -alpha);
+            (-searched.value, searched.action)
+        }
+    }
+
+    /// Searches 'node' with a window of size 'window' around the 'previous' estimate of its
+    /// value. When the value falls outside of the window, the search is repeated with the
+    /// failing side of the window opened.
+    pub fn aspiration<N>(
+        &mut self,
+        node: &mut N,
+        depth: usize,
+        previous: f64,
+        window: f64,
+    ) -> (f64, Option<Action>)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let mut alpha = previous - window;
+        let mut beta = previous + window;
+
+        loop {
+            let (value, action) = self.search(node, depth, alpha, beta);
+
+            if value <= alpha && alpha > f64::NEG_INFINITY {
+                alpha = f64::NEG_INFINITY;
+            } else if value >= beta && beta < f64::INFINITY {
+                beta = f64::INFINITY;
+            } else {
+                return (value, action);
+            }
+
+            self.stats.aspiration_researches += 1;
+        }
+    }
+
+    /// Searches 'node' with increasing depths up to 'depth'. Every iteration uses an aspiration
+    /// window of size 'window' around the value found by the previous one.
+    pub fn iterative_deepening<N>(
+        &mut self,
+        node: &mut N,
+        depth: usize,
+        window: f64,
+    ) -> (f64, Option<Action>)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let mut output = self.search(node, depth.min(2), f64::NEG_INFINITY, f64::INFINITY);
+
+        for next_depth in 3..=depth {
+            output = self.aspiration(node, next_depth, output.0, window);
+        }
+
+        output
+    }
+
+    /// Finds the value of 'node' with MTD(f): a sequence of null window searches that move
+    /// the window towards the true value, starting from 'first_guess'. It relies on the node
+    /// storing the bounds found by each pass, so that later passes do not repeat the work.
+    pub fn mtdf<N>(&mut self, node: &mut N, depth: usize, first_guess: f64) -> (f64, Option<Action>)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let mut value = first_guess;
+        let mut action = None;
+        let mut lower = f64::NEG_INFINITY;
+        let mut upper = f64::INFINITY;
+
+        while lower < upper {
+            let beta = if value == lower {
+                next_up(value)
+            } else {
+                value
+            };
+
+            self.stats.mtdf_passes += 1;
+            let (next_value, next_action) = self.search(node, depth, next_down(beta), beta);
+            value = next_value;
+
+            if value < beta {
+                upper = value;
+                if action.is_none() {
+                    action = next_action;
+                }
+            } else {
+                lower = value;
+                action = next_action;
+            }
+        }
+
+        (value, action)
+    }
+
+    /// Returns the value of 'env' for 'agent_id' after a quiescence search, within the window
+    /// [alpha, beta], or None if the quiescence search is not enabled with 'with_quiescence'.
+    pub fn quiescence(&mut self, env: &T, alpha: f64, beta: f64) -> Option<f64> {
+        let (noisy_actions, max_depth) = self.quiescence?;
+
+        if self.is_maximizer(env) {
+            Some(self.quiesce(env, noisy_actions, max_depth, alpha, beta))
+        } else {
+            Some(-self.quiesce(env, noisy_actions, max_depth, -beta, -alpha))
+        }
+    }
+
+    /// Returns true iff 'agent_id' is the one moving in 'env'.
+    fn is_maximizer(&self, env: &T) -> bool {
+        env.turn() == *self.agent_id
+    }
+
+    /// Returns the priority of trying 'action' in 'env', at 'ply' plies from the root.
+    fn order_key(&self, env: &T, action: &Action, ply: usize) -> OrderKey {
+        [
+            self.ordering.map_or(0f64, |ordering| ordering(env, action)),
+            self.killers
+                .as_ref()
+                .map_or(0f64, |killers| killers.score(action, ply)),
+            self.history
+                .as_ref()
+                .map_or(0f64, |history| history.score(action, ply)),
+        ]
+    }
+
+    /// Searches a node 'ply' plies away from the root. The value returned is seen from the
+    /// agent moving at the node.
+    fn negamax<N>(
+        &mut self,
+        node: &mut N,
+        depth: usize,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> Searched<Action>
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        self.stats.nodes += 1;
+        let after_null = std::mem::replace(&mut self.after_null, false);
+
+        let env = node.environment();
+        let is_maximizer = self.is_maximizer(env);
+
+        // Positions already on the path are draws by repetition. The root is searched anyway,
+        // even if it repeats a position played before the search.
+        let repeated = match &mut self.repetitions {
+            Some(repetitions) => repetitions.enter(env, ply),
+            None => None,
+        };
+        match repeated {
+            Some(level) if ply > 0 => {
+                self.stats.repetitions += 1;
+                self.cycle_level = self.cycle_level.min(level);
+
+                return Searched {
+                    value: 0f64,
+                    depth: MAX_DEPTH,
+                    bound: Bound::Exact,
+                    action: None,
+                };
+            }
+            _ => (),
+        }
+
+        let outer_cycle_level = std::mem::replace(&mut self.cycle_level, usize::MAX);
+        let (searched, is_new) =
+            self.search_position(node, is_maximizer, depth, ply, alpha, beta, after_null);
+
+        // Values depending on a repetition of a position above this one depend on the path
+        // leading here, so they are not remembered.
+        if is_new && self.cycle_level > ply {
+            node.store(&searched, is_maximizer);
+        }
+        self.cycle_level = self.cycle_level.min(outer_cycle_level);
+
+        if let (Some(repetitions), None) = (&mut self.repetitions, repeated) {
+            repetitions.leave(node.environment());
+        }
+
+        searched
+    }
+
+    /// Searches a position that is not a repetition. Returns the result, and whether it is new
+    /// rather than a stored result answering the search.
+    #[allow(clippy::too_many_arguments)]
+    fn search_position<N>(
+        &mut self,
+        node: &mut N,
+        is_maximizer: bool,
+        depth: usize,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+        after_null: bool,
+    ) -> (Searched<Action>, bool)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let env = node.environment();
+        let sign = if is_maximizer { 1f64 } else { -1f64 };
+
+        if let Some(searched) = node.lookup(depth, is_maximizer) {
+            if searched.fits(alpha, beta) {
+                return (searched, false);
+            }
+        }
+
+        let searched = if env.is_terminal() {
+            Searched {
+                value: sign * terminal_score(env, self.agent_id),
+                depth: MAX_DEPTH,
+                bound: Bound::Exact,
+                action: None,
+            }
+        } else if let Some(outcome) = self.probe.filter(|_| ply > 0).and_then(|probe| probe(env)) {
+            self.stats.tablebase_hits += 1;
+
+            Searched {
+                value: outcome_score(outcome),
+                depth: MAX_DEPTH,
+                bound: Bound::Exact,
+                action: None,
+            }
+        } else if depth <= 1 {
+            let (value, bound) = match self.quiescence {
+                Some((noisy_actions, max_depth)) => {
+                    let value = self.quiesce(env, noisy_actions, max_depth, alpha, beta);
+                    (value, Bound::within(value, alpha, beta))
+                }
+                None => (sign * (self.reward)(env, self.agent_id), Bound::Exact),
+            };
+
+            Searched {
+                value,
+                depth: 1,
+                bound,
+                action: None,
+            }
+        } else if let Some(searched) = self.null_move_search(node, depth, ply, beta, after_null) {
+            searched
+        } else {
+            self.search_children(node, is_maximizer, depth, ply, alpha, beta)
+        };
+
+        (searched, true)
+    }
+
+    /// Null-move pruning. Lets the agent moving in 'node' pass, and searches the result with a
+    /// null window at 'beta' and a reduced depth. Returns the result if passing fails high, so
+    /// that the node can be pruned. Two null moves in a row are not allowed. The result is only
+    /// trusted for the reduced depth, so that deeper searches don't take it from the cache.
+    fn null_move_search<N>(
+        &mut self,
+        node: &mut N,
+        depth: usize,
+        ply: usize,
+        beta: f64,
+        after_null: bool,
+    ) -> Option<Searched<Action>>
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        if ply == 0 || after_null {
+            return None;
+        }
+
+        let null_move = self.null_move?;
+        let env = node.environment();
+        let mut child = node.child_from(null_move.null_move(env, depth)?);
+        let same_agent = self.is_maximizer(child.environment()) == self.is_maximizer(env);
+
+        self.stats.null_move_searches += 1;
+        self.after_null = true;
+        let (value, _) = self.negamax_child(
+            &mut child,
+            same_agent,
+            depth - 1 - null_move.reduction,
+            ply,
+            next_down(beta),
+            beta,
+        );
+        self.after_null = false;
+
+        if value >= beta {
+            self.stats.null_move_cutoffs += 1;
+            Some(Searched {
+                value,
+                depth: depth - null_move.reduction,
+                bound: Bound::Lower,
+                action: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Searches the children of 'node', which is 'ply' plies away from the root, within the
+    /// window [alpha, beta]. The value returned is seen from the agent moving at the node.
+    fn search_children<N>(
+        &mut self,
+        node: &mut N,
+        is_maximizer: bool,
+        depth: usize,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> Searched<Action>
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let mut best = Searched {
+            value: f64::NEG_INFINITY,
+            depth,
+            bound: Bound::Exact,
+            action: None,
+        };
+        let mut next_alpha = alpha;
+
+        node.reset_children();
+        if self.ordering.is_some() || self.killers.is_some() || self.history.is_some() {
+            node.order_children(&|env, a| self.order_key(env, a, ply));
+        }
+
+        let mut index = 0;
+        while let Some((mut child, action)) = node.next_child() {
+            let same_agent = self.is_maximizer(child.environment()) == is_maximizer;
+
+            let (value, child_depth) = if index == 0 {
+                self.negamax_child(&mut child, same_agent, depth - 1, ply, next_alpha, beta)
+            } else {
+                self.search_late_child(&mut child, same_agent, index, depth, ply, next_alpha, beta)
+            };
+            index += 1;
+
+            if value > best.value {
+                best.value = value;
+                best.depth = child_depth + 1;
+                best.action = Some(action);
+            }
+
+            if best.value > next_alpha {
+                next_alpha = best.value;
+            }
+
+            if next_alpha >= beta {
+                if let Some(action) = &best.action {
+                    self.add_cutoff(action, ply, depth);
+                }
+                break;
+            }
+        }
+        node.end_children();
+
+        if index == 0 {
+            // Positions without actions end the game, even if the environment does not say so.
+            let sign = if is_maximizer { 1f64 } else { -1f64 };
+            return Searched {
+                value: sign * terminal_score(node.environment(), self.agent_id),
+                depth: MAX_DEPTH,
+                bound: Bound::Exact,
+                action: None,
+            };
+        }
+
+        best.bound = Bound::within(best.value, alpha, beta);
+
+        best
+    }
+
+    /// Searches the 'index'-th child of a node, after the first one. Late children may be
+    /// searched with a reduced depth, and with Principal Variation Search they are scouted with
+    /// a null window first. Returns the value seen by the parent and the depth the value can be
+    /// trusted for.
+    #[allow(clippy::too_many_arguments)]
+    fn search_late_child<N>(
+        &mut self,
+        child: &mut N,
+        same_agent: bool,
+        index: usize,
+        depth: usize,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> (f64, usize)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        let reduction = self.lmr.map_or(0, |lmr| lmr.reduction(index, depth));
+
+        if reduction > 0 {
+            self.stats.lmr_reductions += 1;
+            let reduced = self.negamax_child(
+                child,
+                same_agent,
+                depth - 1 - reduction,
+                ply,
+                alpha,
+                next_up(alpha),
+            );
+
+            if reduced.0 <= alpha {
+                return reduced;
+            }
+            self.stats.lmr_researches += 1;
+        }
+
+        if !self.pvs {
+            return self.negamax_child(child, same_agent, depth - 1, ply, alpha, beta);
+        }
+
+        let scout = self.negamax_child(child, same_agent, depth - 1, ply, alpha, next_up(alpha));
+
+        if scout.0 > alpha && scout.0 < beta {
+            self.stats.pvs_researches += 1;
+            self.negamax_child(child, same_agent, depth - 1, ply, alpha, beta)
+        } else {
+            scout
+        }
+    }
+
+    /// Quiescence search. Keeps searching the noisy actions of 'env' for up to 'depth' plies,
+    /// letting the agent moving stand pat on the reward when no noisy action improves it.
+    /// The value returned is seen from the agent moving in 'env'.
+    fn quiesce(
+        &mut self,
+        env: &T,
+        noisy_actions: ActionsFn<Action, T>,
+        depth: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> f64 {
+        self.stats.quiescence_nodes += 1;
+
+        let is_maximizer = self.is_maximizer(env);
+        let sign = if is_maximizer { 1f64 } else { -1f64 };
+
+        if env.is_terminal() {
+            return sign * terminal_score(env, self.agent_id);
+        }
+
+        let mut best = sign * (self.reward)(env, self.agent_id);
+        if best >= beta || depth == 0 {
+            return best;
+        }
+
+        let mut next_alpha = alpha.max(best);
+        for action in noisy_actions(env) {
+            let next_env = env.what_if(&action);
+
+            let value = if self.is_maximizer(&next_env) == is_maximizer {
+                self.quiesce(&next_env, noisy_actions, depth - 1, next_alpha, beta)
+            } else {
+                -self.quiesce(&next_env, noisy_actions, depth - 1, -beta, -next_alpha)
+            };
+
+            if value > best {
+                best = value;
+            }
+
+            if best > next_alpha {
+                next_alpha = best;
+            }
+
+            if next_alpha >= beta {
+                break;
+            }
+        }
+
+        best
+    }
+
+    /// Records a cutoff in the killer moves and history tables.
+    fn add_cutoff(&mut self, action: &Action, ply: usize, depth: usize) {
+        if let Some(killers) = &mut self.killers {
+            killers.add_cutoff(action, ply, depth);
+        }
+
+        if let Some(history) = &mut self.history {
+            history.add_cutoff(action, ply, depth);
+        }
+    }
+
+    /// Searches a child of a node 'ply' plies away from the root, within the window
+    /// [alpha, beta] as seen by its parent. Returns the value seen by the parent and the
+    /// depth the value can be trusted for.
+    fn negamax_child<N>(
+        &mut self,
+        child: &mut N,
+        same_agent: bool,
+        depth: usize,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> (f64, usize)
+    where
+        N: SearchNode<Action, AgentId, Env = T>,
+    {
+        if same_agent {
+            let searched = self.negamax(child, depth, ply + 1, alpha, beta);
+            (searched.value, searched.depth)
+        } else {
+            let searched = self.negamax(child, depth, ply + 1, -beta, -alpha);
+            (-searched.value, searched.depth)
+        }
+    }
+}
+
+impl<'a, Action, AgentId, T> Negamax<'a, Action, AgentId, T>
+where
+    AgentId: Eq,
+    T: NoisyActions<Action, AgentId>,
+{
+    /// Enables the quiescence search. Instead of evaluating the reward at the nominal depth,
+    /// the search follows noisy actions for up to 'max_depth' more plies.
+    pub fn with_quiescence(self, max_depth: usize) -> Self {
+        self.with_noisy_actions(Some((T::noisy_actions, max_depth)))
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Priority of an action when ordering the children of a position. Keys are compared
+/// lexicographically, and actions with larger keys are tried first. The entries are the
+/// user supplied score, the killer move rank, and the history score.
+pub type OrderKey = [f64; 3];
+
+/// Function scoring an action in an environment. Actions with larger scores are tried first.
+pub type OrderingFn<'a, Action, T> = dyn Fn(&T, &Action) -> f64 + 'a;
+
+/// Function creating an empty ordering table for every search.
+pub type OrderingTableFn<'a, Action> = fn() -> Box<dyn OrderingTable<Action> + 'a>;
+
+/// A table that learns which actions cause cutoffs, so that they can be tried first.
+pub trait OrderingTable<Action> {
+    /// Returns the priority of 'action' at 'ply' plies from the root. Larger is tried first.
+    fn score(&self, action: &Action, ply: usize) -> f64;
+
+    /// Records that 'action' caused a cutoff at 'ply' plies from the root, with 'depth'
+    /// plies left to search.
+    fn add_cutoff(&mut self, action: &Action, ply: usize, depth: usize);
+}
+
+/// Killer moves. Keeps the last two actions that caused a cutoff at each ply, as they are
+/// likely to cause cutoffs in the sibling positions too.
+pub struct Killers<Action> {
+    slots: Vec<[Option<Action>; 2]>,
+}
+
+impl<Action> Killers<Action> {
+    pub fn new() -> Self {
+        Killers { slots: Vec::new() }
+    }
+}
+
+impl<Action> Default for Killers<Action> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Action> OrderingTable<Action> for Killers<Action>
+where
+    Action: Clone + PartialEq,
+{
+    fn score(&self, action: &Action, ply: usize) -> f64 {
+        match self.slots.get(ply) {
+            Some([Some(killer), _]) if killer == action => 2f64,
+            Some([_, Some(killer)]) if killer == action => 1f64,
+            _ => 0f64,
+        }
+    }
+
+    fn add_cutoff(&mut self, action: &Action, ply: usize, _depth: usize) {
+        if self.slots.len() <= ply {
+            self.slots.resize_with(ply + 1, || [None, None]);
+        }
+
+        let slots = &mut self.slots[ply];
+        if slots[0].as_ref() != Some(action) {
+            slots[1] = slots[0].take();
+            slots[0] = Some(action.clone());
+        }
+    }
+}
+
+/// History heuristic. Scores every action by the cutoffs it caused anywhere in the tree,
+/// weighting deeper cutoffs more.
+pub struct History<Action> {
+    table: HashMap<Action, f64>,
+}
+
+impl<Action> History<Action>
+where
+    Action: Hash + Eq,
+{
+    pub fn new() -> Self {
+        History {
+            table: HashMap::new(),
+        }
+    }
+}
+
+impl<Action> Default for History<Action>
+where
+    Action: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Action> OrderingTable<Action> for History<Action>
+where
+    Action: Clone + Hash + Eq,
+{
+    fn score(&self, action: &Action, _ply: usize) -> f64 {
+        self.table.get(action).copied().unwrap_or(0f64)
+    }
+
+    fn add_cutoff(&mut self, action: &Action, _ply: usize, depth: usize) {
+        let depth = depth as f64;
+        *self.table.entry(action.clone()).or_insert(0f64) += depth * depth;
+    }
+}
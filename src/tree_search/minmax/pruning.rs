@@ -0,0 +1,82 @@
+#[cfg(feature = "serde")]
+use serde::Deserialize;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::abstractions::NullMove;
+
+/// Configuration of null-move pruning. Before searching a position, the agent moving passes
+/// its turn and the position is searched 'reduction' plies shallower with a null window. If
+/// the value still fails high, the position is pruned.
+pub struct NullMovePruning<T> {
+    /// Depth reduction of the search after passing.
+    pub reduction: usize,
+    null_move: fn(&T) -> T,
+    allowed: fn(&T) -> bool,
+}
+
+impl<T> NullMovePruning<T> {
+    pub fn new<Action, AgentId>(reduction: usize) -> Self
+    where
+        T: NullMove<Action, AgentId>,
+    {
+        NullMovePruning {
+            reduction,
+            null_move: T::null_move,
+            allowed: T::null_move_allowed,
+        }
+    }
+
+    /// Returns the environment after passing, if passing is allowed and there is enough depth
+    /// left to search it.
+    pub(super) fn null_move(&self, env: &T, depth: usize) -> Option<T> {
+        if depth > self.reduction + 1 && (self.allowed)(env) {
+            Some((self.null_move)(env))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Clone for NullMovePruning<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NullMovePruning<T> {}
+
+/// Configuration of late move reductions. Actions tried late are unlikely to be the best
+/// ones, so they are searched 'reduction' plies shallower with a null window. Only the ones
+/// that fail high are searched again at full depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LateMoveReductions {
+    /// Number of actions searched at full depth before reducing.
+    pub after: usize,
+    /// Minimum depth left to reduce.
+    pub min_depth: usize,
+    /// Depth reduction of late actions.
+    pub reduction: usize,
+}
+
+impl LateMoveReductions {
+    /// Returns how much to reduce the search of the action tried in 'index'-th place.
+    pub(super) fn reduction(&self, index: usize, depth: usize) -> usize {
+        if index >= self.after && depth >= self.min_depth {
+            self.reduction.min(depth - 2)
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for LateMoveReductions {
+    fn default() -> Self {
+        LateMoveReductions {
+            after: 3,
+            min_depth: 3,
+            reduction: 1,
+        }
+    }
+}
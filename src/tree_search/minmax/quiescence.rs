@@ -0,0 +1,24 @@
+use crate::abstractions::NoisyActions;
+
+use super::negamax::Negamax;
+
+/// Returns the value of an environment once it is quiet. Starting from 'env', the function
+/// follows noisy actions for up to 'max_depth' plies, and each agent can stop following them
+/// and take the reward at any point.
+pub fn quiescence<Action, AgentId, T>(
+    env: &T,
+    agent_id: &AgentId,
+    reward: &dyn Fn(&T, &AgentId) -> f64,
+    max_depth: usize,
+    alpha: f64,
+    beta: f64,
+) -> f64
+where
+    AgentId: Eq,
+    T: NoisyActions<Action, AgentId>,
+{
+    Negamax::new(agent_id, reward)
+        .with_quiescence(max_depth)
+        .quiescence(env, alpha, beta)
+        .expect("The quiescence search is enabled")
+}
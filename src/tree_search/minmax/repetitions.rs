@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::abstractions::Zobrist;
+use crate::cache::utils::hash;
+
+/// Positions on the path from the root of a search, and the ones played before it, identified
+/// by a 64-bit key. Each of them is remembered with the level it was first seen at: 0 for the
+/// positions played before the search, and one more than its ply for the ones on the path.
+///
+/// Positions with colliding keys are taken as repetitions.
+pub struct Repetitions<T> {
+    key: fn(&T) -> u64,
+    levels: HashMap<u64, usize>,
+}
+
+impl<T> Repetitions<T> {
+    /// Identifies positions by their hash.
+    pub fn new() -> Self
+    where
+        T: Hash,
+    {
+        Repetitions {
+            key: hash,
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Identifies positions by their Zobrist key.
+    pub fn zobrist<Action, AgentId>() -> Self
+    where
+        T: Zobrist<Action, AgentId>,
+    {
+        Repetitions {
+            key: T::zobrist,
+            levels: HashMap::new(),
+        }
+    }
+
+    /// Adds positions played before the search.
+    pub fn with_previous<'b>(mut self, positions: impl IntoIterator<Item = &'b T>) -> Self
+    where
+        T: 'b,
+    {
+        for env in positions {
+            self.levels.insert((self.key)(env), 0);
+        }
+        self
+    }
+
+    /// Enters 'env', 'ply' plies away from the root. Returns the level of the position it
+    /// repeats, if any, in which case it is not entered.
+    pub(super) fn enter(&mut self, env: &T, ply: usize) -> Option<usize> {
+        let key = (self.key)(env);
+
+        match self.levels.get(&key) {
+            Some(level) => Some(*level),
+            None => {
+                self.levels.insert(key, ply + 1);
+                None
+            }
+        }
+    }
+
+    /// Leaves a position entered before.
+    pub(super) fn leave(&mut self, env: &T) {
+        self.levels.remove(&(self.key)(env));
+    }
+}
+
+impl<T: Hash> Default for Repetitions<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,510 @@
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use crate::abstractions::Environment;
+use crate::abstractions::Undo;
+
+use crate::cache::arena::ArenaMM;
+use crate::cache::arena::NodeId;
+use crate::cache::minmax_data::Bound;
+use crate::cache::minmax_data::MinMaxData;
+use crate::cache::node::NodeRRMM;
+
+use super::ordering::OrderKey;
+
+/// Result of searching a position. The value is seen from the agent moving at that position,
+/// and it can be trusted for searches up to 'depth'. Searches that fall outside of their
+/// window only produce a bound of the true value.
+#[derive(Clone, Copy, Debug)]
+pub struct Searched<Action> {
+    pub value: f64,
+    pub depth: usize,
+    pub bound: Bound,
+    pub action: Option<Action>,
+}
+
+impl<Action> Searched<Action> {
+    /// Returns true iff this result answers a search within the window [alpha, beta].
+    pub fn fits(&self, alpha: f64, beta: f64) -> bool {
+        match self.bound {
+            Bound::Exact => true,
+            Bound::Lower => self.value >= beta,
+            Bound::Upper => self.value <= alpha,
+        }
+    }
+}
+
+/// A position in the game tree as seen by the negamax search core. Implementors decide
+/// how children are produced, and whether search results are remembered between calls.
+pub trait SearchNode<Action, AgentId>: Sized {
+    type Env: Environment<Action, AgentId>;
+
+    /// Returns the environment at this position.
+    fn environment(&self) -> &Self::Env;
+
+    /// Returns a previously stored result if it was searched at least up to 'depth'.
+    /// 'is_maximizer' tells whether the agent moving at this position is the searching agent.
+    fn lookup(&self, _depth: usize, _is_maximizer: bool) -> Option<Searched<Action>> {
+        None
+    }
+
+    /// Remembers the result of searching this position.
+    fn store(&mut self, _searched: &Searched<Action>, _is_maximizer: bool) {}
+
+    /// Restarts the iteration over the children of this position.
+    fn reset_children(&mut self) {}
+
+    /// Reorders the children that have not been produced yet, so that the ones with larger
+    /// keys come first.
+    fn order_children(&mut self, _key: &dyn Fn(&Self::Env, &Action) -> OrderKey) {}
+
+    /// Returns the next child of this position, together with the action leading to it.
+    fn next_child(&mut self) -> Option<(Self, Action)>;
+
+    /// Tells that the children of this position are searched, and dropped.
+    fn end_children(&mut self) {}
+
+    /// Returns a node for an environment reached from this position by other means than its
+    /// actions, like passing the turn.
+    fn child_from(&self, env: Self::Env) -> Self;
+}
+
+/// Environment that is either borrowed from the caller or owned by the search.
+enum EnvRef<'e, T> {
+    Borrowed(&'e T),
+    Owned(T),
+}
+
+/// Actions left to try in a position. They come from 'valid_actions' until they are ordered.
+enum Actions<Action> {
+    Valid(Box<dyn Iterator<Item = Action>>),
+    Ordered(std::vec::IntoIter<Action>),
+}
+
+impl<Action> Iterator for Actions<Action> {
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Action> {
+        match self {
+            Actions::Valid(actions) => actions.next(),
+            Actions::Ordered(actions) => actions.next(),
+        }
+    }
+}
+
+/// A node that does not remember anything. Children are produced with 'what_if' in the
+/// order given by 'valid_actions', unless they are ordered.
+pub struct StatelessNode<'e, Action, T> {
+    env: EnvRef<'e, T>,
+    actions: Option<Actions<Action>>,
+}
+
+impl<'e, Action, T> StatelessNode<'e, Action, T> {
+    pub fn new(env: &'e T) -> Self {
+        StatelessNode {
+            env: EnvRef::Borrowed(env),
+            actions: None,
+        }
+    }
+
+    fn owned(env: T) -> Self {
+        StatelessNode {
+            env: EnvRef::Owned(env),
+            actions: None,
+        }
+    }
+}
+
+impl<'e, Action, AgentId, T> SearchNode<Action, AgentId> for StatelessNode<'e, Action, T>
+where
+    T: Environment<Action, AgentId>,
+{
+    type Env = T;
+
+    fn environment(&self) -> &T {
+        match &self.env {
+            EnvRef::Borrowed(env) => env,
+            EnvRef::Owned(env) => env,
+        }
+    }
+
+    fn reset_children(&mut self) {
+        self.actions = None;
+    }
+
+    fn order_children(&mut self, key: &dyn Fn(&T, &Action) -> OrderKey) {
+        let env = match &self.env {
+            EnvRef::Borrowed(env) => *env,
+            EnvRef::Owned(env) => env,
+        };
+        let actions = self
+            .actions
+            .take()
+            .unwrap_or_else(|| Actions::Valid(env.valid_actions()));
+
+        let ordered = sort_by_key(actions.collect(), |a| key(env, a));
+        self.actions = Some(Actions::Ordered(ordered.into_iter()));
+    }
+
+    fn next_child(&mut self) -> Option<(Self, Action)> {
+        let env = match &self.env {
+            EnvRef::Borrowed(env) => *env,
+            EnvRef::Owned(env) => env,
+        };
+        let actions = self
+            .actions
+            .get_or_insert_with(|| Actions::Valid(env.valid_actions()));
+
+        actions
+            .next()
+            .map(|a| (StatelessNode::owned(env.what_if(&a)), a))
+    }
+
+    fn child_from(&self, env: T) -> Self {
+        StatelessNode::owned(env)
+    }
+}
+
+/// A node playing actions in place with 'Undo::make', on a single environment shared by the
+/// whole line being searched. The environment is held by the deepest node of the line: a
+/// child takes it from its parent, and gives it back through 'slot' when dropped, so that the
+/// parent can take the action back with 'Undo::unmake'. Dropping the nodes, even while
+/// unwinding from a panic, leaves the environment as it was found. It panics if 'Undo::make'
+/// fails on an action given by 'valid_actions'.
+pub struct InPlaceNode<'e, Action, AgentId, T>
+where
+    T: Undo<Action, AgentId>,
+{
+    env: Option<EnvMut<'e, T>>,
+    slot: Rc<RefCell<Option<EnvMut<'e, T>>>>,
+    actions: Option<Actions<Action>>,
+    token: Option<T::Token>,
+}
+
+/// Environment that is either borrowed mutably from the caller or owned by the search.
+enum EnvMut<'e, T> {
+    Borrowed(&'e mut T),
+    Owned(T),
+}
+
+impl<'e, T> EnvMut<'e, T> {
+    fn get(&self) -> &T {
+        match self {
+            EnvMut::Borrowed(env) => env,
+            EnvMut::Owned(env) => env,
+        }
+    }
+
+    fn get_mut(&mut self) -> &mut T {
+        match self {
+            EnvMut::Borrowed(env) => env,
+            EnvMut::Owned(env) => env,
+        }
+    }
+}
+
+impl<'e, Action, AgentId, T> InPlaceNode<'e, Action, AgentId, T>
+where
+    T: Undo<Action, AgentId>,
+{
+    pub fn new(env: &'e mut T) -> Self {
+        Self::with_env(EnvMut::Borrowed(env))
+    }
+
+    fn with_env(env: EnvMut<'e, T>) -> Self {
+        InPlaceNode {
+            env: Some(env),
+            slot: Rc::new(RefCell::new(None)),
+            actions: None,
+            token: None,
+        }
+    }
+
+    /// Takes the environment back from the last child, and unmakes the action leading to it.
+    fn take_back(&mut self) {
+        if let Some(token) = self.token.take() {
+            let mut env = self
+                .slot
+                .borrow_mut()
+                .take()
+                .expect("Children give the environment back when dropped");
+            env.get_mut().unmake(token);
+            self.env = Some(env);
+        }
+    }
+}
+
+impl<'e, Action, AgentId, T> Drop for InPlaceNode<'e, Action, AgentId, T>
+where
+    T: Undo<Action, AgentId>,
+{
+    fn drop(&mut self) {
+        self.take_back();
+        if let Some(env) = self.env.take() {
+            *self.slot.borrow_mut() = Some(env);
+        }
+    }
+}
+
+impl<'e, Action, AgentId, T> SearchNode<Action, AgentId> for InPlaceNode<'e, Action, AgentId, T>
+where
+    T: Undo<Action, AgentId>,
+{
+    type Env = T;
+
+    fn environment(&self) -> &T {
+        self.env
+            .as_ref()
+            .expect("The environment is held by a child of this node")
+            .get()
+    }
+
+    fn reset_children(&mut self) {
+        self.take_back();
+        self.actions = None;
+    }
+
+    fn order_children(&mut self, key: &dyn Fn(&T, &Action) -> OrderKey) {
+        self.take_back();
+        let env = self
+            .env
+            .as_ref()
+            .expect("The node holds its environment")
+            .get();
+        let actions = match self.actions.take() {
+            Some(actions) => actions,
+            None => Actions::Valid(env.valid_actions()),
+        };
+
+        let ordered = sort_by_key(actions.collect(), |a| key(env, a));
+        self.actions = Some(Actions::Ordered(ordered.into_iter()));
+    }
+
+    fn next_child(&mut self) -> Option<(Self, Action)> {
+        self.take_back();
+        let mut env = self.env.take().expect("The node holds its environment");
+        let action = self
+            .actions
+            .get_or_insert_with(|| Actions::Valid(env.get().valid_actions()))
+            .next();
+
+        let token = match action.as_ref().map(|a| env.get_mut().make(a)) {
+            Some(Some(token)) => token,
+            Some(None) => {
+                // Given back before panicking, so that the environment is left untouched.
+                self.env = Some(env);
+                panic!("'Undo::make' failed on an action given by 'valid_actions'");
+            }
+            None => {
+                self.env = Some(env);
+                return None;
+            }
+        };
+        self.token = Some(token);
+
+        let child = InPlaceNode {
+            env: Some(env),
+            slot: self.slot.clone(),
+            actions: None,
+            token: None,
+        };
+        action.map(|a| (child, a))
+    }
+
+    fn end_children(&mut self) {
+        self.take_back();
+    }
+
+    fn child_from(&self, env: T) -> Self {
+        InPlaceNode::with_env(EnvMut::Owned(env))
+    }
+}
+
+/// A node backed by the shared node cache. Results are stored in the cache, and children
+/// that have been visited before are tried first, sorted by their cached value.
+pub struct CachedNode<T, Action, AgentId>
+where
+    T: Environment<Action, AgentId>,
+{
+    node: NodeRRMM<T, Action, AgentId>,
+    env: Rc<T>,
+}
+
+impl<T, Action, AgentId> CachedNode<T, Action, AgentId>
+where
+    T: Environment<Action, AgentId> + Eq + Hash,
+{
+    pub fn new(node: &NodeRRMM<T, Action, AgentId>) -> Self {
+        let env = node.borrow().environment().clone();
+        CachedNode {
+            node: node.clone(),
+            env,
+        }
+    }
+}
+
+impl<T, Action, AgentId> SearchNode<Action, AgentId> for CachedNode<T, Action, AgentId>
+where
+    Action: Copy,
+    T: Environment<Action, AgentId> + Eq + Hash,
+{
+    type Env = T;
+
+    fn environment(&self) -> &T {
+        &self.env
+    }
+
+    fn lookup(&self, depth: usize, is_maximizer: bool) -> Option<Searched<Action>> {
+        lookup_data(&self.node.borrow().data, depth, is_maximizer)
+    }
+
+    fn store(&mut self, searched: &Searched<Action>, is_maximizer: bool) {
+        store_data(&mut self.node.borrow_mut().data, searched, is_maximizer);
+    }
+
+    fn reset_children(&mut self) {
+        let mut node_ptr = self.node.borrow_mut();
+        node_ptr.reset();
+        node_ptr.sort_children();
+    }
+
+    fn order_children(&mut self, key: &dyn Fn(&T, &Action) -> OrderKey) {
+        let env = &self.env;
+        self.node
+            .borrow_mut()
+            .order_unvisited(|actions| sort_by_key(actions, |a| key(env, a)));
+    }
+
+    fn next_child(&mut self) -> Option<(Self, Action)> {
+        let next = self.node.borrow_mut().next();
+        next.map(|(node, a)| (CachedNode::new(&node), a))
+    }
+
+    fn child_from(&self, env: T) -> Self {
+        let node = self.node.borrow().cache_get_or_insert(&Rc::new(env));
+        CachedNode::new(&node)
+    }
+}
+
+/// A node backed by an 'Arena' shared by the whole search. It behaves like 'CachedNode', but
+/// the arena is only borrowed for the duration of each call, never across the recursion.
+pub struct ArenaNode<'a, T, Action> {
+    arena: &'a RefCell<ArenaMM<T, Action>>,
+    id: NodeId,
+    env: T,
+}
+
+impl<'a, T, Action> ArenaNode<'a, T, Action>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new(arena: &'a RefCell<ArenaMM<T, Action>>, id: NodeId) -> Self {
+        let env = arena.borrow().environment(id).clone();
+        ArenaNode { arena, id, env }
+    }
+}
+
+impl<'a, T, Action, AgentId> SearchNode<Action, AgentId> for ArenaNode<'a, T, Action>
+where
+    Action: Copy,
+    T: Environment<Action, AgentId> + Eq + Hash + Clone,
+{
+    type Env = T;
+
+    fn environment(&self) -> &T {
+        &self.env
+    }
+
+    fn lookup(&self, depth: usize, is_maximizer: bool) -> Option<Searched<Action>> {
+        lookup_data(self.arena.borrow().data(self.id), depth, is_maximizer)
+    }
+
+    fn store(&mut self, searched: &Searched<Action>, is_maximizer: bool) {
+        store_data(
+            self.arena.borrow_mut().data_mut(self.id),
+            searched,
+            is_maximizer,
+        );
+    }
+
+    fn reset_children(&mut self) {
+        let mut arena = self.arena.borrow_mut();
+        arena.reset(self.id);
+        arena.sort_children(self.id);
+    }
+
+    fn order_children(&mut self, key: &dyn Fn(&T, &Action) -> OrderKey) {
+        let env = &self.env;
+        self.arena
+            .borrow_mut()
+            .order_unvisited(self.id, |actions| sort_by_key(actions, |a| key(env, a)));
+    }
+
+    fn next_child(&mut self) -> Option<(Self, Action)> {
+        let next = self.arena.borrow_mut().next_child(self.id);
+        next.map(|(id, a)| (ArenaNode::new(self.arena, id), a))
+    }
+
+    fn child_from(&self, env: T) -> Self {
+        let id = self.arena.borrow_mut().insert(env);
+        ArenaNode::new(self.arena, id)
+    }
+}
+
+/// Returns the result stored in 'data', seen from the agent moving, if it was searched at
+/// least up to 'depth'.
+fn lookup_data<Action>(
+    data: &MinMaxData<Action>,
+    depth: usize,
+    is_maximizer: bool,
+) -> Option<Searched<Action>>
+where
+    Action: Copy,
+{
+    if data.depth < depth {
+        None
+    } else if is_maximizer {
+        Some(Searched {
+            value: data.value,
+            depth: data.depth,
+            bound: data.bound,
+            action: data.action,
+        })
+    } else {
+        Some(Searched {
+            value: -data.value,
+            depth: data.depth,
+            bound: data.bound.flip(),
+            action: data.action,
+        })
+    }
+}
+
+/// Stores 'searched' in 'data', where values are seen from the searching agent.
+fn store_data<Action>(
+    data: &mut MinMaxData<Action>,
+    searched: &Searched<Action>,
+    is_maximizer: bool,
+) where
+    Action: Copy,
+{
+    data.is_maximizer = is_maximizer;
+    data.depth = searched.depth;
+    data.action = searched.action;
+
+    if is_maximizer {
+        data.value = searched.value;
+        data.bound = searched.bound;
+    } else {
+        data.value = -searched.value;
+        data.bound = searched.bound.flip();
+    }
+}
+
+/// Sorts actions by decreasing key.
+fn sort_by_key<Action>(actions: Vec<Action>, key: impl Fn(&Action) -> OrderKey) -> Vec<Action> {
+    let mut keyed: Vec<_> = actions.into_iter().map(|a| (key(&a), a)).collect();
+    keyed.sort_by(|(a, _), (b, _)| b.partial_cmp(a).expect("Trying to compare with NaN"));
+    keyed.into_iter().map(|(_, a)| a).collect()
+}
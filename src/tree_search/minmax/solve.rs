@@ -0,0 +1,223 @@
+use std::collections::hash_map::Iter;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::abstractions::Environment;
+use crate::abstractions::Symmetric;
+use crate::cache::canonical::Canonicalizer;
+
+use super::utils::terminal_score;
+
+/// Game-theoretic value of a position, seen from the agent the game was solved for, together
+/// with an optimal action for the agent moving in it. Terminal positions, and positions without
+/// actions, have no action.
+#[derive(Clone, Copy, Debug)]
+pub struct Solved<Action> {
+    pub value: f64,
+    pub action: Option<Action>,
+}
+
+/// Values and optimal actions of every position reachable from the position a game was solved
+/// from. Solutions of symmetric games store canonical positions only, and map their actions
+/// back to the positions asked for.
+pub struct Solution<Action, T> {
+    table: HashMap<T, Solved<Action>>,
+    canonical: Option<Canonicalizer<Action, T>>,
+}
+
+impl<Action, T> Solution<Action, T>
+where
+    Action: Copy,
+    T: Eq + Hash,
+{
+    /// Returns the value and optimal action of 'env', if it is reachable.
+    pub fn get(&self, env: &T) -> Option<Solved<Action>> {
+        match &self.canonical {
+            Some(canonical) => {
+                let (position, symmetry) = canonical.canonical(env);
+                self.table.get(&position).map(|solved| Solved {
+                    value: solved.value,
+                    action: solved
+                        .action
+                        .map(|action| canonical.action_back(&position, &action, symmetry)),
+                })
+            }
+            None => self.table.get(env).copied(),
+        }
+    }
+
+    /// Returns the value of 'env', if it is reachable.
+    pub fn value(&self, env: &T) -> Option<f64> {
+        self.get(env).map(|solved| solved.value)
+    }
+
+    /// Returns an optimal action in 'env', if it is reachable and not terminal.
+    pub fn action(&self, env: &T) -> Option<Action> {
+        self.get(env).and_then(|solved| solved.action)
+    }
+
+    /// Returns the number of distinct positions reachable, including the first one. For
+    /// symmetric games, it is the number of distinct canonical positions.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns true iff no position was solved.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Iterates over every reachable position, or every canonical one, in no particular order.
+    pub fn iter(&self) -> Iter<'_, T, Solved<Action>> {
+        self.table.iter()
+    }
+}
+
+/// Strongly solves a game from 'env' by going all the way down the environment tree, like
+/// 'depth_first', but remembering every position solved so that transpositions are solved
+/// only once. Values are seen from 'agent', and positions without actions are scored like
+/// terminal positions.
+///
+/// Positions are expected not to repeat along a game.
+pub fn solve<Action, AgentId, T>(env: &T, agent: &AgentId) -> Solution<Action, T>
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let mut table = HashMap::new();
+    solve_position(env, agent, None, &mut table);
+
+    Solution {
+        table,
+        canonical: None,
+    }
+}
+
+/// Strongly solves a symmetric game like 'solve', storing a single canonical position for all
+/// the positions related by a symmetry.
+pub fn solve_symmetric<Action, AgentId, T>(env: &T, agent: &AgentId) -> Solution<Action, T>
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Symmetric<Action, AgentId> + Clone + Ord + Hash,
+{
+    let canonical = Canonicalizer::new();
+    let mut table = HashMap::new();
+    solve_position(env, agent, Some(&canonical), &mut table);
+
+    Solution {
+        table,
+        canonical: Some(canonical),
+    }
+}
+
+/// Position being solved by 'solve_position', with its actions left to try.
+struct Frame<Action, T> {
+    env: T,
+    /// Action leading to 'env' from the position below it on the stack.
+    action: Option<Action>,
+    is_maximizer: bool,
+    /// Actions left to try, or None for terminal positions.
+    actions: Option<Box<dyn Iterator<Item = Action>>>,
+    best: Option<(f64, Action)>,
+}
+
+impl<Action, T> Frame<Action, T>
+where
+    Action: Copy,
+{
+    fn new<AgentId>(env: T, action: Option<Action>, agent: &AgentId) -> Self
+    where
+        AgentId: Eq,
+        T: Environment<Action, AgentId>,
+    {
+        let actions = if env.is_terminal() {
+            None
+        } else {
+            Some(env.valid_actions())
+        };
+
+        Frame {
+            is_maximizer: env.turn() == *agent,
+            env,
+            action,
+            actions,
+            best: None,
+        }
+    }
+
+    /// Keeps 'action' if its 'value' is better than the best one so far.
+    fn update(&mut self, value: f64, action: Action) {
+        let is_better = match self.best {
+            Some((best_value, _)) if self.is_maximizer => value > best_value,
+            Some((best_value, _)) => value < best_value,
+            None => true,
+        };
+
+        if is_better {
+            self.best = Some((value, action));
+        }
+    }
+}
+
+/// Solves 'env' and every position reachable from it that is not in 'table' yet. When
+/// 'canonical' is given, positions are replaced by their canonical position first. Positions
+/// on the current line are kept on an explicit stack, so games of any length are solved
+/// without growing the call stack.
+fn solve_position<Action, AgentId, T>(
+    env: &T,
+    agent: &AgentId,
+    canonical: Option<&Canonicalizer<Action, T>>,
+    table: &mut HashMap<T, Solved<Action>>,
+) -> f64
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let canonical_env = |env: T| match canonical {
+        Some(canonical) => canonical.environment(&env),
+        None => env,
+    };
+
+    let env = canonical_env(env.clone());
+    if let Some(solved) = table.get(&env) {
+        return solved.value;
+    }
+
+    let mut stack = vec![Frame::new(env, None, agent)];
+
+    loop {
+        let frame = stack
+            .last_mut()
+            .expect("The stack holds the first position");
+
+        if let Some(action) = frame.actions.as_mut().and_then(|actions| actions.next()) {
+            let next_env = canonical_env(frame.env.what_if(&action));
+            match table.get(&next_env) {
+                Some(solved) => frame.update(solved.value, action),
+                None => stack.push(Frame::new(next_env, Some(action), agent)),
+            }
+            continue;
+        }
+
+        let frame = stack.pop().expect("The stack holds the first position");
+        let solved = match frame.best {
+            Some((value, action)) => Solved {
+                value,
+                action: Some(action),
+            },
+            None => Solved {
+                value: terminal_score(&frame.env, agent),
+                action: None,
+            },
+        };
+        table.insert(frame.env, solved);
+
+        match (stack.last_mut(), frame.action) {
+            (Some(parent), Some(action)) => parent.update(solved.value, action),
+            _ => return solved.value,
+        }
+    }
+}
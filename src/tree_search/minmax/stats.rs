@@ -0,0 +1,27 @@
+/// Counters collected while searching. They are useful to measure the effect of the
+/// different search enhancements.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchStats {
+    /// Number of positions visited, including the ones found in a cache.
+    pub nodes: u64,
+    /// Number of positions visited by the quiescence search.
+    pub quiescence_nodes: u64,
+    /// Number of children searched again after a null window search failed high.
+    pub pvs_researches: u64,
+    /// Number of root searches repeated after failing outside the aspiration window.
+    pub aspiration_researches: u64,
+    /// Number of null window searches run by MTD(f).
+    pub mtdf_passes: u64,
+    /// Number of null-move searches run.
+    pub null_move_searches: u64,
+    /// Number of positions pruned by a null-move search.
+    pub null_move_cutoffs: u64,
+    /// Number of actions searched with a reduced depth.
+    pub lmr_reductions: u64,
+    /// Number of reduced actions searched again at full depth.
+    pub lmr_researches: u64,
+    /// Number of positions scored by probing a tablebase.
+    pub tablebase_hits: u64,
+    /// Number of positions scored as draws because they repeat a previous one.
+    pub repetitions: u64,
+}
@@ -1,17 +1,5 @@
 use crate::abstractions::Environment;
-
-/// Either compares score0 with score1, or score1 with score0 depending on flip.
-pub fn flip_order(score0: &f64, score1: &f64, flip: bool) -> std::cmp::Ordering {
-    if flip {
-        score0
-            .partial_cmp(score1)
-            .expect("Trying to compare with NaN")
-    } else {
-        score1
-            .partial_cmp(score0)
-            .expect("Trying to compare with NaN")
-    }
-}
+use crate::tablebase::Outcome;
 
 /// Finds score for terminal environments
 pub fn terminal_score<Action, AgentId, T>(env: &T, agent: &AgentId) -> f64
@@ -30,3 +18,33 @@ where
         None => 0f64,
     }
 }
+
+/// Scores an 'outcome' found by a tablebase for the agent moving. Wins and losses are as many
+/// floats away from the bounds of f64 as they are plies away from the end of the game, so
+/// that, like in 'Outcome::preference', short wins and long losses are preferred. A win at
+/// distance zero scores like a terminal win.
+pub fn outcome_score(outcome: Outcome) -> f64 {
+    match outcome {
+        Outcome::Win(distance) => f64::from_bits(f64::MAX.to_bits() - distance as u64),
+        Outcome::Loss(distance) => -f64::from_bits(f64::MAX.to_bits() - distance as u64),
+        Outcome::Draw => 0f64,
+    }
+}
+
+/// Returns the smallest float larger than 'x'. Used to build null windows.
+pub fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0f64 {
+        f64::from_bits(1)
+    } else if x > 0f64 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+/// Returns the largest float smaller than 'x'.
+pub fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
@@ -1,8 +1,49 @@
 mod minmax;
 pub use self::minmax::alphabeta;
 pub use self::minmax::depth_first;
+pub use self::minmax::depth_first_in_place;
 pub use self::minmax::minmax;
+pub use self::minmax::minmax_in_place;
+pub use self::minmax::mtdf;
+pub use self::minmax::quiescence;
+pub use self::minmax::solve;
+pub use self::minmax::solve_symmetric;
+pub use self::minmax::ActionsFn;
+pub use self::minmax::ArenaNode;
+pub use self::minmax::CachedNode;
+pub use self::minmax::History;
+pub use self::minmax::InPlaceNode;
+pub use self::minmax::Killers;
+pub use self::minmax::LateMoveReductions;
+pub use self::minmax::Negamax;
+pub use self::minmax::NullMovePruning;
+pub use self::minmax::OrderKey;
+pub use self::minmax::OrderingFn;
+pub use self::minmax::OrderingTable;
+pub use self::minmax::OrderingTableFn;
+pub use self::minmax::Repetitions;
+pub use self::minmax::SearchNode;
+pub use self::minmax::SearchStats;
+pub use self::minmax::Searched;
+pub use self::minmax::Solution;
+pub use self::minmax::Solved;
+pub use self::minmax::StatelessNode;
 
 mod mcts;
 pub use self::mcts::mcts;
+pub use self::mcts::mcts_in_place;
+pub use self::mcts::mcts_with_probe;
 pub use self::mcts::uct;
+pub use self::mcts::uct_in_place;
+pub use self::mcts::MctsCache;
+#[cfg(feature = "serde")]
+pub use self::mcts::SavedCache;
+pub use self::mcts::SymmetricCache;
+pub use self::mcts::ZobristCache;
+
+mod proof_number;
+pub use self::proof_number::dfpn;
+pub use self::proof_number::pns;
+pub use self::proof_number::Proof;
+pub use self::proof_number::ProofLimits;
+pub use self::proof_number::ProofResult;
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem::size_of;
+
+use crate::abstractions::Environment;
+
+use super::combine;
+use super::evaluate;
+use super::Proof;
+use super::ProofLimits;
+use super::ProofNumbers;
+use super::ProofResult;
+use super::INFINITE;
+
+/// State of a depth-first proof-number search.
+struct Dfpn<'a, AgentId, T> {
+    agent_id: &'a AgentId,
+    limits: &'a ProofLimits,
+    table: HashMap<T, ProofNumbers>,
+    entry_size: usize,
+    nodes: usize,
+    exhausted: bool,
+}
+
+/// A position on the line being searched by 'Dfpn::mid', with its thresholds.
+struct Frame<T> {
+    env: T,
+    children: Vec<T>,
+    is_or: bool,
+    max_proof: u64,
+    max_disproof: u64,
+}
+
+/// Depth-first proof-number search (df-pn). Answers the same question as 'pns', but instead
+/// of keeping the tree in memory it searches depth-first with thresholds on the proof and
+/// disproof numbers, and keeps the numbers found in a transposition table.
+pub fn dfpn<Action, AgentId, T>(
+    env: &T,
+    agent_id: &AgentId,
+    limits: &ProofLimits,
+) -> ProofResult<Action>
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let mut search = Dfpn {
+        agent_id,
+        limits,
+        table: HashMap::new(),
+        entry_size: size_of::<(T, ProofNumbers)>(),
+        nodes: 0,
+        exhausted: false,
+    };
+
+    let (proof_number, _) = search.mid(env, INFINITE, INFINITE);
+
+    let proof = if search.exhausted {
+        Proof::Unknown
+    } else if proof_number == 0 {
+        Proof::Win
+    } else {
+        Proof::Loss
+    };
+
+    let action = if proof == Proof::Win && env.turn() == *agent_id {
+        env.valid_actions()
+            .find(|a| search.lookup(&env.what_if(a)).0 == 0)
+    } else {
+        None
+    };
+
+    ProofResult {
+        proof,
+        action,
+        nodes: search.nodes,
+    }
+}
+
+impl<'a, AgentId, T> Dfpn<'a, AgentId, T>
+where
+    AgentId: Eq,
+    T: Eq + Hash + Clone,
+{
+    /// Multiple iterative deepening. Searches 'env' until its proof number reaches
+    /// 'max_proof', or its disproof number reaches 'max_disproof'. The line being searched is
+    /// kept on an explicit stack, so that long games don't overflow the call stack.
+    fn mid<Action>(&mut self, env: &T, max_proof: u64, max_disproof: u64) -> ProofNumbers
+    where
+        T: Environment<Action, AgentId>,
+    {
+        let mut stack: Vec<Frame<T>> = Vec::new();
+        let mut entering = Some((env.clone(), max_proof, max_disproof));
+
+        loop {
+            if let Some((env, max_proof, max_disproof)) = entering.take() {
+                self.nodes += 1;
+
+                if let Some(numbers) = evaluate(&env, self.agent_id) {
+                    self.insert(&env, numbers);
+                    if stack.is_empty() {
+                        return numbers;
+                    }
+                    continue;
+                }
+
+                stack.push(Frame {
+                    is_or: env.turn() == *self.agent_id,
+                    children: env.valid_actions().map(|a| env.what_if(&a)).collect(),
+                    env,
+                    max_proof,
+                    max_disproof,
+                });
+            }
+
+            let frame = stack.last().expect("The root is searched until it returns");
+            let numbers: Vec<ProofNumbers> =
+                frame.children.iter().map(|c| self.lookup(c)).collect();
+            let (proof_number, disproof_number) = combine(frame.is_or, &numbers);
+
+            if proof_number >= frame.max_proof
+                || disproof_number >= frame.max_disproof
+                || self.exhausted
+            {
+                let frame = stack.pop().expect("The frame was just looked at");
+                self.insert(&frame.env, (proof_number, disproof_number));
+                if stack.is_empty() {
+                    return (proof_number, disproof_number);
+                }
+                continue;
+            }
+
+            if self.nodes >= self.limits.max_nodes
+                || self.table.len() * self.entry_size > self.limits.max_memory
+            {
+                self.exhausted = true;
+                continue;
+            }
+
+            // OR nodes follow the child with smallest proof number, and AND nodes the
+            // child with smallest disproof number. The second smallest sets the threshold.
+            let is_or = frame.is_or;
+            let key = |(child_proof, child_disproof): &ProofNumbers| {
+                if is_or {
+                    *child_proof
+                } else {
+                    *child_disproof
+                }
+            };
+
+            let (best, _) = numbers
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, n)| key(n))
+                .expect("Non terminal positions have children");
+            let second = numbers
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != best)
+                .map(|(_, n)| key(n))
+                .min()
+                .unwrap_or(INFINITE);
+
+            let (child_proof, child_disproof) = numbers[best];
+            let (next_max_proof, next_max_disproof) = if is_or {
+                (
+                    frame.max_proof.min(second.saturating_add(1)),
+                    frame
+                        .max_disproof
+                        .saturating_sub(disproof_number)
+                        .saturating_add(child_disproof),
+                )
+            } else {
+                (
+                    frame
+                        .max_proof
+                        .saturating_sub(proof_number)
+                        .saturating_add(child_proof),
+                    frame.max_disproof.min(second.saturating_add(1)),
+                )
+            };
+
+            entering = Some((
+                frame.children[best].clone(),
+                next_max_proof,
+                next_max_disproof,
+            ));
+        }
+    }
+
+    /// Returns the proof numbers of a position, from the table, from its winner if it is
+    /// terminal, or (1, 1) when nothing is known about it.
+    fn lookup<Action>(&mut self, env: &T) -> ProofNumbers
+    where
+        T: Environment<Action, AgentId>,
+    {
+        if let Some(numbers) = self.table.get(env) {
+            return *numbers;
+        }
+
+        match evaluate(env, self.agent_id) {
+            Some(numbers) => {
+                self.insert(env, numbers);
+                numbers
+            }
+            None => (1, 1),
+        }
+    }
+
+    fn insert(&mut self, env: &T, numbers: ProofNumbers) {
+        self.table.insert(env.clone(), numbers);
+    }
+}
@@ -0,0 +1,50 @@
+mod dfpn;
+pub use self::dfpn::dfpn;
+
+mod pns;
+pub use self::pns::pns;
+
+mod utils;
+use self::utils::combine;
+use self::utils::evaluate;
+use self::utils::ProofNumbers;
+use self::utils::INFINITE;
+
+/// Answer of a proof-number search to the question "can the agent force a win?".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Proof {
+    /// The agent can force a win.
+    Win,
+    /// The agent cannot force a win. Draws count as losses.
+    Loss,
+    /// The search ran out of nodes or memory before answering.
+    Unknown,
+}
+
+/// Result of a proof-number search.
+#[derive(Clone, Copy, Debug)]
+pub struct ProofResult<Action> {
+    pub proof: Proof,
+    /// An action that keeps the win, when the agent moves at the root and the root is a win.
+    pub action: Option<Action>,
+    /// Number of positions expanded during the search.
+    pub nodes: usize,
+}
+
+/// Limits of a proof-number search. The search stops with 'Proof::Unknown' when it expands
+/// more than 'max_nodes' positions, or when its tree or table would need more than
+/// 'max_memory' bytes. The memory is estimated from the size of the stored entries.
+#[derive(Clone, Copy, Debug)]
+pub struct ProofLimits {
+    pub max_nodes: usize,
+    pub max_memory: usize,
+}
+
+impl Default for ProofLimits {
+    fn default() -> Self {
+        ProofLimits {
+            max_nodes: 1_000_000,
+            max_memory: 1 << 30,
+        }
+    }
+}
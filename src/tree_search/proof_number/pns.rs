@@ -0,0 +1,160 @@
+use std::mem::size_of;
+
+use crate::abstractions::Environment;
+
+use super::combine;
+use super::evaluate;
+use super::Proof;
+use super::ProofLimits;
+use super::ProofNumbers;
+use super::ProofResult;
+
+/// A position in the proof-number search tree.
+struct PnNode<Action, T> {
+    env: T,
+    action: Option<Action>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    is_or: bool,
+    numbers: ProofNumbers,
+}
+
+/// Proof-number search. Answers whether 'agent_id' can force a win in 'env' by growing a
+/// tree best-first, always expanding a most-proving position: the one whose solution would
+/// change the proof or disproof number of the root the most.
+pub fn pns<Action, AgentId, T>(
+    env: &T,
+    agent_id: &AgentId,
+    limits: &ProofLimits,
+) -> ProofResult<Action>
+where
+    Action: Copy,
+    AgentId: Eq,
+    T: Environment<Action, AgentId> + Clone,
+{
+    let node_size = size_of::<PnNode<Action, T>>() + size_of::<usize>();
+    let mut tree = vec![new_node(env.clone(), None, None, agent_id)];
+    let mut nodes = 0;
+
+    while tree[0].numbers.0 != 0 && tree[0].numbers.1 != 0 {
+        if nodes >= limits.max_nodes || tree.len() * node_size > limits.max_memory {
+            return ProofResult {
+                proof: Proof::Unknown,
+                action: None,
+                nodes,
+            };
+        }
+
+        let most_proving = select_most_proving(&tree);
+        expand(&mut tree, most_proving, agent_id);
+        update_ancestors(&mut tree, most_proving);
+        nodes += 1;
+    }
+
+    let root = &tree[0];
+    if root.numbers.0 == 0 {
+        let action = if root.is_or {
+            root.children
+                .iter()
+                .find(|child| tree[**child].numbers.0 == 0)
+                .and_then(|child| tree[*child].action)
+        } else {
+            None
+        };
+
+        ProofResult {
+            proof: Proof::Win,
+            action,
+            nodes,
+        }
+    } else {
+        ProofResult {
+            proof: Proof::Loss,
+            action: None,
+            nodes,
+        }
+    }
+}
+
+/// Creates a node, solving it right away if it is terminal.
+fn new_node<Action, AgentId, T>(
+    env: T,
+    action: Option<Action>,
+    parent: Option<usize>,
+    agent_id: &AgentId,
+) -> PnNode<Action, T>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    PnNode {
+        is_or: env.turn() == *agent_id,
+        numbers: evaluate(&env, agent_id).unwrap_or((1, 1)),
+        env,
+        action,
+        parent,
+        children: Vec::new(),
+    }
+}
+
+/// Descends from the root to a most-proving leaf. OR nodes follow the child with the
+/// smallest proof number, and AND nodes the child with the smallest disproof number.
+fn select_most_proving<Action, T>(tree: &[PnNode<Action, T>]) -> usize {
+    let mut index = 0;
+
+    while !tree[index].children.is_empty() {
+        let node = &tree[index];
+        let next = if node.is_or {
+            node.children
+                .iter()
+                .min_by_key(|child| tree[**child].numbers.0)
+        } else {
+            node.children
+                .iter()
+                .min_by_key(|child| tree[**child].numbers.1)
+        };
+
+        index = *next.expect("Expanded nodes have children");
+    }
+
+    index
+}
+
+/// Adds the children of a leaf to the tree.
+fn expand<Action, AgentId, T>(tree: &mut Vec<PnNode<Action, T>>, index: usize, agent_id: &AgentId)
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    let actions = tree[index].env.valid_actions();
+
+    for action in actions {
+        let child = new_node(
+            tree[index].env.what_if(&action),
+            Some(action),
+            Some(index),
+            agent_id,
+        );
+        tree.push(child);
+
+        let child_index = tree.len() - 1;
+        tree[index].children.push(child_index);
+    }
+}
+
+/// Recomputes the proof numbers from a node up to the root.
+fn update_ancestors<Action, T>(tree: &mut [PnNode<Action, T>], index: usize) {
+    let mut next = Some(index);
+
+    while let Some(index) = next {
+        let node = &tree[index];
+        let numbers: Vec<ProofNumbers> = node
+            .children
+            .iter()
+            .map(|child| tree[*child].numbers)
+            .collect();
+
+        tree[index].numbers = combine(node.is_or, &numbers);
+        next = tree[index].parent;
+    }
+}
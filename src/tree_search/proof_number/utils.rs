@@ -0,0 +1,42 @@
+use crate::abstractions::Environment;
+
+/// Proof and disproof numbers of a position: the minimum number of positions that need to be
+/// solved to prove, respectively disprove, that the agent wins.
+pub(super) type ProofNumbers = (u64, u64);
+
+/// Proof number of a proven position, and disproof number of a disproven one.
+pub(super) const INFINITE: u64 = u64::MAX;
+
+/// Proof numbers of a position before expanding it. Terminal positions and positions
+/// without valid actions are solved with the winner of the environment.
+pub(super) fn evaluate<Action, AgentId, T>(env: &T, agent_id: &AgentId) -> Option<ProofNumbers>
+where
+    AgentId: Eq,
+    T: Environment<Action, AgentId>,
+{
+    if env.is_terminal() || env.valid_actions().next().is_none() {
+        match env.winner() {
+            Some(winner) if winner == *agent_id => Some((0, INFINITE)),
+            _ => Some((INFINITE, 0)),
+        }
+    } else {
+        None
+    }
+}
+
+/// Proof numbers of a node given the proof numbers of its children.
+pub(super) fn combine(is_or: bool, numbers: &[ProofNumbers]) -> ProofNumbers {
+    if is_or {
+        numbers
+            .iter()
+            .fold((INFINITE, 0), |(pn, dn), (child_pn, child_dn)| {
+                (pn.min(*child_pn), dn.saturating_add(*child_dn))
+            })
+    } else {
+        numbers
+            .iter()
+            .fold((0, INFINITE), |(pn, dn), (child_pn, child_dn)| {
+                (pn.saturating_add(*child_pn), dn.min(*child_dn))
+            })
+    }
+}
@@ -0,0 +1,291 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use crate::abstractions::Environment;
+use crate::cache::utils::hash;
+
+/// Implicit contracts of the 'Environment' trait checked by 'Conformance'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invariant {
+    /// Terminal positions have no valid actions.
+    NoActionsAtEnd,
+    /// There is no winner before the game ends.
+    NoWinnerBeforeEnd,
+    /// 'turn' returns the same agent every time it is called on a position.
+    StableTurn,
+    /// 'what_if' leaves the environment untouched.
+    WhatIfDoesNotMutate,
+    /// Valid actions are accepted by 'is_valid' and 'update'.
+    ValidActionsAccepted,
+    /// 'what_if' produces the same environment as cloning and calling 'update'.
+    WhatIfMatchesUpdate,
+    /// Equal environments have equal hashes, and clones are equal to the original.
+    HashConsistentWithEq,
+}
+
+/// An invariant broken after playing 'actions' from the first position of a walk. When the
+/// invariant is about an action, it is the last one of the sequence.
+#[derive(Clone, Debug)]
+pub struct Violation<Action> {
+    pub invariant: Invariant,
+    pub actions: Vec<Action>,
+}
+
+impl<Action: Debug> fmt::Display for Violation<Action> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} broken after actions {:?}",
+            self.invariant, self.actions
+        )
+    }
+}
+
+impl<Action: Debug> Error for Violation<Action> {}
+
+/// Conformance harness for 'Environment' implementations. It plays random games, checking the
+/// invariants in 'Invariant' on every position reached and for every valid action. Once an
+/// invariant breaks, the actions leading to it are shrunk to a sequence where no action can
+/// be removed without fixing the problem.
+///
+/// Walks are pseudo-random and reproducible for a given seed.
+#[derive(Clone, Copy, Debug)]
+pub struct Conformance {
+    walks: usize,
+    max_length: usize,
+    seed: u64,
+}
+
+impl Default for Conformance {
+    fn default() -> Self {
+        Conformance {
+            walks: 100,
+            max_length: 1000,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl Conformance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of games played.
+    pub fn with_walks(mut self, walks: usize) -> Self {
+        self.walks = walks;
+        self
+    }
+
+    /// Sets the maximum number of actions played in every game.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Sets the seed of the random walks.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Checks the games starting from the initial state.
+    pub fn check<Action, AgentId, T>(&self) -> Result<(), Violation<Action>>
+    where
+        Action: Clone,
+        AgentId: PartialEq,
+        T: Environment<Action, AgentId> + Clone + Eq + Hash,
+    {
+        self.check_from(&T::initial_state())
+    }
+
+    /// Checks the games starting from 'env'.
+    pub fn check_from<Action, AgentId, T>(&self, env: &T) -> Result<(), Violation<Action>>
+    where
+        Action: Clone,
+        AgentId: PartialEq,
+        T: Environment<Action, AgentId> + Clone + Eq + Hash,
+    {
+        let mut rng = XorShift::new(self.seed);
+
+        for _ in 0..self.walks {
+            if let Some(violation) = self.walk(env, &mut rng) {
+                return Err(shrink(env, violation));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Plays a random game from 'env', returning the first invariant broken.
+    fn walk<Action, AgentId, T>(&self, env: &T, rng: &mut XorShift) -> Option<Violation<Action>>
+    where
+        Action: Clone,
+        AgentId: PartialEq,
+        T: Environment<Action, AgentId> + Clone + Eq + Hash,
+    {
+        let mut env = env.clone();
+        let mut actions = Vec::new();
+
+        for _ in 0..self.max_length {
+            if let Some((invariant, action)) = check_position(&env) {
+                actions.extend(action);
+                return Some(Violation { invariant, actions });
+            }
+
+            let valid: Vec<Action> = env.valid_actions().collect();
+            if env.is_terminal() || valid.is_empty() {
+                break;
+            }
+
+            let action = valid[(rng.next() % valid.len() as u64) as usize].clone();
+            env = env.what_if(&action);
+            actions.push(action);
+        }
+
+        None
+    }
+}
+
+/// Checks every invariant on 'env'. Returns the first invariant broken, and the action that
+/// broke it, if any.
+fn check_position<Action, AgentId, T>(env: &T) -> Option<(Invariant, Option<Action>)>
+where
+    Action: Clone,
+    AgentId: PartialEq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    if env.turn() != env.turn() {
+        return Some((Invariant::StableTurn, None));
+    }
+
+    let copy = env.clone();
+    if copy != *env || hash(&copy) != hash(env) {
+        return Some((Invariant::HashConsistentWithEq, None));
+    }
+
+    if env.is_terminal() {
+        if env.valid_actions().next().is_some() {
+            return Some((Invariant::NoActionsAtEnd, None));
+        }
+        return None;
+    }
+
+    if env.winner().is_some() {
+        return Some((Invariant::NoWinnerBeforeEnd, None));
+    }
+
+    for action in env.valid_actions() {
+        let next_env = env.what_if(&action);
+        if *env != copy || env.turn() != copy.turn() {
+            return Some((Invariant::WhatIfDoesNotMutate, Some(action)));
+        }
+
+        let mut updated = env.clone();
+        if !env.is_valid(&action) || !updated.update(&action) {
+            return Some((Invariant::ValidActionsAccepted, Some(action)));
+        }
+
+        if updated != next_env {
+            return Some((Invariant::WhatIfMatchesUpdate, Some(action)));
+        }
+
+        if hash(&updated) != hash(&next_env) {
+            return Some((Invariant::HashConsistentWithEq, Some(action)));
+        }
+    }
+
+    None
+}
+
+/// Removes chunks of actions from the sequence of a violation, from large to single actions,
+/// while the rest of the sequence can still be played and breaks the same invariant sooner.
+/// Removing chunks lets the shrinking keep whose turn each action is played in.
+fn shrink<Action, AgentId, T>(env: &T, violation: Violation<Action>) -> Violation<Action>
+where
+    Action: Clone,
+    AgentId: PartialEq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let mut violation = violation;
+    let mut size = (violation.actions.len() / 2).max(1);
+    let mut length = violation.actions.len();
+
+    loop {
+        let mut start = 0;
+        while start + size <= violation.actions.len() {
+            let mut actions = violation.actions.clone();
+            actions.drain(start..start + size);
+
+            match replay(env, &actions) {
+                Some(shorter)
+                    if shorter.invariant == violation.invariant
+                        && shorter.actions.len() < violation.actions.len() =>
+                {
+                    violation = shorter
+                }
+                _ => start += 1,
+            }
+        }
+
+        if size > 1 {
+            size = size.div_ceil(2);
+        } else if violation.actions.len() < length {
+            // Removing single actions may have made larger chunks removable.
+            length = violation.actions.len();
+            size = (length / 2).max(1);
+        } else {
+            break;
+        }
+    }
+
+    violation
+}
+
+/// Plays 'actions' from 'env', checking every position on the way. Returns the first invariant
+/// broken, or None if there is none or the actions cannot be played.
+fn replay<Action, AgentId, T>(env: &T, actions: &[Action]) -> Option<Violation<Action>>
+where
+    Action: Clone,
+    AgentId: PartialEq,
+    T: Environment<Action, AgentId> + Clone + Eq + Hash,
+{
+    let mut env = env.clone();
+
+    for (index, action) in actions.iter().enumerate() {
+        if let Some((invariant, action)) = check_position(&env) {
+            let mut actions = actions[..index].to_vec();
+            actions.extend(action);
+            return Some(Violation { invariant, actions });
+        }
+
+        if env.is_terminal() || !env.is_valid(action) {
+            return None;
+        }
+        env = env.what_if(action);
+    }
+
+    check_position(&env).map(|(invariant, action)| {
+        let mut actions = actions.to_vec();
+        actions.extend(action);
+        Violation { invariant, actions }
+    })
+}
+
+/// Small pseudo-random generator, so that walks do not need external crates.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        XorShift(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
@@ -0,0 +1,11 @@
+mod conformance;
+pub use self::conformance::Conformance;
+pub use self::conformance::Invariant;
+pub use self::conformance::Violation;
+
+mod perft;
+pub use self::perft::divide;
+pub use self::perft::perft;
+pub use self::perft::validate;
+pub use self::perft::CandidatesFn;
+pub use self::perft::PerftError;
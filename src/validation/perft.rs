@@ -0,0 +1,210 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Debug;
+
+use crate::abstractions::Environment;
+
+/// Function producing every action that could possibly be valid in an environment.
+pub type CandidatesFn<'a, Action, T> = dyn Fn(&T) -> Vec<Action> + 'a;
+
+/// Counts the positions reached after every number of plies from 'env', up to 'depth'.
+/// The count at index 'd' is the number of leaves of the tree of depth 'd': positions reached
+/// with exactly 'd' actions, whichever order they were played in. Games end at terminal
+/// positions, even if they have valid actions.
+pub fn perft<Action, AgentId, T>(env: &T, depth: usize) -> Vec<u64>
+where
+    T: Environment<Action, AgentId>,
+{
+    let mut counts = vec![0; depth + 1];
+    count(env, 0, &mut counts);
+    counts
+}
+
+/// Counts the leaves of the tree of depth 'depth' under each of the actions of 'env'. Comparing
+/// these counts with the ones of a trusted implementation narrows down where they differ.
+pub fn divide<Action, AgentId, T>(env: &T, depth: usize) -> Vec<(Action, u64)>
+where
+    T: Environment<Action, AgentId>,
+{
+    if depth == 0 || env.is_terminal() {
+        return Vec::new();
+    }
+
+    env.valid_actions()
+        .map(|action| {
+            let leaves = perft(&env.what_if(&action), depth - 1)[depth - 1];
+            (action, leaves)
+        })
+        .collect()
+}
+
+/// Counts positions like 'perft', checking the environment on every position it visits:
+///
+/// * Every action from 'valid_actions' is accepted by 'is_valid' and 'update', and appears once.
+/// * 'what_if' produces the same environment as cloning and calling 'update'.
+/// * When 'candidates' is given, every candidate accepted by 'is_valid' is among the valid
+///   actions. 'candidates' should produce every action that could possibly be valid.
+///
+/// Returns the first inconsistency found.
+pub fn validate<Action, AgentId, T>(
+    env: &T,
+    depth: usize,
+    candidates: Option<&CandidatesFn<'_, Action, T>>,
+) -> Result<Vec<u64>, PerftError<Action, T>>
+where
+    Action: Clone + PartialEq,
+    T: Environment<Action, AgentId> + Clone + PartialEq,
+{
+    let mut counts = vec![0; depth + 1];
+    count_checked(env, 0, candidates, &mut counts)?;
+    Ok(counts)
+}
+
+/// Inconsistencies between the methods of an environment. Each of them holds the environment
+/// where it was found, and the offending action.
+#[derive(Clone, Debug)]
+pub enum PerftError<Action, T> {
+    /// An action from 'valid_actions' is rejected by 'is_valid'.
+    Rejected(T, Action),
+    /// An action from 'valid_actions' is rejected by 'update'.
+    UpdateFailed(T, Action),
+    /// An action appears more than once in 'valid_actions'.
+    Duplicated(T, Action),
+    /// An action accepted by 'is_valid' is missing from 'valid_actions'.
+    Missing(T, Action),
+    /// 'what_if' and 'update' produce different environments for an action.
+    WhatIfMismatch(T, Action),
+}
+
+impl<Action, T> fmt::Display for PerftError<Action, T>
+where
+    Action: Debug,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PerftError::Rejected(env, action) => {
+                write!(
+                    f,
+                    "Valid action {:?} rejected by is_valid in {:?}",
+                    action, env
+                )
+            }
+            PerftError::UpdateFailed(env, action) => {
+                write!(
+                    f,
+                    "Valid action {:?} rejected by update in {:?}",
+                    action, env
+                )
+            }
+            PerftError::Duplicated(env, action) => {
+                write!(
+                    f,
+                    "Action {:?} produced more than once in {:?}",
+                    action, env
+                )
+            }
+            PerftError::Missing(env, action) => {
+                write!(
+                    f,
+                    "Action {:?} is valid but not produced in {:?}",
+                    action, env
+                )
+            }
+            PerftError::WhatIfMismatch(env, action) => {
+                write!(f, "what_if and update differ for {:?} in {:?}", action, env)
+            }
+        }
+    }
+}
+
+impl<Action, T> Error for PerftError<Action, T>
+where
+    Action: Debug,
+    T: Debug,
+{
+}
+
+/// Adds the positions under 'env', which is 'ply' plies away from the root, to 'counts'.
+fn count<Action, AgentId, T>(env: &T, ply: usize, counts: &mut [u64])
+where
+    T: Environment<Action, AgentId>,
+{
+    counts[ply] += 1;
+
+    if ply + 1 == counts.len() || env.is_terminal() {
+        return;
+    }
+
+    for action in env.valid_actions() {
+        count(&env.what_if(&action), ply + 1, counts);
+    }
+}
+
+/// Adds the positions under 'env' to 'counts' like 'count', checking every position.
+fn count_checked<Action, AgentId, T>(
+    env: &T,
+    ply: usize,
+    candidates: Option<&CandidatesFn<'_, Action, T>>,
+    counts: &mut [u64],
+) -> Result<(), PerftError<Action, T>>
+where
+    Action: Clone + PartialEq,
+    T: Environment<Action, AgentId> + Clone + PartialEq,
+{
+    counts[ply] += 1;
+
+    if ply + 1 == counts.len() || env.is_terminal() {
+        return Ok(());
+    }
+
+    let actions: Vec<Action> = env.valid_actions().collect();
+    check_actions(env, &actions, candidates)?;
+
+    for action in actions {
+        let next_env = env.what_if(&action);
+
+        let mut updated = env.clone();
+        if !updated.update(&action) {
+            return Err(PerftError::UpdateFailed(env.clone(), action));
+        }
+        if updated != next_env {
+            return Err(PerftError::WhatIfMismatch(env.clone(), action));
+        }
+
+        count_checked(&next_env, ply + 1, candidates, counts)?;
+    }
+
+    Ok(())
+}
+
+/// Checks that the valid actions of 'env' agree with 'is_valid'.
+fn check_actions<Action, AgentId, T>(
+    env: &T,
+    actions: &[Action],
+    candidates: Option<&CandidatesFn<'_, Action, T>>,
+) -> Result<(), PerftError<Action, T>>
+where
+    Action: Clone + PartialEq,
+    T: Environment<Action, AgentId> + Clone,
+{
+    for (index, action) in actions.iter().enumerate() {
+        if !env.is_valid(action) {
+            return Err(PerftError::Rejected(env.clone(), action.clone()));
+        }
+        if actions[..index].contains(action) {
+            return Err(PerftError::Duplicated(env.clone(), action.clone()));
+        }
+    }
+
+    if let Some(candidates) = candidates {
+        if let Some(action) = candidates(env)
+            .into_iter()
+            .find(|a| env.is_valid(a) && !actions.contains(a))
+        {
+            return Err(PerftError::Missing(env.clone(), action));
+        }
+    }
+
+    Ok(())
+}
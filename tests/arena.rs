@@ -0,0 +1,230 @@
+mod common;
+
+use std::collections::HashSet;
+
+use gts::abstractions::Environment;
+use gts::cache::arena::Arena;
+use gts::cache::arena::NodeId;
+
+use common::TicTacToe;
+
+/// Positions 0 to 'SIZE' - 1 on a ring, where agents 1 and 2 move forwards or backwards in
+/// turn. Every position comes back, so the game tree has cycles and never ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Ring {
+    position: u8,
+    turn: u8,
+}
+
+const SIZE: u8 = 5;
+
+impl Environment<u8, u8> for Ring {
+    fn initial_state() -> Self {
+        Ring {
+            position: 0,
+            turn: 1,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.position = (self.position + a) % SIZE;
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    /// Forwards with 1, backwards with 'SIZE' - 1.
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        Box::new(vec![1, SIZE - 1].into_iter())
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a == 1 || *a == SIZE - 1
+    }
+
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        None
+    }
+}
+
+/// Visits every child of 'id' and their children, down to 'depth' plies.
+fn expand<T>(arena: &mut Arena<T, u8, u32>, id: NodeId, depth: usize)
+where
+    T: Environment<u8, u8> + Eq + std::hash::Hash,
+{
+    if depth == 0 {
+        return;
+    }
+    arena.reset(id);
+    let mut children = Vec::new();
+    while let Some((child, _)) = arena.next_child(id) {
+        children.push(child);
+    }
+    for child in children {
+        expand(arena, child, depth - 1);
+    }
+}
+
+/// Checks that every child of every node is the position its action leads to, and that every
+/// node can be reached from the root.
+fn assert_consistent<T>(arena: &Arena<T, u8, u32>)
+where
+    T: Environment<u8, u8> + Eq + std::hash::Hash + std::fmt::Debug,
+{
+    let root = arena.root().unwrap();
+    let mut reached = HashSet::new();
+    let mut stack = vec![root];
+
+    while let Some(id) = stack.pop() {
+        if !reached.insert(id) {
+            continue;
+        }
+        let env = arena.environment(id);
+        assert_eq!(arena.get(env), Some(id));
+        for (child, action) in arena.children(id) {
+            assert_eq!(*arena.environment(*child), env.what_if(action));
+            stack.push(*child);
+        }
+    }
+
+    assert_eq!(reached.len(), arena.len());
+}
+
+/// The child of 'id' reached with 'action', which must have been visited.
+fn reply(arena: &Arena<TicTacToe, u8, u32>, id: NodeId, action: u8) -> NodeId {
+    arena
+        .children(id)
+        .iter()
+        .find(|(_, a)| *a == action)
+        .map(|(child, _)| *child)
+        .unwrap()
+}
+
+#[test]
+fn transpositions_share_a_node() {
+    let mut arena: Arena<TicTacToe, u8, u32> = Arena::new();
+    let root = arena.set_root(TicTacToe::initial_state());
+    expand(&mut arena, root, 2);
+
+    // The 9 first actions and the 8 replies to each all lead to different positions.
+    assert_eq!(arena.len(), 1 + 9 + 9 * 8);
+
+    let corner = arena.get(&TicTacToe::initial_state().what_if(&0)).unwrap();
+    let center = arena.get(&TicTacToe::initial_state().what_if(&4)).unwrap();
+    // 0 then 4 and 4 then 0 are different positions, as the marks differ.
+    assert_ne!(reply(&arena, corner, 4), reply(&arena, center, 0));
+
+    // Going on to the same position through different orders of actions finds one node.
+    expand(&mut arena, root, 3);
+    let other_corner = arena.get(&TicTacToe::initial_state().what_if(&8)).unwrap();
+    let through_corner = reply(&arena, reply(&arena, corner, 4), 8);
+    let through_other_corner = reply(&arena, reply(&arena, other_corner, 4), 0);
+    assert_eq!(through_corner, through_other_corner);
+    assert_consistent(&arena);
+}
+
+#[test]
+fn unreachable_nodes_are_dropped_and_the_rest_renumbered() {
+    let mut arena: Arena<TicTacToe, u8, u32> = Arena::new();
+    let root = arena.set_root(TicTacToe::initial_state());
+    expand(&mut arena, root, 3);
+    let before = arena.len();
+
+    // The center is played, and its position keeps its data.
+    let center = TicTacToe::initial_state().what_if(&4);
+    let id = arena.set_root(center);
+    *arena.data_mut(id) = 7;
+    assert_eq!(arena.len(), before);
+
+    arena.retain_reachable();
+
+    let root = arena.root().unwrap();
+    assert_eq!(format!("{:?}", root), "NodeId(0)");
+    assert_eq!(*arena.environment(root), center);
+    assert_eq!(*arena.data(root), 7);
+    assert_eq!(arena.len(), 1 + 8 + 8 * 7);
+    assert_eq!(arena.get(&TicTacToe::initial_state()), None);
+    assert_eq!(arena.get(&TicTacToe::initial_state().what_if(&0)), None);
+    assert_consistent(&arena);
+
+    // Nodes found after renumbering are added after the ones kept.
+    expand(&mut arena, root, 3);
+    assert_consistent(&arena);
+}
+
+#[test]
+fn cycles_are_kept_once() {
+    let mut arena: Arena<Ring, u8, u32> = Arena::new();
+    let root = arena.set_root(Ring::initial_state());
+    expand(&mut arena, root, 2 * SIZE as usize);
+
+    // Every position is reached with either agent to move.
+    assert_eq!(arena.len(), 2 * SIZE as usize);
+
+    arena.set_root(Ring {
+        position: 3,
+        turn: 2,
+    });
+    arena.retain_reachable();
+
+    assert_eq!(arena.len(), 2 * SIZE as usize);
+    assert_eq!(
+        *arena.environment(arena.root().unwrap()),
+        Ring {
+            position: 3,
+            turn: 2
+        }
+    );
+    assert_consistent(&arena);
+}
+
+#[test]
+fn unvisited_actions_are_visited_in_the_order_given() {
+    let mut arena: Arena<TicTacToe, u8, u32> = Arena::new();
+    let root = arena.set_root(TicTacToe::initial_state());
+
+    assert_eq!(arena.next_child(root).map(|(_, a)| a), Some(0));
+
+    // The actions left are given in the order they would be visited, and the first one was
+    // already visited.
+    let mut given = Vec::new();
+    arena.order_unvisited(root, |actions| {
+        given = actions.clone();
+        actions.into_iter().rev().collect()
+    });
+    assert_eq!(given, (1..9).collect::<Vec<u8>>());
+
+    let mut visited = Vec::new();
+    arena.reset(root);
+    while let Some((child, action)) = arena.next_child(root) {
+        assert_eq!(
+            *arena.environment(child),
+            TicTacToe::initial_state().what_if(&action)
+        );
+        visited.push(action);
+    }
+    assert_eq!(visited, [0, 8, 7, 6, 5, 4, 3, 2, 1]);
+
+    // Once every action was visited, there is nothing left to order.
+    arena.order_unvisited(root, |actions| {
+        assert!(actions.is_empty());
+        actions
+    });
+    assert_eq!(arena.children(root).len(), 9);
+}
@@ -0,0 +1,228 @@
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+
+use gts::abstractions::Environment;
+use gts::abstractions::Notation;
+use gts::agents::AgentConfig;
+use gts::cli::Cli;
+use gts::cli::CliError;
+use gts::cli::GameEntry;
+use gts::record::parse_record;
+use gts::record::replay;
+use gts::record::start_position;
+use gts::record::GameRecord;
+use gts::record::GameResult;
+
+use common::reward;
+use common::TicTacToe;
+
+/// Agent 1 holds the top row.
+const WON: &str = "111.22... 2";
+
+fn cli() -> Cli {
+    Cli::new().with_game(GameEntry::new("tictactoe", (1, 2), reward))
+}
+
+/// Runs the command line 'args', split on spaces, on the tic-tac-toe entry.
+fn run(args: &str) -> Result<(), CliError> {
+    run_args(args.split_whitespace().map(String::from).collect())
+}
+
+/// Runs the command line 'args', for arguments containing spaces.
+fn run_args(args: Vec<String>) -> Result<(), CliError> {
+    cli().run(args)
+}
+
+/// Returns the message of the error of the command line 'args'.
+fn error(args: &str) -> String {
+    run(args).unwrap_err().to_string()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gts-cli-{}-{}", std::process::id(), name))
+}
+
+/// Reads the records written to 'path', separated by empty lines, and removes the file.
+fn read_records(path: &PathBuf) -> Vec<GameRecord<u8, u8>> {
+    let text = fs::read_to_string(path).unwrap();
+    fs::remove_file(path).unwrap();
+    text.split("\n\n")
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| parse_record(record).unwrap())
+        .collect()
+}
+
+/// Returns the configuration of the agent which played 'side' in 'record'.
+fn config(record: &GameRecord<u8, u8>, side: u8) -> Option<AgentConfig> {
+    record
+        .players
+        .iter()
+        .find(|player| player.agent_id == side)
+        .and_then(|player| player.config)
+}
+
+#[test]
+fn help_and_games_are_listed() {
+    assert_eq!(run("help"), Ok(()));
+    assert_eq!(run("games"), Ok(()));
+    assert_eq!(error("games tictactoe"), "Unexpected argument 'tictactoe'");
+}
+
+#[test]
+fn unknown_commands_and_games_are_refused() {
+    assert_eq!(error(""), "Missing command");
+    assert_eq!(error("match"), "Missing game");
+    assert_eq!(error("solve tictactoe"), "Unknown command 'solve'");
+    assert_eq!(error("perft chess 3"), "Unknown game 'chess'");
+}
+
+#[test]
+fn arguments_are_checked() {
+    assert_eq!(
+        error("perft tictactoe 2 --position"),
+        "Missing value of '--position'"
+    );
+    assert_eq!(
+        error("perft tictactoe 2 --speed 3"),
+        "Unknown option '--speed'"
+    );
+    assert_eq!(error("perft tictactoe 2 3"), "Unexpected argument '3'");
+    assert_eq!(error("perft tictactoe deep"), "Invalid depth 'deep'");
+    assert_eq!(error("perft tictactoe"), "Missing depth");
+    assert_eq!(
+        error("match tictactoe human human --games many"),
+        "Invalid value of '--games': many"
+    );
+    assert_eq!(
+        error("analyze tictactoe minmax:depth=2 --position 1"),
+        "Invalid position '1'"
+    );
+}
+
+#[test]
+fn agent_specs_are_checked() {
+    assert_eq!(
+        error("analyze tictactoe alphabeta"),
+        "Invalid agent 'alphabeta': Invalid agent spec: missing option 'depth'"
+    );
+    assert_eq!(
+        error("analyze tictactoe alphabeta:depth=2,symmetries"),
+        "The agent 'alphabeta:depth=2,symmetries' can't be used from the command line"
+    );
+    assert_eq!(
+        error("match tictactoe minmax:depth=2 tablebase"),
+        "The agent 'tablebase' can't be used from the command line"
+    );
+}
+
+#[test]
+fn matches_swap_sides_and_write_their_records() {
+    let path = temp_path("match.txt");
+    let first = "alphabeta:depth=8,pvs";
+    let second = "mcts:runs=20,c=1.4";
+    let args = format!(
+        "match tictactoe {} {} --games 3 --record {}",
+        first,
+        second,
+        path.display()
+    );
+
+    assert_eq!(run(&args), Ok(()));
+
+    // Three games are rounded up to two pairs.
+    let records = read_records(&path);
+    assert_eq!(records.len(), 4);
+    for (game, record) in records.iter().enumerate() {
+        let configs: Vec<String> = [1, 2]
+            .iter()
+            .map(|side| config(record, *side).unwrap().to_string())
+            .collect();
+        let expected = if game % 2 == 0 {
+            [first, second]
+        } else {
+            [second, first]
+        };
+        assert_eq!(configs, expected);
+        assert_eq!(record.metadata["Round"], (game + 1).to_string());
+
+        let start: TicTacToe = start_position(record).unwrap();
+        assert_eq!(start, TicTacToe::initial_state());
+        let end = replay(record, start).unwrap();
+        assert!(end.is_terminal());
+
+        // A perfect player never loses at tic-tac-toe.
+        let perfect = if game % 2 == 0 { 1 } else { 2 };
+        assert_ne!(end.winner(), Some(3 - perfect));
+    }
+}
+
+#[test]
+fn finished_positions_are_played_without_asking_the_human() {
+    let path = temp_path("play.txt");
+    let args = vec![
+        "play".to_string(),
+        "tictactoe".to_string(),
+        "minmax:depth=2".to_string(),
+        "--position".to_string(),
+        WON.to_string(),
+        "--record".to_string(),
+        path.display().to_string(),
+    ];
+
+    assert_eq!(run_args(args), Ok(()));
+
+    let records = read_records(&path);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].start.as_deref(), Some(WON));
+    assert_eq!(records[0].result, GameResult::Win(1));
+    assert_eq!(
+        config(&records[0], 1),
+        Some(AgentConfig::Human),
+        "The human plays the first side by default"
+    );
+
+    assert_eq!(
+        error("play tictactoe minmax:depth=2 --side 3"),
+        "Unknown side '3'"
+    );
+}
+
+#[test]
+fn positions_are_analyzed_and_counted() {
+    let position = TicTacToe {
+        board: [1, 0, 0, 0, 2, 0, 0, 0, 0],
+        turn: 1,
+    }
+    .to_notation();
+    let with_position = |command: &str| {
+        let mut args: Vec<String> = command.split_whitespace().map(String::from).collect();
+        args.push("--position".to_string());
+        args.push(position.clone());
+        run_args(args)
+    };
+
+    assert_eq!(with_position("analyze tictactoe alphabeta:depth=4"), Ok(()));
+    assert_eq!(
+        with_position("analyze tictactoe mcts:runs=20,c=1.4"),
+        Ok(())
+    );
+    assert_eq!(with_position("perft tictactoe 3"), Ok(()));
+    assert_eq!(with_position("perft tictactoe 3 --divide"), Ok(()));
+    assert_eq!(run("perft tictactoe 4"), Ok(()));
+
+    let terminal = vec![
+        "analyze".to_string(),
+        "tictactoe".to_string(),
+        "minmax:depth=2".to_string(),
+        "--position".to_string(),
+        WON.to_string(),
+    ];
+    assert_eq!(
+        run_args(terminal).unwrap_err().to_string(),
+        "The position is terminal"
+    );
+}
@@ -0,0 +1,490 @@
+//! Games shared by the integration tests.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::abstractions::Notation;
+use gts::abstractions::NullMove;
+use gts::abstractions::Symmetric;
+use gts::abstractions::Undo;
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// Tic-tac-toe between agents 1 and 2, with the cells numbered from 0 to 8 row by row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TicTacToe {
+    pub board: [u8; 9],
+    pub turn: u8,
+}
+
+impl Environment<u8, u8> for TicTacToe {
+    fn initial_state() -> Self {
+        TicTacToe {
+            board: [0; 9],
+            turn: 1,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.board[*a as usize] = self.turn;
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        if self.is_terminal() {
+            return Box::new(std::iter::empty());
+        }
+        let board = self.board;
+        Box::new((0..9).filter(move |a| board[*a as usize] == 0))
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a < 9 && self.board[*a as usize] == 0 && !self.is_terminal()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.winner().is_some() || self.board.iter().all(|cell| *cell != 0)
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        LINES
+            .iter()
+            .map(|line| line.map(|cell| self.board[cell]))
+            .find(|[a, b, c]| *a != 0 && a == b && b == c)
+            .map(|[a, _, _]| a)
+    }
+}
+
+/// Tokens are the cells played.
+impl Undo<u8, u8> for TicTacToe {
+    type Token = u8;
+
+    fn make(&mut self, a: &u8) -> Option<u8> {
+        if self.update(a) {
+            Some(*a)
+        } else {
+            None
+        }
+    }
+
+    fn unmake(&mut self, cell: u8) {
+        self.board[cell as usize] = 0;
+        self.turn = 3 - self.turn;
+    }
+}
+
+/// The cells row by row, '.' when empty and the number of the agent otherwise, then the agent
+/// to move: "1........ 2" once agent 1 played in the top left corner.
+impl Notation<u8, u8> for TicTacToe {
+    fn from_notation(notation: &str) -> Option<Self> {
+        let (cells, turn) = notation.split_once(' ')?;
+        let mut env = TicTacToe::initial_state();
+        if cells.len() != 9 {
+            return None;
+        }
+        for (cell, mark) in env.board.iter_mut().zip(cells.chars()) {
+            *cell = match mark {
+                '.' => 0,
+                '1' => 1,
+                '2' => 2,
+                _ => return None,
+            };
+        }
+        env.turn = match turn {
+            "1" => 1,
+            "2" => 2,
+            _ => return None,
+        };
+        Some(env)
+    }
+
+    fn to_notation(&self) -> String {
+        let cells: String = self
+            .board
+            .iter()
+            .map(|cell| match cell {
+                0 => '.',
+                1 => '1',
+                _ => '2',
+            })
+            .collect();
+        format!("{} {}", cells, self.turn)
+    }
+}
+
+/// The board in three rows, with the notation of the cells.
+impl fmt::Display for TicTacToe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self.to_notation();
+        for row in 0..3 {
+            writeln!(f, "{}", &cells[3 * row..3 * row + 3])?;
+        }
+        Ok(())
+    }
+}
+
+/// Passing never hurts in tic-tac-toe, as an extra mark can't make a position worse.
+impl NullMove<u8, u8> for TicTacToe {
+    fn null_move(&self) -> Self {
+        TicTacToe {
+            board: self.board,
+            turn: 3 - self.turn,
+        }
+    }
+}
+
+/// Returns the cell 'cell' goes to under 'symmetry', one of the 8 symmetries of the square:
+/// the identity, three rotations, and four reflections.
+fn transform_cell(cell: usize, symmetry: usize) -> usize {
+    let (row, column) = (cell / 3, cell % 3);
+    let (row, column) = match symmetry {
+        0 => (row, column),
+        1 => (column, 2 - row),
+        2 => (2 - row, 2 - column),
+        3 => (2 - column, row),
+        4 => (row, 2 - column),
+        5 => (2 - row, column),
+        6 => (column, row),
+        _ => (2 - column, 2 - row),
+    };
+    3 * row + column
+}
+
+impl Symmetric<u8, u8> for TicTacToe {
+    fn symmetries(&self) -> usize {
+        8
+    }
+
+    fn transform(&self, symmetry: usize) -> Self {
+        let mut env = *self;
+        for cell in 0..9 {
+            env.board[transform_cell(cell, symmetry)] = self.board[cell];
+        }
+        env
+    }
+
+    fn transform_action(&self, action: &u8, symmetry: usize) -> u8 {
+        transform_cell(*action as usize, symmetry) as u8
+    }
+
+    fn inverse(&self, symmetry: usize) -> usize {
+        (0..8)
+            .find(|inverse| {
+                (0..9).all(|cell| transform_cell(transform_cell(cell, symmetry), *inverse) == cell)
+            })
+            .expect("Symmetries of the square have an inverse")
+    }
+}
+
+/// Plays the first valid action of tic-tac-toe.
+pub struct First(pub u8);
+
+impl Agent<u8, u8, TicTacToe> for First {
+    fn identity(&self) -> u8 {
+        self.0
+    }
+
+    fn action(&mut self, env: &TicTacToe) -> Option<u8> {
+        env.valid_actions().next()
+    }
+}
+
+/// Plays the last valid action of tic-tac-toe.
+pub struct Last(pub u8);
+
+impl Agent<u8, u8, TicTacToe> for Last {
+    fn identity(&self) -> u8 {
+        self.0
+    }
+
+    fn action(&mut self, env: &TicTacToe) -> Option<u8> {
+        env.valid_actions().last()
+    }
+}
+
+/// One for a win, minus one for a loss, and zero otherwise.
+pub fn reward(env: &TicTacToe, agent_id: &u8) -> f64 {
+    match env.winner() {
+        Some(winner) if winner == *agent_id => 1f64,
+        Some(_) => -1f64,
+        None => 0f64,
+    }
+}
+
+/// Positions after a few openings, including ones where an agent can force a win.
+pub fn positions() -> Vec<TicTacToe> {
+    [&[][..], &[4], &[0, 1], &[0, 4, 8], &[0, 3, 1, 4]]
+        .iter()
+        .map(|actions| {
+            let mut env = TicTacToe::initial_state();
+            for action in actions.iter() {
+                env.update(action);
+            }
+            env
+        })
+        .collect()
+}
+
+/// A game of 'length' plies with a single action, 0, in every position. Agent 1 wins at the
+/// end. Searches descending into it go as deep as the game is long.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chain {
+    pub ply: u32,
+    pub length: u32,
+}
+
+impl Chain {
+    pub fn new(length: u32) -> Self {
+        Chain { ply: 0, length }
+    }
+}
+
+impl Environment<u8, u8> for Chain {
+    fn initial_state() -> Self {
+        Chain::new(100_000)
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.ply += 1;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        if self.is_terminal() {
+            Box::new(std::iter::empty())
+        } else {
+            Box::new(std::iter::once(0))
+        }
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a == 0 && !self.is_terminal()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.ply >= self.length
+    }
+
+    fn turn(&self) -> u8 {
+        1 + (self.ply % 2) as u8
+    }
+
+    fn winner(&self) -> Option<u8> {
+        if self.is_terminal() {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+impl Undo<u8, u8> for Chain {
+    type Token = ();
+
+    fn make(&mut self, a: &u8) -> Option<()> {
+        if self.update(a) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn unmake(&mut self, _: ()) {
+        self.ply -= 1;
+    }
+}
+
+/// A bug of 'Broken'. Unless told otherwise, it shows once cell 8 is taken, and affects the
+/// first valid action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Bug {
+    None,
+    /// 'is_valid' rejects the action.
+    RejectedByIsValid,
+    /// 'update' rejects the action.
+    RejectedByUpdate,
+    /// 'valid_actions' produces the action twice.
+    DuplicatedAction,
+    /// 'valid_actions' leaves out the last action, which 'is_valid' still accepts.
+    MissingAction,
+    /// 'what_if' does not change the turn for the action.
+    WhatIfDiffers,
+    /// 'what_if' gives the turn to the other agent in the environment it is called on.
+    MutatingWhatIf,
+    /// 'turn' changes every time it is called.
+    UnstableTurn,
+    /// 'hash' changes every time it is called.
+    UnstableHash,
+    /// 'valid_actions' produces the free cells in every position, even after a win.
+    ActionsAtEnd,
+    /// Games only end when the board is full, even after a win.
+    WinnerBeforeEnd,
+}
+
+/// Tic-tac-toe with a deliberate bug, to check that validation tools find it. The game sits in
+/// cells so that the bugs can change it from methods taking '&self'.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Broken {
+    pub env: Cell<TicTacToe>,
+    pub bug: Bug,
+    turns: Cell<u8>,
+}
+
+/// Number of hashes of environments showing 'Bug::UnstableHash'.
+static UNSTABLE_HASHES: AtomicU64 = AtomicU64::new(0);
+
+impl Broken {
+    pub fn new(bug: Bug) -> Self {
+        Broken {
+            env: Cell::new(TicTacToe::initial_state()),
+            bug,
+            turns: Cell::new(0),
+        }
+    }
+
+    /// Returns true iff the bug shows in this position.
+    fn shows(&self, bug: Bug) -> bool {
+        self.bug == bug && self.env.get().board[8] != 0
+    }
+
+    /// Returns true iff 'a' is the action affected by the bug.
+    fn affects(&self, bug: Bug, a: &u8) -> bool {
+        self.shows(bug) && self.env.get().valid_actions().next() == Some(*a)
+    }
+}
+
+impl Hash for Broken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.env.get().hash(state);
+        self.bug.hash(state);
+        if self.shows(Bug::UnstableHash) {
+            UNSTABLE_HASHES.fetch_add(1, Ordering::Relaxed).hash(state);
+        }
+    }
+}
+
+impl Environment<u8, u8> for Broken {
+    fn initial_state() -> Self {
+        Broken::new(Bug::None)
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if self.affects(Bug::RejectedByUpdate, a) || !self.is_valid(a) {
+            return false;
+        }
+        self.env.get_mut().update(a)
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let env = self.env.get();
+        let next = Broken {
+            env: Cell::new(env.what_if(a)),
+            bug: self.bug,
+            turns: Cell::new(0),
+        };
+        if self.affects(Bug::WhatIfDiffers, a) {
+            next.env.set(TicTacToe {
+                turn: env.turn,
+                ..next.env.get()
+            });
+        }
+        if self.affects(Bug::MutatingWhatIf, a) {
+            self.env.set(TicTacToe {
+                turn: 3 - env.turn,
+                ..env
+            });
+        }
+        next
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        let env = self.env.get();
+        let actions: Vec<u8> = if self.bug == Bug::ActionsAtEnd {
+            (0..9).filter(|a| env.board[*a as usize] == 0).collect()
+        } else {
+            env.valid_actions().collect()
+        };
+
+        if self.shows(Bug::DuplicatedAction) {
+            Box::new(std::iter::once(actions[0]).chain(actions))
+        } else if self.shows(Bug::MissingAction) {
+            let kept = actions.len() - 1;
+            Box::new(actions.into_iter().take(kept))
+        } else {
+            Box::new(actions.into_iter())
+        }
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        let env = self.env.get();
+        if self.affects(Bug::RejectedByIsValid, a) {
+            false
+        } else if self.bug == Bug::WinnerBeforeEnd {
+            *a < 9 && env.board[*a as usize] == 0
+        } else {
+            env.is_valid(a)
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        let env = self.env.get();
+        if self.bug == Bug::WinnerBeforeEnd {
+            env.board.iter().all(|cell| *cell != 0)
+        } else {
+            env.is_terminal()
+        }
+    }
+
+    fn turn(&self) -> u8 {
+        if self.shows(Bug::UnstableTurn) {
+            self.turns.set(self.turns.get() + 1);
+            1 + self.turns.get() % 2
+        } else {
+            self.env.get().turn()
+        }
+    }
+
+    fn winner(&self) -> Option<u8> {
+        self.env.get().winner()
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(feature = "serde")]
+use std::time::Duration;
+
+use gts::agents::AgentConfig;
+use gts::agents::AlphabetaConfig;
+use gts::agents::MctsUctConfig;
+use gts::agents::MinmaxConfig;
+use gts::agents::ParseConfigError;
+#[cfg(feature = "serde")]
+use gts::record::GameRecord;
+#[cfg(feature = "serde")]
+use gts::record::GameResult;
+#[cfg(feature = "serde")]
+use gts::record::Move;
+use gts::tree_search::LateMoveReductions;
+
+/// A configuration of every agent, with every option of each set.
+fn configs() -> Vec<AgentConfig> {
+    let mut minmax = MinmaxConfig::new(4);
+    minmax.killers = true;
+    minmax.history = true;
+    minmax.quiescence = Some(3);
+
+    let mut alphabeta = AlphabetaConfig::new(6);
+    alphabeta.pvs = true;
+    alphabeta.aspiration = Some(0.25);
+    alphabeta.mtdf = true;
+    alphabeta.first_guess = Some(-0.5);
+    alphabeta.killers = true;
+    alphabeta.history = true;
+    alphabeta.lmr = Some(LateMoveReductions {
+        after: 4,
+        min_depth: 3,
+        reduction: 2,
+    });
+    alphabeta.repetitions = true;
+    alphabeta.quiescence = Some(2);
+    alphabeta.null_move = Some(2);
+    alphabeta.symmetries = true;
+
+    let mut mcts = MctsUctConfig::new(1.4, 10_000);
+    mcts.keep_cache = true;
+
+    vec![
+        AgentConfig::Human,
+        AgentConfig::Tablebase,
+        AgentConfig::Minmax(MinmaxConfig::new(2)),
+        AgentConfig::Minmax(minmax),
+        AgentConfig::Alphabeta(AlphabetaConfig::new(1)),
+        AgentConfig::Alphabeta(alphabeta),
+        AgentConfig::MctsUct(MctsUctConfig::new(0.5, 100)),
+        AgentConfig::MctsUct(mcts),
+    ]
+}
+
+/// Parses 'spec', with the message of the error when it is invalid.
+fn parse(spec: &str) -> Result<AgentConfig, String> {
+    spec.parse()
+        .map_err(|error: ParseConfigError| error.to_string())
+}
+
+#[test]
+fn specs_are_read_back() {
+    for config in configs() {
+        let spec = config.to_string();
+
+        assert_eq!(spec.parse(), Ok(config), "{}", spec);
+    }
+}
+
+#[test]
+fn specs_are_written_shortly() {
+    let lmr = LateMoveReductions {
+        after: 4,
+        min_depth: 3,
+        reduction: 2,
+    };
+    let mut alphabeta = AlphabetaConfig::new(6);
+    alphabeta.pvs = true;
+    alphabeta.lmr = Some(lmr);
+
+    assert_eq!(
+        AgentConfig::Alphabeta(alphabeta).to_string(),
+        "alphabeta:depth=6,pvs,lmr=4/3/2"
+    );
+    assert_eq!(
+        AgentConfig::MctsUct(MctsUctConfig::new(1.4, 100)).to_string(),
+        "mcts:runs=100,c=1.4"
+    );
+    assert_eq!(AgentConfig::Human.to_string(), "human");
+}
+
+#[test]
+fn specs_accept_aliases_spaces_and_flag_values() {
+    let expected = AgentConfig::MctsUct(MctsUctConfig::new(1.4, 100));
+
+    assert_eq!(parse("mcts:runs=100,c=1.4"), Ok(expected));
+    assert_eq!(
+        parse("mcts: mc_runs = 100 , exploration = 1.4"),
+        Ok(expected)
+    );
+    assert_eq!(parse("mcts:c=1.4,runs=100,keep=false"), Ok(expected));
+
+    let mut alphabeta = AlphabetaConfig::new(3);
+    alphabeta.pvs = true;
+    alphabeta.lmr = Some(LateMoveReductions {
+        after: 1,
+        min_depth: 2,
+        reduction: 1,
+    });
+    assert_eq!(
+        parse("alphabeta:lmr=1/2/1,pvs=true,depth=3"),
+        Ok(AgentConfig::Alphabeta(alphabeta))
+    );
+}
+
+#[test]
+fn invalid_specs_are_refused() {
+    for (spec, error) in [
+        ("minmax", "missing option 'depth'"),
+        ("alphabeta:pvs", "missing option 'depth'"),
+        ("mcts:c=1.4", "missing option 'runs'"),
+        ("mcts:runs=100", "missing option 'c'"),
+        ("alphabeta:depth=3,depth=4", "repeated option 'depth'"),
+        ("mcts:runs=1,mc_runs=2,c=1", "repeated option 'runs'"),
+        ("alphabeta:depth=3,speed=9", "unknown option 'speed'"),
+        ("human:depth=3", "unknown option 'depth'"),
+        ("alphabeta:depth", "missing value for 'depth'"),
+        ("alphabeta:depth=deep", "invalid value for 'depth'"),
+        ("alphabeta:depth=3,pvs=maybe", "invalid value for 'pvs'"),
+        ("alphabeta:depth=3,lmr=1/2", "invalid value for 'lmr'"),
+        ("alphabeta:depth=3,lmr=1/2/3/4", "invalid value for 'lmr'"),
+        ("chess:depth=3", "unknown agent 'chess'"),
+    ] {
+        assert_eq!(
+            parse(spec),
+            Err(format!("Invalid agent spec: {}", error)),
+            "{}",
+            spec
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn configs_are_read_back_from_json() {
+    for config in configs() {
+        let json = serde_json::to_string(&config).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<AgentConfig>(&json).unwrap(),
+            config,
+            "{}",
+            json
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_configs_are_tagged_with_the_agent_and_default_their_options() {
+    let json = serde_json::to_value(AgentConfig::MctsUct(MctsUctConfig::new(1.4, 100))).unwrap();
+    assert_eq!(json["agent"], "mcts");
+    assert_eq!(json["mc_runs"], 100);
+
+    let config: AgentConfig =
+        serde_json::from_str(r#"{"agent": "alphabeta", "depth": 5}"#).unwrap();
+    assert_eq!(config, AgentConfig::Alphabeta(AlphabetaConfig::new(5)));
+
+    let config: AgentConfig = serde_json::from_str(r#"{"agent": "human"}"#).unwrap();
+    assert_eq!(config, AgentConfig::Human);
+
+    assert!(serde_json::from_str::<AgentConfig>(r#"{"agent": "minmax"}"#).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn game_records_are_read_back_from_json() {
+    let mut record: GameRecord<u8, u8> = GameRecord::new(vec![1, 2])
+        .with_metadata("Event", "Test")
+        .with_player_name(&1, "First")
+        .with_player_config(&2, configs()[5])
+        .with_start("1........ 2");
+    record.moves.push(Move {
+        agent_id: 2,
+        action: 4,
+        time: Some(Duration::from_millis(15)),
+        score: Some(0.5),
+        depth: Some(6),
+    });
+    record.result = GameResult::Unfinished;
+
+    let json = serde_json::to_string(&record).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<GameRecord<u8, u8>>(&json).unwrap(),
+        record
+    );
+}
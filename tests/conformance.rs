@@ -0,0 +1,118 @@
+mod common;
+
+use std::collections::HashSet;
+
+use gts::abstractions::Environment;
+use gts::validation::Conformance;
+use gts::validation::Invariant;
+use gts::validation::Violation;
+
+use common::Broken;
+use common::Bug;
+use common::TicTacToe;
+
+fn check(bug: Bug) -> Result<(), Violation<u8>> {
+    Conformance::new().check_from(&Broken::new(bug))
+}
+
+/// Returns the invariant broken with 'bug', and the actions leading to it.
+fn violation(bug: Bug) -> (Invariant, Vec<u8>) {
+    let violation = check(bug).expect_err("The bug is found");
+    (violation.invariant, violation.actions)
+}
+
+/// Returns true iff playing 'actions' on tic-tac-toe wins the game with the last one.
+fn wins_with_the_last(actions: &[u8]) -> bool {
+    let mut env = TicTacToe::initial_state();
+    actions.iter().enumerate().all(|(index, action)| {
+        let played = env.update(action);
+        played && (index + 1 == actions.len()) == env.winner().is_some()
+    })
+}
+
+#[test]
+fn correct_environments_conform() {
+    assert!(Conformance::new().check::<u8, u8, TicTacToe>().is_ok());
+    assert!(check(Bug::None).is_ok());
+}
+
+#[test]
+fn bugs_in_positions_are_shrunk_to_the_action_showing_them() {
+    assert_eq!(
+        violation(Bug::UnstableTurn),
+        (Invariant::StableTurn, vec![8])
+    );
+    assert_eq!(
+        violation(Bug::UnstableHash),
+        (Invariant::HashConsistentWithEq, vec![8])
+    );
+}
+
+#[test]
+fn bugs_in_actions_are_shrunk_to_the_action_showing_them_and_the_broken_one() {
+    assert_eq!(
+        violation(Bug::MutatingWhatIf),
+        (Invariant::WhatIfDoesNotMutate, vec![8, 0])
+    );
+    assert_eq!(
+        violation(Bug::RejectedByIsValid),
+        (Invariant::ValidActionsAccepted, vec![8, 0])
+    );
+    assert_eq!(
+        violation(Bug::RejectedByUpdate),
+        (Invariant::ValidActionsAccepted, vec![8, 0])
+    );
+    assert_eq!(
+        violation(Bug::WhatIfDiffers),
+        (Invariant::WhatIfMatchesUpdate, vec![8, 0])
+    );
+}
+
+#[test]
+fn bugs_at_the_end_are_shrunk_to_a_game_without_removable_actions() {
+    let cases = [
+        (Bug::ActionsAtEnd, Invariant::NoActionsAtEnd),
+        (Bug::WinnerBeforeEnd, Invariant::NoWinnerBeforeEnd),
+    ];
+
+    for (bug, invariant) in cases {
+        let (found, actions) = violation(bug);
+
+        assert_eq!(found, invariant);
+        assert!(wins_with_the_last(&actions), "{:?}", actions);
+        for index in 0..actions.len() {
+            let mut shorter = actions.clone();
+            shorter.remove(index);
+            assert!(!wins_with_the_last(&shorter), "{:?}", shorter);
+        }
+    }
+}
+
+#[test]
+fn walks_are_reproducible() {
+    let mut found = HashSet::new();
+    for seed in 1..10 {
+        let conformance = Conformance::new().with_seed(seed).with_walks(3);
+        let first = conformance.check_from(&Broken::new(Bug::ActionsAtEnd));
+        let second = conformance.check_from(&Broken::new(Bug::ActionsAtEnd));
+
+        let actions = first.unwrap_err().actions;
+        assert_eq!(second.unwrap_err().actions, actions);
+        found.insert(actions);
+    }
+
+    // Other seeds play other games.
+    assert!(found.len() > 1);
+}
+
+#[test]
+fn walks_are_bounded() {
+    let env = Broken::new(Bug::ActionsAtEnd);
+
+    // Games need at least five actions to be won.
+    assert!(Conformance::new()
+        .with_max_length(4)
+        .check_from(&env)
+        .is_ok());
+    assert!(Conformance::new().with_walks(0).check_from(&env).is_ok());
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use std::collections::HashMap;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+use gts::abstractions::Environment;
+use gts::abstractions::Undo;
+use gts::tree_search::depth_first;
+use gts::tree_search::depth_first_in_place;
+use gts::tree_search::mcts;
+use gts::tree_search::mcts_in_place;
+use gts::tree_search::minmax;
+use gts::tree_search::minmax_in_place;
+use gts::tree_search::uct;
+use gts::tree_search::uct_in_place;
+use gts::tree_search::InPlaceNode;
+use gts::tree_search::Killers;
+use gts::tree_search::Negamax;
+use gts::tree_search::SearchNode;
+use gts::tree_search::StatelessNode;
+
+use common::positions;
+use common::reward;
+use common::Chain;
+use common::TicTacToe;
+
+#[test]
+fn in_place_searches_agree_with_cloning_ones() {
+    for env in positions() {
+        for depth in 1..=6 {
+            for agent_id in [1, 2] {
+                let mut in_place = env;
+                let expected = minmax(&env, &agent_id, &reward, depth, f64::MIN, f64::MAX);
+                let found =
+                    minmax_in_place(&mut in_place, &agent_id, &reward, depth, f64::MIN, f64::MAX);
+
+                assert_eq!(found, expected, "{:?} at depth {}", env, depth);
+                assert_eq!(in_place, env);
+            }
+        }
+
+        let mut in_place = env;
+        assert_eq!(
+            depth_first_in_place(&mut in_place, &1),
+            depth_first(&env, &1)
+        );
+        assert_eq!(in_place, env);
+    }
+}
+
+#[test]
+fn mcts_in_place_fills_the_cache_like_mcts() {
+    for env in positions() {
+        let mut cache = HashMap::new();
+        let mut in_place_cache = HashMap::new();
+        let mut in_place = env;
+
+        for _ in 0..200 {
+            let expected = mcts(&env, &1, &|e, a, c| uct(e, a, c, 1.4), &mut cache);
+            let found = mcts_in_place(
+                &mut in_place,
+                &1,
+                &|e, a, c| uct_in_place(e, a, c, 1.4),
+                &mut in_place_cache,
+            );
+
+            assert_eq!(found, expected);
+            assert_eq!(in_place, env);
+        }
+        assert_eq!(in_place_cache, cache);
+    }
+}
+
+/// Searches 'node' at depth 6 with PVS and killer moves, and returns the result and the
+/// number of positions visited.
+fn search_enhanced<N>(node: &mut N) -> ((f64, Option<u8>), u64)
+where
+    N: SearchNode<u8, u8, Env = TicTacToe>,
+{
+    let mut negamax = Negamax::new(&1, &reward)
+        .with_pvs(true)
+        .with_killers(Some(Box::new(Killers::new())));
+    let found = negamax.search(node, 6, f64::NEG_INFINITY, f64::INFINITY);
+    (found, negamax.stats().nodes)
+}
+
+#[test]
+fn in_place_nodes_support_the_search_enhancements() {
+    for env in positions() {
+        let expected = search_enhanced(&mut StatelessNode::new(&env));
+        let mut in_place = env;
+
+        assert_eq!(
+            search_enhanced(&mut InPlaceNode::new(&mut in_place)),
+            expected
+        );
+        assert_eq!(in_place, env);
+    }
+}
+
+/// A chain whose actions can't be made in place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Stuck(Chain);
+
+impl Environment<u8, u8> for Stuck {
+    fn initial_state() -> Self {
+        Stuck(Chain::new(10))
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        self.0.update(a)
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        Stuck(self.0.what_if(a))
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        self.0.valid_actions()
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        self.0.is_valid(a)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.0.is_terminal()
+    }
+
+    fn turn(&self) -> u8 {
+        self.0.turn()
+    }
+
+    fn winner(&self) -> Option<u8> {
+        self.0.winner()
+    }
+}
+
+impl Undo<u8, u8> for Stuck {
+    type Token = ();
+
+    fn make(&mut self, _: &u8) -> Option<()> {
+        None
+    }
+
+    fn unmake(&mut self, _: ()) {}
+}
+
+/// Runs 'search' on a fresh 'Stuck' environment, checks that it panics because an action can't
+/// be made, and that the environment is left as it was found.
+fn assert_panics_and_restores(search: impl Fn(&mut Stuck)) {
+    let mut env = Stuck::initial_state();
+    env.update(&0);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| search(&mut env)));
+
+    let message = result.expect_err("The search must panic");
+    let message = match message.downcast_ref::<String>() {
+        Some(message) => message.as_str(),
+        None => message.downcast_ref::<&str>().copied().unwrap_or_default(),
+    };
+    assert!(message.starts_with("'Undo::make' failed"), "{}", message);
+
+    let mut expected = Stuck::initial_state();
+    expected.update(&0);
+    assert_eq!(env, expected);
+}
+
+#[test]
+fn mcts_in_place_panics_when_an_action_can_not_be_made() {
+    assert_panics_and_restores(|env| {
+        let mut cache = HashMap::new();
+        mcts_in_place(env, &1, &|e, a, c| uct_in_place(e, a, c, 1.4), &mut cache);
+    });
+}
+
+#[test]
+fn minmax_in_place_panics_when_an_action_can_not_be_made() {
+    assert_panics_and_restores(|env| {
+        minmax_in_place(env, &1, &|_, _| 0f64, 4, f64::MIN, f64::MAX);
+    });
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gts::abstractions::Environment;
+use gts::cache::minmax_data::MinMaxData;
+use gts::cache::node::Node;
+use gts::tree_search::mcts;
+use gts::tree_search::mcts_in_place;
+use gts::tree_search::uct;
+use gts::tree_search::uct_in_place;
+
+use common::Chain;
+
+/// Deep enough to overflow the stack of any search recursing once per ply.
+const LENGTH: u32 = 100_000;
+
+type ChainNode = Node<Chain, u8, u8, MinMaxData<u8>>;
+
+#[test]
+fn mcts_runs_to_the_end_of_a_long_game() {
+    let env = Chain::new(LENGTH);
+    let mut cache = HashMap::new();
+    let value = mcts(&env, &1, &|e, a, c| uct(e, a, c, 1.4), &mut cache);
+
+    assert_eq!(value, (1f64, 1));
+    assert_eq!(cache.len(), LENGTH as usize + 1);
+    assert_eq!(cache[&env], (1f64, 1));
+}
+
+#[test]
+fn mcts_in_place_runs_to_the_end_of_a_long_game() {
+    let mut env = Chain::new(LENGTH);
+    let mut cache = HashMap::new();
+    let value = mcts_in_place(
+        &mut env,
+        &2,
+        &|e, a, c| uct_in_place(e, a, c, 1.4),
+        &mut cache,
+    );
+
+    assert_eq!(value, (-1f64, 1));
+    assert_eq!(env, Chain::new(LENGTH));
+    assert_eq!(cache.len(), LENGTH as usize + 1);
+}
+
+#[test]
+fn node_cache_is_traversed_and_rebased_through_a_long_game() {
+    let env = Rc::new(Chain::new(LENGTH));
+    let root = Rc::new(RefCell::new(ChainNode::new(&env)));
+
+    let mut node = root.clone();
+    let mut plies = 0;
+    loop {
+        let child = node.borrow_mut().next();
+        match child {
+            Some((child, _)) => node = child,
+            None => break,
+        }
+        plies += 1;
+    }
+
+    assert_eq!(plies, LENGTH);
+    assert!(node.borrow().environment().is_terminal());
+
+    // Rebasing on the second position keeps every position after it.
+    let second = root.borrow().cache_get(&env.what_if(&0)).unwrap();
+    second.borrow().rebase_cache();
+    assert_eq!(second.borrow().cache_len(), LENGTH as usize);
+    assert!(second.borrow().cache_get(&env).is_none());
+}
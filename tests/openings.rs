@@ -0,0 +1,86 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::abstractions::Notation;
+use gts::record::replay;
+use gts::record::start_position;
+use gts::record::GameRecord;
+use gts::record::RecordError;
+use gts::runner::play_from;
+use gts::runner::play_paired_openings;
+
+use common::positions;
+use common::First;
+use common::Last;
+use common::TicTacToe;
+
+#[test]
+fn positions_are_read_back_from_their_notation() {
+    for env in positions() {
+        let notation = env.to_notation();
+
+        assert_eq!(
+            TicTacToe::from_notation(&notation),
+            Some(env),
+            "{}",
+            notation
+        );
+    }
+    assert_eq!(TicTacToe::initial_state().to_notation(), "......... 1");
+
+    for invalid in ["", "......... 3", "........ 1", ".........1", "....x.... 2"] {
+        assert_eq!(TicTacToe::from_notation(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn games_played_from_a_position_replay_from_their_start() {
+    for env in positions() {
+        let record = play_from(&env, &mut First(1), &mut Last(2));
+        let start: TicTacToe = start_position(&record).unwrap();
+
+        assert_eq!(start, env);
+        let end = replay(&record, start).unwrap();
+        assert!(end.is_terminal());
+    }
+}
+
+#[test]
+fn starts_default_to_the_initial_state() {
+    let mut record: GameRecord<u8, u8> = GameRecord::new(vec![1, 2]);
+    assert_eq!(start_position(&record), Ok(TicTacToe::initial_state()));
+
+    record = record.with_start("not a position");
+    assert_eq!(
+        start_position::<u8, u8, TicTacToe>(&record),
+        Err(RecordError::InvalidStart)
+    );
+}
+
+#[test]
+fn paired_openings_swap_the_sides_of_the_engines() {
+    let openings = positions();
+    let paired = play_paired_openings(&openings, (1, 2), First, Last);
+
+    assert_eq!(paired.len(), openings.len());
+    for (games, opening) in paired.iter().zip(&openings) {
+        for (record, side) in games.games() {
+            // Engine 1 plays 'First', so the first action of its side is the first one left.
+            let mut env: TicTacToe = start_position(record).unwrap();
+            assert_eq!(env, *opening);
+            for m in record.moves.iter() {
+                let expected = if m.agent_id == side {
+                    env.valid_actions().next()
+                } else {
+                    env.valid_actions().last()
+                };
+                assert_eq!(Some(m.action), expected);
+                env.update(&m.action);
+            }
+        }
+
+        let (points_1, points_2) = games.points();
+        assert_eq!(points_1 + points_2, 2f64);
+        assert_eq!(games.wdl().points(), points_1);
+    }
+}
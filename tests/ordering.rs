@@ -0,0 +1,74 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::tree_search::History;
+use gts::tree_search::Killers;
+use gts::tree_search::Negamax;
+use gts::tree_search::OrderingFn;
+use gts::tree_search::StatelessNode;
+
+use common::reward;
+use common::TicTacToe;
+
+/// Deep enough to reach the end of every game, so that the value found is the draw.
+const DEPTH: usize = 9;
+
+/// Searches the initial position at 'DEPTH' with the enhancements set by 'configure', and
+/// returns the value found and the number of positions visited.
+fn search<'a>(
+    configure: impl FnOnce(Negamax<'a, u8, u8, TicTacToe>) -> Negamax<'a, u8, u8, TicTacToe>,
+) -> (f64, u64) {
+    let env = TicTacToe::initial_state();
+    let mut negamax = configure(Negamax::new(&1, &reward));
+    let (value, _) = negamax.search(
+        &mut StatelessNode::new(&env),
+        DEPTH,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+    );
+    (value, negamax.stats().nodes)
+}
+
+/// Tries winning actions first, then the center, then the corners.
+fn ordering(env: &TicTacToe, action: &u8) -> f64 {
+    if env.what_if(action).winner().is_some() {
+        3f64
+    } else {
+        match action {
+            4 => 2f64,
+            0 | 2 | 6 | 8 => 1f64,
+            _ => 0f64,
+        }
+    }
+}
+
+#[test]
+fn ordering_visits_fewer_positions() {
+    let ordering: &OrderingFn<u8, TicTacToe> = &ordering;
+    let (value, nodes) = search(|negamax| negamax);
+    assert_eq!(value, 0f64);
+    let (ordered_value, ordered_nodes) = search(|negamax| negamax.with_ordering(Some(ordering)));
+
+    assert_eq!(ordered_value, value);
+    assert!(ordered_nodes < nodes, "{} >= {}", ordered_nodes, nodes);
+}
+
+#[test]
+fn killers_visit_fewer_positions() {
+    let (value, nodes) = search(|negamax| negamax);
+    let (killers_value, killers_nodes) =
+        search(|negamax| negamax.with_killers(Some(Box::new(Killers::new()))));
+
+    assert_eq!(killers_value, value);
+    assert!(killers_nodes < nodes, "{} >= {}", killers_nodes, nodes);
+}
+
+#[test]
+fn history_visits_fewer_positions() {
+    let (value, nodes) = search(|negamax| negamax);
+    let (history_value, history_nodes) =
+        search(|negamax| negamax.with_history(Some(Box::new(History::new()))));
+
+    assert_eq!(history_value, value);
+    assert!(history_nodes < nodes, "{} >= {}", history_nodes, nodes);
+}
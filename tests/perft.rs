@@ -0,0 +1,101 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::validation::divide;
+use gts::validation::perft;
+use gts::validation::validate;
+use gts::validation::PerftError;
+
+use common::Broken;
+use common::Bug;
+use common::TicTacToe;
+
+const COUNTS: [u64; 10] = [1, 9, 72, 504, 3024, 15120, 54720, 148176, 200448, 127872];
+
+/// Every cell, whether it is free or not.
+fn cells<T>(_: &T) -> Vec<u8> {
+    (0..9).collect()
+}
+
+#[test]
+fn tic_tac_toe_counts() {
+    assert_eq!(perft(&TicTacToe::initial_state(), 9), COUNTS);
+    assert_eq!(perft(&TicTacToe::initial_state(), 3), COUNTS[..4]);
+}
+
+#[test]
+fn divided_counts_add_up() {
+    let env = TicTacToe::initial_state();
+
+    for (depth, count) in COUNTS.iter().enumerate().take(7).skip(1) {
+        let divided = divide(&env, depth);
+        assert_eq!(divided.len(), 9);
+        assert_eq!(
+            divided.iter().map(|(_, leaves)| leaves).sum::<u64>(),
+            *count
+        );
+    }
+    assert!(divide(&env, 0).is_empty());
+}
+
+#[test]
+fn tic_tac_toe_is_valid() {
+    let env = TicTacToe::initial_state();
+
+    assert_eq!(validate(&env, 9, Some(&cells)).unwrap(), COUNTS);
+    assert_eq!(
+        validate(&Broken::new(Bug::None), 5, Some(&cells)).unwrap(),
+        COUNTS[..6]
+    );
+}
+
+#[test]
+fn broken_environments_are_reported() {
+    // Each bug shows once cell 8 is taken, on the first valid action, or on the last one when
+    // it is left out.
+    let found = |bug: Bug| {
+        validate(&Broken::new(bug), 9, Some(&cells))
+            .err()
+            .unwrap_or_else(|| panic!("{:?} is not found", bug))
+    };
+
+    match found(Bug::RejectedByIsValid) {
+        PerftError::Rejected(env, action) => {
+            assert_ne!(env.env.get().board[8], 0);
+            assert_eq!(action, env.env.get().valid_actions().next().unwrap());
+        }
+        error => panic!("Unexpected error {:?}", error),
+    }
+    match found(Bug::RejectedByUpdate) {
+        PerftError::UpdateFailed(env, action) => {
+            assert_eq!(action, env.env.get().valid_actions().next().unwrap())
+        }
+        error => panic!("Unexpected error {:?}", error),
+    }
+    match found(Bug::DuplicatedAction) {
+        PerftError::Duplicated(env, action) => {
+            assert_eq!(action, env.env.get().valid_actions().next().unwrap())
+        }
+        error => panic!("Unexpected error {:?}", error),
+    }
+    match found(Bug::MissingAction) {
+        PerftError::Missing(env, action) => {
+            assert_eq!(action, env.env.get().valid_actions().last().unwrap())
+        }
+        error => panic!("Unexpected error {:?}", error),
+    }
+    match found(Bug::WhatIfDiffers) {
+        PerftError::WhatIfMismatch(env, action) => {
+            assert_eq!(action, env.env.get().valid_actions().next().unwrap())
+        }
+        error => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn missing_actions_need_candidates() {
+    let env = Broken::new(Bug::MissingAction);
+
+    assert!(validate(&env, 3, None).is_ok());
+    assert!(validate(&env, 3, Some(&cells)).is_err());
+}
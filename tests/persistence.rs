@@ -0,0 +1,167 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::agents::AlphabetaAgent;
+use gts::agents::MctsUctAgent;
+use gts::cache::arena::Arena;
+use gts::cache::persistence;
+use gts::tree_search::MctsCache;
+
+use common::reward;
+use common::TicTacToe;
+
+type Stored = (f64, u32);
+
+/// Returns a path in the temporary directory, unique to this process and 'name'.
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gts-{}-{}", std::process::id(), name))
+}
+
+/// Returns the position reached by playing 'actions' from the initial one.
+fn position(actions: &[u8]) -> TicTacToe {
+    let mut env = TicTacToe::initial_state();
+    for action in actions {
+        assert!(env.update(action));
+    }
+    env
+}
+
+#[test]
+fn values_are_read_back() {
+    let value = vec![(1u32, String::from("one")), (2, String::from("two"))];
+    let mut bytes = Vec::new();
+    persistence::write_to(&value, b"TEST", &mut bytes).unwrap();
+
+    let read: Vec<(u32, String)> = persistence::read_from(b"TEST", &mut &bytes[..]).unwrap();
+    assert_eq!(read, value);
+}
+
+#[test]
+fn files_with_another_magic_number_are_refused() {
+    let mut bytes = Vec::new();
+    persistence::write_to(&1u32, b"TEST", &mut bytes).unwrap();
+
+    let error = persistence::read_from::<u32, _>(b"ELSE", &mut &bytes[..]).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn files_of_another_version_are_refused() {
+    let mut bytes = Vec::new();
+    persistence::write_to(&1u32, b"TEST", &mut bytes).unwrap();
+    bytes[4] += 1;
+
+    let error = persistence::read_from::<u32, _>(b"TEST", &mut &bytes[..]).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn arenas_are_read_back() {
+    let positions = [position(&[]), position(&[4]), position(&[4, 0])];
+    let mut arena: Arena<TicTacToe, u8, Stored> = Arena::new();
+    for (index, env) in positions.iter().enumerate() {
+        arena.add(env, &(index as f64, index as u32 + 1));
+    }
+    arena.set_root(positions[1]);
+    let root = arena.root().unwrap();
+    arena.next_child::<u8>(root).unwrap();
+
+    let path = temp_path("arena");
+    arena.save(&path).unwrap();
+    let read: Arena<TicTacToe, u8, Stored> = Arena::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read.len(), arena.len());
+    assert_eq!(read.environment(read.root().unwrap()), &positions[1]);
+    for env in &positions {
+        assert_eq!(MctsCache::get(&read, env), MctsCache::get(&arena, env));
+    }
+    let children = read.children(read.root().unwrap());
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        read.environment(children[0].0),
+        &positions[1].what_if(&children[0].1)
+    );
+}
+
+/// Writes an arena made of 'root' and one entry for the initial position with 'children',
+/// laid out like serialized arenas.
+fn arena_bytes(root: Option<u32>, children: Vec<(u32, u8)>) -> Vec<u8> {
+    let entry = (
+        TicTacToe::initial_state(),
+        (0f64, 0u32),
+        children,
+        None::<Vec<u8>>,
+    );
+    let mut bytes = Vec::new();
+    persistence::write_to(&(root, vec![entry]), b"GTAR", &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn arenas_with_nodes_out_of_range_are_refused() {
+    let read = |bytes: Vec<u8>| Arena::<TicTacToe, u8, Stored>::read_from(&mut &bytes[..]);
+
+    assert_eq!(read(arena_bytes(Some(0), vec![(0, 4)])).unwrap().len(), 1);
+
+    let error = read(arena_bytes(Some(1), Vec::new())).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let error = read(arena_bytes(Some(0), vec![(7, 4)])).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn alphabeta_caches_are_read_back() {
+    let env = position(&[0, 4]);
+    let mut agent = AlphabetaAgent::new(1, &reward, 4);
+    let action = agent.action(&env);
+
+    let path = temp_path("alphabeta");
+    agent.save_cache(&path).unwrap();
+    let mut loaded = AlphabetaAgent::new(1, &reward, 4);
+    loaded.load_cache(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.action(&env), action);
+    assert_eq!(loaded.last_evaluation(), agent.last_evaluation());
+}
+
+#[test]
+fn mcts_caches_are_read_back() {
+    let env = position(&[0, 4]);
+    let mut agent = MctsUctAgent::new(1, 1.4, 200).with_kept_cache();
+    let action = agent.action(&env);
+
+    let path = temp_path("mcts");
+    agent.save_cache(&path).unwrap();
+    // Without runs, the agent picks its action from the values loaded.
+    let mut loaded = MctsUctAgent::new(1, 1.4, 0);
+    loaded.load_cache(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.action(&env), action);
+}
+
+#[test]
+fn mcts_caches_of_another_kind_are_refused() {
+    let env = position(&[0, 4]);
+    let mut agent: MctsUctAgent<u8, TicTacToe, HashMap<TicTacToe, Stored>> =
+        MctsUctAgent::new(1, 1.4, 20).with_kept_cache();
+    agent.action(&env);
+
+    let path = temp_path("mcts-kind");
+    agent.save_cache(&path).unwrap();
+    let mut loaded = MctsUctAgent::new(1, 1.4, 0).with_cache(Arena::<TicTacToe, u8, Stored>::new());
+    let error = loaded.load_cache(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use std::collections::VecDeque;
+
+use gts::abstractions::play;
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::record::play_recorded;
+use gts::record::GameResult;
+
+use common::TicTacToe;
+
+/// Plays the actions of a script, then has no action.
+struct Scripted {
+    agent_id: u8,
+    actions: VecDeque<u8>,
+}
+
+impl Scripted {
+    fn new(agent_id: u8, actions: &[u8]) -> Self {
+        Scripted {
+            agent_id,
+            actions: actions.iter().copied().collect(),
+        }
+    }
+}
+
+impl Agent<u8, u8, TicTacToe> for Scripted {
+    fn identity(&self) -> u8 {
+        self.agent_id
+    }
+
+    fn action(&mut self, _env: &TicTacToe) -> Option<u8> {
+        self.actions.pop_front()
+    }
+}
+
+#[test]
+fn games_end_at_terminal_positions() {
+    let mut env = TicTacToe::initial_state();
+    let log = play(
+        &mut env,
+        &mut Scripted::new(1, &[0, 1, 2, 8]),
+        &mut Scripted::new(2, &[3, 4, 5]),
+    );
+
+    assert_eq!(log, vec![(1, 0), (2, 3), (1, 1), (2, 4), (1, 2)]);
+    assert_eq!(GameResult::of(&env), GameResult::Win(1));
+}
+
+#[test]
+fn an_agent_without_action_stops_the_game() {
+    let mut env = TicTacToe::initial_state();
+    let log = play(
+        &mut env,
+        &mut Scripted::new(1, &[0, 1]),
+        &mut Scripted::new(2, &[4]),
+    );
+
+    assert_eq!(log, vec![(1, 0), (2, 4), (1, 1)]);
+    assert!(!env.is_terminal());
+}
+
+#[test]
+fn games_stopped_by_an_agent_are_unfinished() {
+    let mut env = TicTacToe::initial_state();
+    let record = play_recorded(
+        &mut env,
+        &mut Scripted::new(1, &[0]),
+        &mut Scripted::new(2, &[]),
+    );
+
+    assert_eq!(record.moves.len(), 1);
+    assert_eq!(record.result, GameResult::Unfinished);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::tree_search::dfpn;
+use gts::tree_search::pns;
+use gts::tree_search::solve;
+use gts::tree_search::Proof;
+use gts::tree_search::ProofLimits;
+use gts::tree_search::ProofResult;
+
+use common::Chain;
+use common::TicTacToe;
+
+/// Returns the position reached by playing 'actions' from the initial one.
+fn position(actions: &[u8]) -> TicTacToe {
+    let mut env = TicTacToe::initial_state();
+    for action in actions {
+        assert!(env.update(action));
+    }
+    env
+}
+
+/// Runs both solvers on 'env' with the default limits.
+fn both(env: &TicTacToe, agent_id: &u8) -> [ProofResult<u8>; 2] {
+    let limits = ProofLimits::default();
+    [pns(env, agent_id, &limits), dfpn(env, agent_id, &limits)]
+}
+
+#[test]
+fn wins_are_proved_with_a_winning_action() {
+    // Agent 2 answered the corner with an edge next to it, which loses.
+    let env = position(&[0, 1]);
+    let solution = solve(&env, &1);
+
+    for result in both(&env, &1) {
+        assert_eq!(result.proof, Proof::Win);
+        let action = result.action.expect("Agent 1 moves at the root");
+        assert_eq!(solution.value(&env.what_if(&action)), Some(f64::MAX));
+    }
+}
+
+#[test]
+fn draws_are_disproved() {
+    let env = TicTacToe::initial_state();
+
+    for agent_id in [1, 2] {
+        for result in both(&env, &agent_id) {
+            assert_eq!(result.proof, Proof::Loss);
+            assert_eq!(result.action, None);
+        }
+    }
+}
+
+#[test]
+fn wins_of_the_opponent_are_disproved() {
+    let env = position(&[0, 1]);
+
+    for result in both(&env, &2) {
+        assert_eq!(result.proof, Proof::Loss);
+        assert_eq!(result.action, None);
+    }
+}
+
+#[test]
+fn limits_give_unknown_results() {
+    let env = TicTacToe::initial_state();
+    let few_nodes = ProofLimits {
+        max_nodes: 10,
+        ..ProofLimits::default()
+    };
+    let no_memory = ProofLimits {
+        max_memory: 0,
+        ..ProofLimits::default()
+    };
+
+    for limits in [few_nodes, no_memory] {
+        for result in [pns(&env, &1, &limits), dfpn(&env, &1, &limits)] {
+            assert_eq!(result.proof, Proof::Unknown);
+            assert_eq!(result.action, None);
+            assert!(result.nodes <= 10);
+        }
+    }
+}
+
+#[test]
+fn pns_and_dfpn_agree_with_solve() {
+    let initial = TicTacToe::initial_state();
+    let mut positions = vec![initial];
+    for first in initial.valid_actions() {
+        let env = initial.what_if(&first);
+        positions.push(env);
+        positions.extend(env.valid_actions().map(|second| env.what_if(&second)));
+    }
+
+    for agent_id in [1, 2] {
+        let solution = solve(&initial, &agent_id);
+
+        for env in &positions {
+            let expected = if solution.value(env) == Some(f64::MAX) {
+                Proof::Win
+            } else {
+                Proof::Loss
+            };
+            let [by_pns, by_dfpn] = both(env, &agent_id);
+
+            assert_eq!(by_pns.proof, expected, "{:?}", env);
+            assert_eq!(by_dfpn.proof, expected, "{:?}", env);
+        }
+    }
+}
+
+#[test]
+fn dfpn_solves_long_games() {
+    let env = Chain::new(100_000);
+    let result = dfpn(&env, &1, &ProofLimits::default());
+
+    assert_eq!(result.proof, Proof::Win);
+    assert_eq!(result.action, Some(0));
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use std::cell::Cell;
+use std::cell::RefCell;
+
+use gts::abstractions::Environment;
+use gts::cache::minmax_data::Bound;
+use gts::tree_search::LateMoveReductions;
+use gts::tree_search::Negamax;
+use gts::tree_search::NullMovePruning;
+use gts::tree_search::OrderKey;
+use gts::tree_search::SearchNode;
+use gts::tree_search::SearchStats;
+use gts::tree_search::Searched;
+use gts::tree_search::StatelessNode;
+
+use common::reward;
+use common::TicTacToe;
+
+/// Deep enough for the searches of the openings to find the draw.
+const DEPTH: usize = 8;
+
+/// Positions after the first action in a corner, on an edge and in the center, and before it.
+fn openings() -> Vec<TicTacToe> {
+    [None, Some(0), Some(1), Some(4)]
+        .iter()
+        .map(|action| {
+            let mut env = TicTacToe::initial_state();
+            if let Some(action) = action {
+                env.update(action);
+            }
+            env
+        })
+        .collect()
+}
+
+/// Searches 'env' with the enhancements set by 'configure', and returns the value found and
+/// the counters of the search.
+fn search<'a>(
+    env: &TicTacToe,
+    configure: impl FnOnce(Negamax<'a, u8, u8, TicTacToe>) -> Negamax<'a, u8, u8, TicTacToe>,
+) -> (f64, SearchStats) {
+    let mut negamax = configure(Negamax::new(&1, &reward));
+    let (value, _) = negamax.search(
+        &mut StatelessNode::new(env),
+        DEPTH,
+        f64::NEG_INFINITY,
+        f64::INFINITY,
+    );
+    (value, *negamax.stats())
+}
+
+#[test]
+fn null_move_pruning_keeps_the_value_with_fewer_positions() {
+    for env in openings() {
+        let (value, stats) = search(&env, |negamax| negamax);
+        assert_eq!(value, 0f64);
+        let (pruned_value, pruned_stats) = search(&env, |negamax| {
+            negamax.with_null_move(Some(NullMovePruning::new(2)))
+        });
+
+        assert_eq!(pruned_value, value, "{:?}", env);
+        assert!(pruned_stats.nodes < stats.nodes, "{:?}", env);
+        assert!(pruned_stats.null_move_searches > 0);
+        assert!(pruned_stats.null_move_cutoffs > 0);
+    }
+}
+
+#[test]
+fn late_move_reductions_keep_the_value_with_fewer_positions() {
+    for env in openings() {
+        let (value, stats) = search(&env, |negamax| negamax);
+        assert_eq!(value, 0f64);
+        let (reduced_value, reduced_stats) = search(&env, |negamax| {
+            negamax.with_lmr(Some(LateMoveReductions::default()))
+        });
+
+        assert_eq!(reduced_value, value, "{:?}", env);
+        assert!(reduced_stats.nodes < stats.nodes, "{:?}", env);
+        assert!(reduced_stats.lmr_reductions > 0);
+        assert!(reduced_stats.lmr_researches > 0);
+    }
+}
+
+/// A node recording the results stored for it, together with the depth it was searched at.
+struct Recording<'e, 'r> {
+    node: StatelessNode<'e, u8, TicTacToe>,
+    depth: Cell<usize>,
+    stored: &'r RefCell<Vec<(usize, Searched<u8>)>>,
+}
+
+impl<'e, 'r> Recording<'e, 'r> {
+    fn wrap(&self, node: StatelessNode<'e, u8, TicTacToe>) -> Self {
+        Recording {
+            node,
+            depth: Cell::new(0),
+            stored: self.stored,
+        }
+    }
+}
+
+impl<'e, 'r> SearchNode<u8, u8> for Recording<'e, 'r> {
+    type Env = TicTacToe;
+
+    fn environment(&self) -> &TicTacToe {
+        self.node.environment()
+    }
+
+    fn lookup(&self, depth: usize, _is_maximizer: bool) -> Option<Searched<u8>> {
+        self.depth.set(depth);
+        None
+    }
+
+    fn store(&mut self, searched: &Searched<u8>, _is_maximizer: bool) {
+        self.stored.borrow_mut().push((self.depth.get(), *searched));
+    }
+
+    fn reset_children(&mut self) {
+        SearchNode::<u8, u8>::reset_children(&mut self.node)
+    }
+
+    fn order_children(&mut self, key: &dyn Fn(&TicTacToe, &u8) -> OrderKey) {
+        self.node.order_children(key)
+    }
+
+    fn next_child(&mut self) -> Option<(Self, u8)> {
+        let (child, action) = self.node.next_child()?;
+        Some((self.wrap(child), action))
+    }
+
+    fn child_from(&self, env: TicTacToe) -> Self {
+        self.wrap(SearchNode::<u8, u8>::child_from(&self.node, env))
+    }
+}
+
+#[test]
+fn null_move_cutoffs_are_stored_with_the_reduced_depth() {
+    let reduction = 2;
+    let env = TicTacToe::initial_state();
+    let stored = RefCell::new(Vec::new());
+    let mut node = Recording {
+        node: StatelessNode::new(&env),
+        depth: Cell::new(0),
+        stored: &stored,
+    };
+
+    let mut negamax =
+        Negamax::new(&1, &reward).with_null_move(Some(NullMovePruning::new(reduction)));
+    negamax.search(&mut node, 6, f64::NEG_INFINITY, f64::INFINITY);
+    assert!(negamax.stats().null_move_cutoffs > 0);
+
+    // Cutoffs of the children have an action, unlike the ones of null moves.
+    let cutoffs: Vec<_> = stored
+        .borrow()
+        .iter()
+        .filter(|(depth, searched)| {
+            *depth > 1 && searched.bound == Bound::Lower && searched.action.is_none()
+        })
+        .cloned()
+        .collect();
+    assert_eq!(cutoffs.len() as u64, negamax.stats().null_move_cutoffs);
+    for (depth, searched) in cutoffs {
+        assert_eq!(searched.depth, depth - reduction);
+    }
+}
@@ -0,0 +1,150 @@
+use gts::abstractions::Environment;
+use gts::abstractions::NoisyActions;
+use gts::tree_search::quiescence;
+use gts::tree_search::Negamax;
+use gts::tree_search::StatelessNode;
+
+const QUIET: u8 = 0;
+const CAPTURE: u8 = 1;
+
+/// A line of pieces that agents 1 and 2 capture in order, for their value. Each agent can also
+/// play a quiet action that changes nothing. Whoever captures first gets a pawn worth 3, and
+/// lets the other agent take a queen worth 9, so that no agent should start the exchange.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Exchange {
+    pieces: Vec<i32>,
+    taken: usize,
+    /// Value captured by agent 1, minus the one captured by agent 2.
+    material: i32,
+    turn: u8,
+    ply: u32,
+}
+
+impl Environment<u8, u8> for Exchange {
+    fn initial_state() -> Self {
+        Exchange {
+            pieces: vec![3, 9],
+            taken: 0,
+            material: 0,
+            turn: 1,
+            ply: 0,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        if *a == CAPTURE {
+            let value = self.pieces[self.taken];
+            self.material += if self.turn == 1 { value } else { -value };
+            self.taken += 1;
+        }
+        self.turn = 3 - self.turn;
+        self.ply += 1;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = self.clone();
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        if self.is_terminal() {
+            Box::new(std::iter::empty())
+        } else if self.taken < self.pieces.len() {
+            Box::new(vec![QUIET, CAPTURE].into_iter())
+        } else {
+            Box::new(std::iter::once(QUIET))
+        }
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        !self.is_terminal() && (*a == QUIET || (*a == CAPTURE && self.taken < self.pieces.len()))
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.ply >= 100
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        None
+    }
+}
+
+impl NoisyActions<u8, u8> for Exchange {
+    fn noisy_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        Box::new(self.valid_actions().filter(|a| *a == CAPTURE))
+    }
+}
+
+/// The material won by 'agent_id'.
+fn material(env: &Exchange, agent_id: &u8) -> f64 {
+    if *agent_id == 1 {
+        f64::from(env.material)
+    } else {
+        -f64::from(env.material)
+    }
+}
+
+#[test]
+fn searches_without_quiescence_start_losing_exchanges() {
+    let env = Exchange::initial_state();
+    let mut negamax = Negamax::new(&1, &material);
+
+    // The recapture is past the horizon.
+    let found = negamax.search(&mut StatelessNode::new(&env), 2, f64::MIN, f64::MAX);
+
+    assert_eq!(found, (3f64, Some(CAPTURE)));
+    assert_eq!(negamax.stats().quiescence_nodes, 0);
+}
+
+#[test]
+fn searches_with_quiescence_see_the_recapture() {
+    let env = Exchange::initial_state();
+
+    for depth in 2..=4 {
+        let mut negamax = Negamax::new(&1, &material).with_quiescence(4);
+        let found = negamax.search(&mut StatelessNode::new(&env), depth, f64::MIN, f64::MAX);
+
+        assert_eq!(found, (0f64, Some(QUIET)), "at depth {}", depth);
+        assert!(negamax.stats().quiescence_nodes > 0);
+    }
+}
+
+#[test]
+fn quiescence_follows_captures_until_the_position_is_quiet() {
+    let captured = Exchange::initial_state().what_if(&CAPTURE);
+
+    assert_eq!(material(&captured, &1), 3f64);
+    assert_eq!(
+        quiescence(&captured, &1, &material, 4, f64::MIN, f64::MAX),
+        -6f64
+    );
+    assert_eq!(
+        quiescence(&captured, &1, &material, 0, f64::MIN, f64::MAX),
+        3f64
+    );
+}
+
+#[test]
+fn quiescence_needs_to_be_enabled() {
+    let env = Exchange::initial_state().what_if(&CAPTURE);
+
+    assert_eq!(
+        Negamax::new(&2, &material).quiescence(&env, f64::MIN, f64::MAX),
+        None
+    );
+    assert_eq!(
+        Negamax::new(&2, &material)
+            .with_quiescence(4)
+            .quiescence(&env, f64::MIN, f64::MAX),
+        Some(6f64)
+    );
+}
@@ -0,0 +1,105 @@
+use gts::runner::elo_from_score;
+use gts::runner::Crosstable;
+use gts::runner::EloEstimate;
+use gts::runner::Ratings;
+use gts::runner::Wdl;
+
+#[test]
+fn no_elo_estimate_without_games() {
+    assert_eq!(EloEstimate::from_wdl(&Wdl::default()), None);
+
+    let estimate = EloEstimate::from_wdl(&Wdl {
+        wins: 3,
+        draws: 2,
+        losses: 1,
+    })
+    .unwrap();
+    assert!(estimate.elo > 0f64);
+    assert!(estimate.lower < estimate.elo && estimate.elo < estimate.upper);
+}
+
+#[test]
+fn ratings_print_no_score_for_entrants_without_games() {
+    let mut crosstable = Crosstable::new(vec!["a".into(), "b".into(), "idle".into()]);
+    crosstable.add(
+        0,
+        1,
+        &Wdl {
+            wins: 2,
+            draws: 1,
+            losses: 1,
+        },
+    );
+
+    let printed = Ratings::new(&crosstable, 1f64).to_string();
+
+    assert!(!printed.contains("NaN"));
+    let idle = printed.lines().find(|line| line.contains("idle")).unwrap();
+    assert_eq!(idle.split_whitespace().nth(5), Some("-"));
+}
+
+/// Entrant 'a' won 30 games and lost 10 against entrant 'b', a score of 75%.
+fn three_to_one(names: &[&str]) -> Crosstable {
+    let mut crosstable = Crosstable::new(names.iter().map(|name| name.to_string()).collect());
+    crosstable.add(
+        0,
+        1,
+        &Wdl {
+            wins: 30,
+            draws: 0,
+            losses: 10,
+        },
+    );
+    crosstable
+}
+
+#[test]
+fn ratings_of_two_entrants_match_their_score() {
+    // 75% is about 190.8 Elo, shared by the two entrants.
+    let ratings = Ratings::new(&three_to_one(&["a", "b"]), 1e-9);
+    let (a, b) = (&ratings.ratings()[0], &ratings.ratings()[1]);
+    assert!((a.elo - 95.42).abs() < 0.01, "{}", a.elo);
+    assert!((b.elo + 95.42).abs() < 0.01, "{}", b.elo);
+
+    // The prior adds a draw: 'a' scores 30.5 points out of 41 games. The variance of the
+    // difference of the ratings is the inverse of games * p * (1 - p), in natural units, and
+    // each rating carries half of the difference.
+    let ratings = Ratings::new(&three_to_one(&["a", "b"]), 1f64);
+    let score = 30.5 / 41f64;
+    let elo = elo_from_score(score) / 2f64;
+    let margin = 1.959_964 * 400f64 / 10f64.ln() / (41f64 * score * (1f64 - score)).sqrt() / 2f64;
+
+    let (a, b) = (&ratings.ratings()[0], &ratings.ratings()[1]);
+    assert!((a.elo - elo).abs() < 1e-6, "{} and {}", a.elo, elo);
+    assert!((b.elo + elo).abs() < 1e-6, "{} and {}", b.elo, elo);
+    assert!(
+        (a.margin - margin).abs() < 1e-3,
+        "{} and {}",
+        a.margin,
+        margin
+    );
+    assert!(
+        (b.margin - margin).abs() < 1e-3,
+        "{} and {}",
+        b.margin,
+        margin
+    );
+    assert!(ratings.los(0, 1) > 0.99);
+}
+
+#[test]
+fn entrants_without_games_leave_the_others_comparable() {
+    let pair = Ratings::new(&three_to_one(&["a", "b"]), 1f64);
+    let ratings = Ratings::new(&three_to_one(&["a", "b", "idle"]), 1f64);
+
+    for (rating, expected) in ratings.ratings().iter().zip(pair.ratings()) {
+        assert!((rating.elo - expected.elo).abs() < 1e-6);
+        assert!((rating.margin - expected.margin).abs() < 1e-6);
+    }
+    assert!((ratings.los(0, 1) - pair.los(0, 1)).abs() < 1e-9);
+
+    let idle = &ratings.ratings()[2];
+    assert_eq!(idle.elo, 0f64);
+    assert_eq!(idle.margin, f64::INFINITY);
+    assert_eq!(ratings.los(0, 2), 0.5);
+}
@@ -0,0 +1,90 @@
+use std::io::ErrorKind;
+
+use gts::record::parse_record;
+use gts::record::GameRecord;
+use gts::record::GameResult;
+use gts::record::Move;
+use gts::record::RecordWriter;
+
+/// Writes 'record' in the text format.
+fn write(record: &GameRecord<u8, u8>) -> std::io::Result<String> {
+    let mut writer = RecordWriter::new(Vec::new());
+    writer.write_record(record)?;
+    Ok(String::from_utf8(writer.into_inner()).unwrap())
+}
+
+fn scored(score: f64) -> Move<u8, u8> {
+    Move {
+        agent_id: 1,
+        action: 4,
+        time: None,
+        score: Some(score),
+        depth: None,
+    }
+}
+
+#[test]
+fn records_are_read_back() {
+    let mut record = GameRecord::new(vec![1, 2])
+        .with_metadata("Event", "Test")
+        .with_player_name(&1, "First")
+        .with_player_name(&2, "Second")
+        .with_start("1........ 2");
+    record.moves.push(scored(0.5));
+    record.result = GameResult::Win(1);
+
+    let text = write(&record).unwrap();
+
+    assert_eq!(parse_record::<u8, u8>(&text).unwrap(), record);
+}
+
+#[test]
+fn line_breaks_in_values_are_escaped() {
+    let record: GameRecord<u8, u8> = GameRecord::new(vec![2])
+        .with_metadata("Comment", "first line\nsecond line\r\n\"quoted\" \\")
+        .with_player_name(&2, "Two\nLines");
+
+    let text = write(&record).unwrap();
+
+    assert_eq!(text.lines().count(), 3);
+    assert!(text.contains(r#"[Comment "first line\nsecond line\r\n\"quoted\" \\"]"#));
+    assert_eq!(parse_record::<u8, u8>(&text).unwrap(), record);
+}
+
+#[test]
+fn invalid_metadata_keys_are_refused() {
+    for key in [
+        "",
+        "Two words",
+        "Tab\tkey",
+        "Quote\"",
+        "Result",
+        "Position",
+        "Player",
+        "Config",
+    ] {
+        let record: GameRecord<u8, u8> = GameRecord::new(vec![1, 2]).with_metadata(key, "value");
+
+        let error = write(&record).err().map(|error| error.kind());
+        assert_eq!(error, Some(ErrorKind::InvalidInput), "{:?}", key);
+    }
+}
+
+#[test]
+fn extreme_scores_are_written_in_scientific_notation() {
+    for score in [f64::MAX, f64::MIN, 1e-300, f64::INFINITY, 0.5, -3f64, 0f64] {
+        let mut record = GameRecord::new(vec![1]);
+        record.moves.push(scored(score));
+
+        let text = write(&record).unwrap();
+
+        assert!(text.len() < 80, "{}", text);
+        assert_eq!(parse_record::<u8, u8>(&text).unwrap(), record);
+    }
+
+    let mut record = GameRecord::new(vec![1, 2]);
+    record.moves.push(scored(f64::MAX));
+    assert!(write(&record)
+        .unwrap()
+        .contains("{score=1.7976931348623157e308}"));
+}
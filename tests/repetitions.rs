@@ -0,0 +1,111 @@
+mod common;
+
+use gts::abstractions::play;
+use gts::abstractions::play_with_repetitions;
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::agents::AlphabetaAgent;
+
+use common::reward;
+use common::TicTacToe;
+
+/// A token passed back and forth between agents 1 and 2 with the only action, 0. The game
+/// never ends, and its two positions come back every other ply.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Pass {
+    turn: u8,
+}
+
+impl Environment<u8, u8> for Pass {
+    fn initial_state() -> Self {
+        Pass { turn: 1 }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        Box::new(std::iter::once(0))
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        *a == 0
+    }
+
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        None
+    }
+}
+
+/// Every position of 'Pass' is better for agent 1.
+fn favours_1(_: &Pass, agent_id: &u8) -> f64 {
+    if *agent_id == 1 {
+        0.5
+    } else {
+        -0.5
+    }
+}
+
+#[test]
+#[should_panic(expected = "at least twice")]
+fn repetitions_below_two_are_refused() {
+    let mut env = TicTacToe::initial_state();
+    let mut agent_1 = AlphabetaAgent::new(1, &reward, 2);
+    let mut agent_2 = AlphabetaAgent::new(2, &reward, 2);
+
+    play_with_repetitions(&mut env, &mut agent_1, &mut agent_2, 1);
+}
+
+#[test]
+fn repeated_positions_are_scored_as_draws() {
+    let env = Pass::initial_state();
+    let mut agent = AlphabetaAgent::new(1, &favours_1, 4);
+    let mut repeating = AlphabetaAgent::new(1, &favours_1, 4).with_repetitions();
+
+    agent.action(&env);
+    repeating.action(&env);
+
+    assert_eq!(agent.last_evaluation().map(|(value, _)| value), Some(0.5));
+    assert_eq!(
+        repeating.last_evaluation().map(|(value, _)| value),
+        Some(0f64)
+    );
+    assert!(repeating.stats().repetitions > 0);
+}
+
+#[test]
+fn agents_forget_the_positions_of_previous_games() {
+    let mut agent_1 = AlphabetaAgent::new(1, &reward, 4).with_repetitions();
+    let mut agent_2 = AlphabetaAgent::new(2, &reward, 4).with_repetitions();
+
+    let mut env = TicTacToe::initial_state();
+    play(&mut env, &mut agent_1, &mut agent_2);
+    assert!(env.is_terminal());
+
+    // Positions of the first game are repetitions until a new game starts.
+    agent_1.action(&TicTacToe::initial_state());
+    assert!(agent_1.stats().repetitions > 0);
+
+    agent_1.new_game();
+    agent_1.action(&TicTacToe::initial_state());
+    assert_eq!(agent_1.stats().repetitions, 0);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::record::parse_record;
+use gts::record::play_written;
+use gts::record::replay;
+use gts::record::resume;
+use gts::record::GameRecord;
+use gts::record::GameResult;
+use gts::record::Move;
+use gts::record::RecordError;
+use gts::record::RecordWriter;
+
+use common::First;
+use common::Last;
+use common::TicTacToe;
+
+/// The record of a game with 'actions' played in turn from the initial state, and 'result'.
+fn record(actions: &[u8], result: GameResult<u8>) -> GameRecord<u8, u8> {
+    let mut record = GameRecord::new(vec![1, 2]);
+    record.moves = actions
+        .iter()
+        .enumerate()
+        .map(|(ply, action)| Move {
+            agent_id: if ply % 2 == 0 { 1 } else { 2 },
+            action: *action,
+            time: None,
+            score: None,
+            depth: None,
+        })
+        .collect();
+    record.result = result;
+    record
+}
+
+#[test]
+fn replays_reach_the_recorded_result() {
+    let won = record(&[0, 3, 1, 4, 2], GameResult::Win(1));
+    let env = replay(&won, TicTacToe::initial_state()).unwrap();
+
+    assert_eq!(env.winner(), Some(1));
+    assert_eq!(
+        replay(
+            &record(&[0, 4], GameResult::Unfinished),
+            TicTacToe::initial_state()
+        ),
+        Ok(TicTacToe {
+            board: [1, 0, 0, 0, 2, 0, 0, 0, 0],
+            turn: 1,
+        })
+    );
+}
+
+#[test]
+fn replays_report_the_first_wrong_action() {
+    let start = TicTacToe::initial_state;
+
+    // The center is taken twice.
+    let taken = record(&[4, 0, 4], GameResult::Unfinished);
+    assert_eq!(
+        replay(&taken, start()),
+        Err(RecordError::InvalidAction { ply: 2 })
+    );
+
+    // Agent 2 plays twice in a row.
+    let mut out_of_turn = record(&[4, 0, 8], GameResult::Unfinished);
+    out_of_turn.moves[2].agent_id = 2;
+    assert_eq!(
+        replay(&out_of_turn, start()),
+        Err(RecordError::WrongTurn { ply: 2 })
+    );
+
+    // Agent 1 wins, but the record says that agent 2 did.
+    let misreported = record(&[0, 3, 1, 4, 2], GameResult::Win(2));
+    assert_eq!(
+        replay(&misreported, start()),
+        Err(RecordError::ResultMismatch)
+    );
+
+    // The game is over before the record says so.
+    let unfinished = record(&[0, 3, 1, 4, 2], GameResult::Unfinished);
+    assert_eq!(
+        replay(&unfinished, start()),
+        Err(RecordError::ResultMismatch)
+    );
+}
+
+#[test]
+fn resumed_games_are_played_to_the_end() {
+    let unfinished = record(&[4, 0], GameResult::Unfinished);
+
+    let (env, resumed) = resume(
+        unfinished.clone(),
+        TicTacToe::initial_state(),
+        &mut First(1),
+        &mut Last(2),
+    )
+    .unwrap();
+
+    assert!(env.is_terminal());
+    assert_eq!(resumed.moves[..2], unfinished.moves[..]);
+    assert_eq!(resumed.result, GameResult::of(&env));
+    assert_eq!(replay(&resumed, TicTacToe::initial_state()), Ok(env));
+
+    // Records that can't be replayed are not resumed.
+    let wrong = record(&[4, 4], GameResult::Unfinished);
+    let error = resume(
+        wrong,
+        TicTacToe::initial_state(),
+        &mut First(1),
+        &mut Last(2),
+    )
+    .err();
+    assert_eq!(error, Some(RecordError::InvalidAction { ply: 1 }));
+}
+
+#[test]
+fn written_games_are_read_back() {
+    let unfinished = record(&[4, 0], GameResult::Unfinished).with_metadata("Event", "Test");
+    let mut env = replay(&unfinished, TicTacToe::initial_state()).unwrap();
+    let mut writer = RecordWriter::new(Vec::new());
+
+    let played = play_written(
+        &mut env,
+        &mut First(1),
+        &mut Last(2),
+        unfinished,
+        &mut writer,
+    )
+    .unwrap();
+
+    let text = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(env.is_terminal());
+    assert_eq!(played.result, GameResult::of(&env));
+    assert_eq!(parse_record::<u8, u8>(&text).unwrap(), played);
+}
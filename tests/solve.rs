@@ -0,0 +1,92 @@
+mod common;
+
+use gts::abstractions::Environment;
+use gts::tree_search::solve;
+use gts::tree_search::solve_symmetric;
+
+use common::Chain;
+use common::TicTacToe;
+
+/// Returns the position reached by playing 'actions' from the initial one.
+fn position(actions: &[u8]) -> TicTacToe {
+    let mut env = TicTacToe::initial_state();
+    for action in actions {
+        assert!(env.update(action));
+    }
+    env
+}
+
+#[test]
+fn tic_tac_toe_is_a_draw() {
+    let env = TicTacToe::initial_state();
+
+    assert_eq!(solve(&env, &1).value(&env), Some(0f64));
+    assert_eq!(solve_symmetric(&env, &1).value(&env), Some(0f64));
+}
+
+#[test]
+fn tic_tac_toe_has_5478_positions() {
+    let solution = solve(&TicTacToe::initial_state(), &1);
+
+    assert_eq!(solution.len(), 5478);
+}
+
+#[test]
+fn tic_tac_toe_has_765_positions_up_to_symmetry() {
+    let solution = solve_symmetric(&TicTacToe::initial_state(), &1);
+
+    assert_eq!(solution.len(), 765);
+}
+
+#[test]
+fn symmetric_solutions_agree_with_full_ones() {
+    let full = solve(&TicTacToe::initial_state(), &1);
+    let symmetric = solve_symmetric(&TicTacToe::initial_state(), &1);
+
+    for (env, solved) in full.iter() {
+        assert_eq!(symmetric.value(env), Some(solved.value));
+        if let Some(action) = symmetric.action(env) {
+            assert_eq!(full.value(&env.what_if(&action)), Some(solved.value));
+        }
+    }
+}
+
+#[test]
+fn winning_actions_are_found() {
+    // Agent 1 holds 0 and 1, and wins by playing 2.
+    let env = position(&[0, 3, 1, 4]);
+    let solution = solve(&env, &1);
+
+    assert_eq!(solution.value(&env), Some(f64::MAX));
+    assert_eq!(solution.action(&env), Some(2));
+}
+
+#[test]
+fn positions_without_actions_are_scored_like_terminal_positions() {
+    // A full board without a line.
+    let drawn = position(&[0, 1, 2, 4, 3, 5, 7, 6, 8]);
+    assert!(drawn.valid_actions().next().is_none());
+
+    let solution = solve(&drawn, &1);
+    assert_eq!(solution.len(), 1);
+    assert_eq!(solution.value(&drawn), Some(0f64));
+    assert_eq!(solution.action(&drawn), None);
+
+    // Agent 1 won with the line 0, 1, 2.
+    let won = position(&[0, 3, 1, 4, 2]);
+    assert!(won.valid_actions().next().is_none());
+
+    let solution = solve_symmetric(&won, &2);
+    assert_eq!(solution.value(&won), Some(f64::MIN));
+    assert_eq!(solution.action(&won), None);
+}
+
+#[test]
+fn long_games_are_solved() {
+    let env = Chain::new(100_000);
+    let solution = solve(&env, &1);
+
+    assert_eq!(solution.len(), 100_001);
+    assert_eq!(solution.value(&env), Some(f64::MAX));
+    assert_eq!(solution.action(&env), Some(0));
+}
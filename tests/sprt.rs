@@ -0,0 +1,75 @@
+mod common;
+
+use gts::abstractions::Agent;
+use gts::agents::AlphabetaAgent;
+use gts::runner::Sprt;
+use gts::runner::SprtStatus;
+use gts::runner::Wdl;
+
+use common::reward;
+use common::First;
+use common::TicTacToe;
+
+const MAX_GAMES: usize = 1000;
+
+/// Never finds an action, so that its games stop unfinished.
+struct Resign(u8);
+
+impl Agent<u8, u8, TicTacToe> for Resign {
+    fn identity(&self) -> u8 {
+        self.0
+    }
+
+    fn action(&mut self, _: &TicTacToe) -> Option<u8> {
+        None
+    }
+}
+
+/// Plays tic-tac-toe without mistakes, and wins against 'First' with either side.
+fn perfect(agent_id: u8) -> AlphabetaAgent<'static, u8, u8, TicTacToe> {
+    AlphabetaAgent::new(agent_id, &reward, 8)
+}
+
+#[test]
+fn bounds_follow_the_error_rates() {
+    let (lower, upper) = Sprt::new(0f64, 5f64, 0.05, 0.05).bounds();
+
+    assert!((lower + 2.944).abs() < 1e-3, "{}", lower);
+    assert!((upper - 2.944).abs() < 1e-3, "{}", upper);
+}
+
+#[test]
+fn candidates_winning_every_game_are_accepted_early() {
+    let mut sprt = Sprt::new(0f64, 5f64, 0.05, 0.05);
+    let mut games = 0;
+
+    let status = sprt.run((1, 2), First, perfect, MAX_GAMES, |_| games += 1);
+
+    assert_eq!(status, SprtStatus::AcceptH1);
+    assert_eq!(sprt.wdl().wins, games);
+    assert!((games as usize) < MAX_GAMES);
+}
+
+#[test]
+fn candidates_losing_every_game_are_rejected_early() {
+    let mut sprt = Sprt::new(0f64, 5f64, 0.05, 0.05);
+    let mut games = 0;
+
+    let status = sprt.run((1, 2), perfect, First, MAX_GAMES, |_| games += 1);
+
+    assert_eq!(status, SprtStatus::AcceptH0);
+    assert_eq!(sprt.wdl().losses, games);
+    assert!((games as usize) < MAX_GAMES);
+}
+
+#[test]
+fn unfinished_games_are_left_out() {
+    let mut sprt = Sprt::new(0f64, 5f64, 0.05, 0.05);
+    let mut games = 0;
+
+    let status = sprt.run((1, 2), First, Resign, 10, |_| games += 1);
+
+    assert_eq!(status, SprtStatus::Continue);
+    assert_eq!(games, 10);
+    assert_eq!(sprt.wdl(), Wdl::default());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use std::collections::HashMap;
+
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::abstractions::Symmetric;
+use gts::agents::AlphabetaAgent;
+use gts::cache::canonical::Canonicalizer;
+use gts::tree_search::mcts;
+use gts::tree_search::solve;
+use gts::tree_search::uct;
+use gts::tree_search::MctsCache;
+use gts::tree_search::SymmetricCache;
+
+use common::positions;
+use common::reward;
+use common::TicTacToe;
+
+/// Every position of tic-tac-toe that is not over.
+fn playable() -> Vec<TicTacToe> {
+    let solution = solve(&TicTacToe::initial_state(), &1);
+    let mut playable: Vec<TicTacToe> = solution
+        .iter()
+        .map(|(env, _)| *env)
+        .filter(|env| !env.is_terminal())
+        .collect();
+    playable.sort();
+    playable
+}
+
+#[test]
+fn actions_mapped_back_are_valid_in_the_original_position() {
+    let canonicalizer: Canonicalizer<u8, TicTacToe> = Canonicalizer::new();
+
+    for env in playable() {
+        let (canonical, symmetry) = canonicalizer.canonical(&env);
+
+        for action in canonical.valid_actions() {
+            let back = canonicalizer.action_back(&canonical, &action, symmetry);
+
+            assert!(env.is_valid(&back), "{} in {:?}", back, env);
+            assert_eq!(
+                env.what_if(&back).canonical().0,
+                canonical.what_if(&action).canonical().0
+            );
+        }
+    }
+}
+
+#[test]
+fn agents_with_symmetries_play_as_well_as_without() {
+    let solution = solve(&TicTacToe::initial_state(), &1);
+    let mut transformed = 0;
+
+    for env in positions().into_iter().chain(playable()) {
+        let mut agent = AlphabetaAgent::new(env.turn(), &reward, 8);
+        let mut symmetric = AlphabetaAgent::new(env.turn(), &reward, 8).with_symmetries();
+
+        agent.action(&env);
+        let action = symmetric.action(&env).unwrap();
+
+        assert_eq!(
+            symmetric.last_evaluation(),
+            agent.last_evaluation(),
+            "{:?}",
+            env
+        );
+        assert!(env.is_valid(&action), "{} in {:?}", action, env);
+        assert_eq!(
+            solution.value(&env.what_if(&action)),
+            solution.value(&env),
+            "{} in {:?}",
+            action,
+            env
+        );
+
+        if env.canonical().1 != 0 {
+            transformed += 1;
+        }
+    }
+
+    assert!(transformed > 0);
+}
+
+#[test]
+fn symmetric_caches_share_the_values_of_symmetric_positions() {
+    let env = TicTacToe::initial_state();
+    let mut plain = HashMap::new();
+    let mut symmetric = SymmetricCache::new(HashMap::new());
+
+    for _ in 0..500 {
+        mcts(&env, &1, &|e, a, c| uct(e, a, c, 1.4), &mut plain);
+        mcts(&env, &1, &|e, a, c| uct(e, a, c, 1.4), &mut symmetric);
+    }
+
+    assert!(symmetric.len() < plain.len());
+
+    // The searches visit different positions, so only the ones found by both are compared.
+    let mut shared = 0;
+    for env in plain.keys() {
+        if let Some(stored) = symmetric.get(env) {
+            for symmetry in 0..env.symmetries() {
+                assert_eq!(symmetric.get(&env.transform(symmetry)), Some(stored));
+            }
+            shared += 1;
+        }
+    }
+    assert!(shared > 0);
+}
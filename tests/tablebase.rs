@@ -0,0 +1,202 @@
+use std::io::ErrorKind;
+
+use gts::abstractions::Agent;
+use gts::abstractions::Environment;
+use gts::abstractions::Predecessors;
+use gts::agents::AlphabetaAgent;
+use gts::tablebase::retrograde;
+use gts::tablebase::retrograde_with_predecessors;
+use gts::tablebase::Outcome;
+use gts::tablebase::Tablebase;
+use gts::tree_search::Negamax;
+use gts::tree_search::StatelessNode;
+
+const STONES: u8 = 10;
+
+/// Agents 1 and 2 take one or two stones in turn, and the one taking the last stone wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Nim {
+    stones: u8,
+    turn: u8,
+}
+
+impl Nim {
+    /// Every position with up to 'STONES' stones.
+    fn positions() -> Vec<Nim> {
+        (0..=STONES)
+            .flat_map(|stones| [1, 2].map(|turn| Nim { stones, turn }))
+            .collect()
+    }
+}
+
+impl Environment<u8, u8> for Nim {
+    fn initial_state() -> Self {
+        Nim {
+            stones: STONES,
+            turn: 1,
+        }
+    }
+
+    fn update(&mut self, a: &u8) -> bool {
+        if !self.is_valid(a) {
+            return false;
+        }
+        self.stones -= a;
+        self.turn = 3 - self.turn;
+        true
+    }
+
+    fn what_if(&self, a: &u8) -> Self {
+        let mut env = *self;
+        env.update(a);
+        env
+    }
+
+    fn valid_actions(&self) -> Box<dyn Iterator<Item = u8>> {
+        let stones = self.stones;
+        Box::new((1..=2).filter(move |a| *a <= stones))
+    }
+
+    fn is_valid(&self, a: &u8) -> bool {
+        (1..=2).contains(a) && *a <= self.stones
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.stones == 0
+    }
+
+    fn turn(&self) -> u8 {
+        self.turn
+    }
+
+    fn winner(&self) -> Option<u8> {
+        if self.is_terminal() {
+            Some(3 - self.turn)
+        } else {
+            None
+        }
+    }
+}
+
+/// Lists every predecessor twice.
+impl Predecessors<u8, u8> for Nim {
+    fn predecessors(&self) -> Vec<Self> {
+        (1..=2)
+            .map(|a| Nim {
+                stones: self.stones + a,
+                turn: 3 - self.turn,
+            })
+            .filter(|env| env.stones <= STONES)
+            .flat_map(|env| [env, env])
+            .collect()
+    }
+}
+
+#[test]
+fn positions_with_a_multiple_of_three_stones_are_lost() {
+    let tablebase = retrograde(Nim::positions());
+
+    for env in Nim::positions() {
+        let expected = match env.stones % 3 {
+            0 => Outcome::Loss(usize::from(env.stones / 3 * 2)),
+            _ => Outcome::Win(usize::from(env.stones / 3 * 2 + 1)),
+        };
+        assert_eq!(tablebase.probe(&env), Some(expected), "{:?}", env);
+    }
+}
+
+#[test]
+fn repeated_predecessors_count_once() {
+    let forwards = retrograde(Nim::positions());
+    let backwards = retrograde_with_predecessors(Nim::positions());
+
+    for env in Nim::positions() {
+        assert_eq!(backwards.probe(&env), forwards.probe(&env), "{:?}", env);
+    }
+}
+
+/// Nothing is known about positions before the end, so that only the tablebase tells them apart.
+fn no_reward(_: &Nim, _: &u8) -> f64 {
+    0f64
+}
+
+#[test]
+fn alphabeta_agents_with_a_probe_convert_won_positions() {
+    let tablebase = retrograde(Nim::positions());
+    let probe = |env: &Nim| tablebase.probe(env);
+
+    for stones in (1..=STONES).filter(|stones| stones % 3 != 0) {
+        let mut env = Nim { stones, turn: 1 };
+        let mut agents = [
+            AlphabetaAgent::new(1, &no_reward, 2).with_probe(&probe),
+            AlphabetaAgent::new(2, &no_reward, 2).with_probe(&probe),
+        ];
+
+        while !env.is_terminal() {
+            let agent = &mut agents[usize::from(env.turn() - 1)];
+            let action = agent.action(&env).unwrap();
+            assert!(env.update(&action));
+        }
+
+        assert_eq!(env.winner(), Some(1), "from {} stones", stones);
+    }
+}
+
+#[test]
+fn searches_with_a_probe_prefer_short_wins() {
+    let tablebase = retrograde(Nim::positions());
+    let probe = |env: &Nim| tablebase.probe(env);
+    let mut wins: Vec<(usize, f64)> = Vec::new();
+
+    for env in Nim::positions().into_iter().filter(|env| env.turn == 1) {
+        if let Some(Outcome::Win(distance)) = tablebase.probe(&env) {
+            let (value, _) = Negamax::new(&1, &no_reward)
+                .with_probe(Some(&probe))
+                .search(
+                    &mut StatelessNode::new(&env),
+                    2,
+                    f64::NEG_INFINITY,
+                    f64::INFINITY,
+                );
+            wins.push((distance, value));
+        }
+    }
+
+    for (distance, value) in wins.iter() {
+        for (other_distance, other_value) in wins.iter() {
+            if distance < other_distance {
+                assert!(value > other_value, "{} and {}", distance, other_distance);
+            }
+        }
+    }
+}
+
+#[test]
+fn tablebases_are_read_back() {
+    let tablebase = retrograde(Nim::positions());
+    let mut bytes = Vec::new();
+    tablebase.write_to(&mut bytes).unwrap();
+
+    let read = Tablebase::read_from(&mut bytes.as_slice(), Nim::positions()).unwrap();
+
+    assert_eq!(read.len(), tablebase.len());
+    for env in Nim::positions() {
+        assert_eq!(read.probe(&env), tablebase.probe(&env));
+    }
+}
+
+#[test]
+fn lengths_not_matching_the_positions_are_refused() {
+    let tablebase = retrograde(Nim::positions());
+    let mut bytes = Vec::new();
+    tablebase.write_to(&mut bytes).unwrap();
+
+    // The number of positions follows the magic number and the version.
+    bytes[5..13].copy_from_slice(&u64::MAX.to_le_bytes());
+
+    let error = Tablebase::read_from(&mut bytes.as_slice(), Nim::positions()).err();
+    assert_eq!(
+        error.map(|error| error.kind()),
+        Some(ErrorKind::InvalidData)
+    );
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::Instant;
+
+use gts::record::GameResult;
+use gts::runner::Pairings;
+use gts::runner::Tournament;
+use gts::runner::TournamentResult;
+use gts::runner::Wdl;
+
+use common::First;
+use common::Last;
+use common::TicTacToe;
+
+/// A tournament between 'entrants' entrants named after their index, playing 'First' at even
+/// indices and 'Last' at odd ones.
+fn field<'a>(entrants: usize) -> Tournament<'a, u8, u8, TicTacToe> {
+    let mut tournament = Tournament::new((1, 2));
+    for entrant in 0..entrants {
+        let name = entrant.to_string();
+        tournament = if entrant % 2 == 0 {
+            tournament.with_entrant(&name, First)
+        } else {
+            tournament.with_entrant(&name, Last)
+        };
+    }
+    tournament
+}
+
+/// The players, actions and result of a game.
+type Summary = ((usize, usize), Vec<u8>, GameResult<u8>);
+
+/// Summarizes every game, leaving out the times of the actions.
+fn summary(result: &TournamentResult<u8, u8>) -> Vec<Summary> {
+    result
+        .games
+        .iter()
+        .map(|game| {
+            let actions = game.record.moves.iter().map(|m| m.action).collect();
+            (game.players, actions, game.record.result)
+        })
+        .collect()
+}
+
+/// Returns, for every entrant, how many more games it played on 'sides.0' than on 'sides.1'.
+fn balance(result: &TournamentResult<u8, u8>) -> Vec<i64> {
+    let mut balance = vec![0i64; result.names.len()];
+    for game in result.games.iter() {
+        balance[game.players.0] += 1;
+        balance[game.players.1] -= 1;
+    }
+    balance
+}
+
+#[test]
+fn round_robins_pair_every_entrant_with_every_other() {
+    let result = field(4).run();
+
+    assert_eq!(result.games.len(), 4 * 3 / 2 * 2);
+    let mut meetings: HashMap<(usize, usize), usize> = HashMap::new();
+    for game in result.games.iter() {
+        let (i, j) = game.players;
+        *meetings.entry((i.min(j), i.max(j))).or_insert(0) += 1;
+    }
+    assert_eq!(meetings.len(), 4 * 3 / 2);
+    assert!(meetings.values().all(|count| *count == 2));
+    assert!(balance(&result).iter().all(|b| *b == 0));
+}
+
+#[test]
+fn gauntlets_pair_the_first_entrant_with_every_other() {
+    let result = field(4).with_pairings(Pairings::Gauntlet).run();
+
+    assert_eq!(result.games.len(), 3 * 2);
+    for game in result.games.iter() {
+        let (i, j) = game.players;
+        assert!(i == 0 || j == 0);
+    }
+}
+
+#[test]
+fn sides_are_balanced_over_meetings_of_one_game() {
+    for entrants in 2..=7 {
+        let result = field(entrants).with_games(1).run();
+
+        assert!(
+            balance(&result).iter().all(|b| b.abs() <= 1),
+            "{:?} with {} entrants",
+            balance(&result),
+            entrants
+        );
+    }
+}
+
+#[test]
+fn swiss_rounds_give_byes_to_different_entrants() {
+    let rounds = 3;
+    let result = field(5).with_pairings(Pairings::Swiss { rounds }).run();
+
+    assert_eq!(result.games.len(), rounds * 2 * 2);
+    assert_eq!(result.byes.len(), rounds);
+    let byes: HashSet<usize> = result.byes.iter().copied().collect();
+    assert_eq!(byes.len(), rounds);
+    for game in result.games.iter() {
+        assert_ne!(game.players.0, game.players.1);
+    }
+
+    // Every game gives out one point, and every bye those of winning a meeting.
+    let points: f64 = result.points().iter().sum();
+    assert_eq!(points, (result.games.len() + rounds * 2) as f64);
+}
+
+#[test]
+fn crosstables_count_the_games_of_every_pairing() {
+    // The agent playing first wins every game between 'First' and 'Last', whichever plays it.
+    let result = field(3).run();
+    let crosstable = result.crosstable();
+
+    assert!(summary(&result)
+        .iter()
+        .all(|(_, _, result)| *result == GameResult::Win(1)));
+    for i in 0..3 {
+        for j in (0..3).filter(|j| *j != i) {
+            let one_each = Wdl {
+                wins: 1,
+                draws: 0,
+                losses: 1,
+            };
+            assert_eq!(crosstable.get(i, j), one_each);
+        }
+        assert_eq!(crosstable.total(i).points(), result.points()[i]);
+    }
+}
+
+#[test]
+fn games_on_several_threads_match_games_on_one() {
+    let pairings = [
+        Pairings::RoundRobin,
+        Pairings::Gauntlet,
+        Pairings::Swiss { rounds: 3 },
+    ];
+
+    for pairings in pairings {
+        let single = field(5).with_pairings(pairings).run();
+        let threaded = field(5).with_pairings(pairings).with_threads(4).run();
+
+        assert_eq!(summary(&threaded), summary(&single), "{:?}", pairings);
+        assert_eq!(threaded.byes, single.byes);
+    }
+}
+
+#[test]
+fn swiss_rounds_without_rematch_free_pairings_end_quickly() {
+    // More rounds than opponents, so that later rounds can't avoid rematches.
+    let entrants = 24;
+    let rounds = 30;
+    let mut tournament = Tournament::new((1, 2))
+        .with_pairings(Pairings::Swiss { rounds })
+        .with_games(1);
+    for entrant in 0..entrants {
+        tournament = tournament.with_entrant(&entrant.to_string(), First);
+    }
+
+    let start = Instant::now();
+    let result = tournament.run();
+
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert_eq!(result.games.len(), rounds * entrants / 2);
+
+    // Rematches only come once every opponent was met.
+    let mut meetings: HashMap<(usize, usize), usize> = HashMap::new();
+    for (index, game) in result.games.iter().enumerate() {
+        let (i, j) = game.players;
+        *meetings.entry((i.min(j), i.max(j))).or_insert(0) += 1;
+        if index < (entrants - 1) * entrants / 2 / 2 {
+            assert!(meetings.values().all(|count| *count == 1));
+        }
+    }
+    assert_eq!(meetings.len(), entrants * (entrants - 1) / 2);
+}
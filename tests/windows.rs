@@ -0,0 +1,125 @@
+mod common;
+
+use std::cell::RefCell;
+
+use gts::cache::arena::ArenaMM;
+use gts::tree_search::ArenaNode;
+use gts::tree_search::Negamax;
+use gts::tree_search::SearchStats;
+
+use common::positions;
+use common::reward;
+use common::TicTacToe;
+
+/// Deep enough to search every position to the end of the game.
+const DEPTH: usize = 9;
+
+/// Searches 'env' on a fresh arena with 'search' and 'negamax', and returns its result
+/// together with the counters of the search.
+fn on_arena<'a>(
+    env: TicTacToe,
+    mut negamax: Negamax<'a, u8, u8, TicTacToe>,
+    search: impl FnOnce(
+        &mut Negamax<'a, u8, u8, TicTacToe>,
+        &mut ArenaNode<TicTacToe, u8>,
+    ) -> (f64, Option<u8>),
+) -> ((f64, Option<u8>), SearchStats) {
+    let arena: RefCell<ArenaMM<TicTacToe, u8>> = RefCell::new(ArenaMM::new());
+    let root = arena.borrow_mut().set_root(env);
+    let found = search(&mut negamax, &mut ArenaNode::new(&arena, root));
+    (found, *negamax.stats())
+}
+
+/// Searches 'env' for 'agent_id' with a full window.
+fn alphabeta(env: TicTacToe, agent_id: u8) -> (f64, Option<u8>) {
+    on_arena(env, Negamax::new(&agent_id, &reward), |negamax, node| {
+        negamax.search(node, DEPTH, f64::NEG_INFINITY, f64::INFINITY)
+    })
+    .0
+}
+
+#[test]
+fn mtdf_agrees_with_alphabeta_for_any_first_guess() {
+    for env in positions() {
+        for agent_id in [1, 2] {
+            let expected = alphabeta(env, agent_id);
+
+            for first_guess in [-1.0, -0.5, 0.0, 0.5, 1.0] {
+                let found = on_arena(env, Negamax::new(&agent_id, &reward), |negamax, node| {
+                    negamax.mtdf(node, DEPTH, first_guess)
+                })
+                .0;
+
+                assert_eq!(
+                    found, expected,
+                    "{:?} for {} from {}",
+                    env, agent_id, first_guess
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn pvs_agrees_with_alphabeta() {
+    let mut researches = 0;
+
+    for env in positions() {
+        for agent_id in [1, 2] {
+            let negamax = Negamax::new(&agent_id, &reward).with_pvs(true);
+            let (found, stats) = on_arena(env, negamax, |negamax, node| {
+                negamax.search(node, DEPTH, f64::NEG_INFINITY, f64::INFINITY)
+            });
+
+            assert_eq!(
+                found,
+                alphabeta(env, agent_id),
+                "{:?} for {}",
+                env,
+                agent_id
+            );
+            researches += stats.pvs_researches;
+        }
+    }
+
+    assert!(researches > 0);
+}
+
+#[test]
+fn aspiration_agrees_with_alphabeta_when_the_window_fails() {
+    let mut fails_low = 0;
+    let mut fails_high = 0;
+
+    for env in positions() {
+        for agent_id in [1, 2] {
+            let expected = alphabeta(env, agent_id);
+
+            // Wins and losses are scored beyond any window around these estimates, so only
+            // draws are found without failing.
+            for previous in [-10.0, 0.0, 10.0] {
+                let (found, stats) =
+                    on_arena(env, Negamax::new(&agent_id, &reward), |negamax, node| {
+                        negamax.aspiration(node, DEPTH, previous, 0.5)
+                    });
+
+                assert_eq!(
+                    found, expected,
+                    "{:?} for {} from {}",
+                    env, agent_id, previous
+                );
+
+                if expected.0 <= previous - 0.5 {
+                    fails_low += 1;
+                } else if expected.0 >= previous + 0.5 {
+                    fails_high += 1;
+                } else {
+                    assert_eq!(stats.aspiration_researches, 0);
+                    continue;
+                }
+                assert_eq!(stats.aspiration_researches, 1);
+            }
+        }
+    }
+
+    assert!(fails_low > 0 && fails_high > 0);
+}
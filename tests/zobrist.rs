@@ -0,0 +1,99 @@
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gts::abstractions::Environment;
+use gts::abstractions::Zobrist;
+use gts::cache::arena::ArenaMM;
+use gts::cache::node::Node;
+use gts::cache::node::NodeRRMM;
+use gts::tree_search::alphabeta;
+use gts::tree_search::solve;
+use gts::tree_search::ArenaNode;
+use gts::tree_search::Negamax;
+
+use common::reward;
+use common::TicTacToe;
+
+/// A poor key on purpose: the number of cells played, shared by many positions.
+impl Zobrist<u8, u8> for TicTacToe {
+    fn zobrist(&self) -> u64 {
+        self.board.iter().filter(|cell| **cell != 0).count() as u64
+    }
+}
+
+/// Every position reachable in tic-tac-toe.
+fn positions() -> Vec<TicTacToe> {
+    let solution = solve(&TicTacToe::initial_state(), &1);
+    solution.iter().map(|(env, _)| *env).collect()
+}
+
+#[test]
+fn arenas_keyed_on_zobrist_keys_tell_colliding_positions_apart() {
+    let mut arena: ArenaMM<TicTacToe, u8> = ArenaMM::new().with_zobrist();
+
+    let positions = positions();
+    let ids: Vec<_> = positions.iter().map(|env| arena.insert(*env)).collect();
+
+    assert_eq!(arena.len(), positions.len());
+    for (env, id) in positions.iter().zip(ids) {
+        assert_eq!(arena.get(env), Some(id));
+        assert_eq!(arena.environment(id), env);
+    }
+}
+
+#[test]
+fn arenas_keep_their_nodes_when_switching_to_zobrist_keys() {
+    let mut arena: ArenaMM<TicTacToe, u8> = ArenaMM::new();
+    let positions = positions();
+    let ids: Vec<_> = positions.iter().map(|env| arena.insert(*env)).collect();
+
+    let arena = arena.with_zobrist();
+
+    for (env, id) in positions.iter().zip(ids) {
+        assert_eq!(arena.get(env), Some(id));
+    }
+}
+
+#[test]
+fn searches_agree_with_and_without_zobrist_keys() {
+    let env = TicTacToe::initial_state();
+    let search = |arena: ArenaMM<TicTacToe, u8>| {
+        let arena = RefCell::new(arena);
+        let root = arena.borrow_mut().set_root(env);
+        let found = Negamax::new(&1, &reward).search(
+            &mut ArenaNode::new(&arena, root),
+            9,
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+        );
+        let len = arena.borrow().len();
+        (found, len)
+    };
+
+    assert_eq!(
+        search(ArenaMM::new().with_zobrist()),
+        search(ArenaMM::new())
+    );
+}
+
+#[test]
+fn node_caches_keyed_on_zobrist_keys_tell_colliding_positions_apart() {
+    let env = Rc::new(TicTacToe::initial_state());
+    let search = |node: NodeRRMM<TicTacToe, u8, u8>| {
+        let value = alphabeta(&node, &1, &reward, 9, f64::NEG_INFINITY, f64::INFINITY);
+        let len = node.borrow().cache_len();
+        (value, len)
+    };
+
+    let hashed = Rc::new(RefCell::new(Node::new(&env)));
+    let keyed = Rc::new(RefCell::new(Node::new(&env).with_zobrist()));
+
+    assert_eq!(search(keyed.clone()), search(hashed));
+    for position in positions() {
+        if let Some(node) = keyed.borrow().cache_get(&position) {
+            assert_eq!(**node.borrow().environment(), position);
+        }
+    }
+}